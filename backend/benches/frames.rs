@@ -0,0 +1,39 @@
+//! Emulation speed, in frames per second, of a few ROMs exercising different mappers
+//! Run with `cargo bench`, optionally passing a frame count: `cargo bench -- 1200`
+//! With `cargo bench -- lockstep`, the PPU and APU catch up after every master cycle instead of when observed
+
+use std::time::Instant;
+use wasm_nes::Emulator;
+
+const ROMS: [(&str, &[u8]); 3] = [
+    ("nestest (NROM)", include_bytes!("../tests/roms/cpu/nestest/nestest.nes")),
+    ("holy mapperel (MMC1)", include_bytes!("../tests/roms/mapper/holy-mapperel-bin-0.02/testroms/M1_P128K_C128K.nes")),
+    ("mmc3_test (MMC3)", include_bytes!("../tests/roms/mapper/mmc3_test/4-scanline_timing.nes")),
+];
+
+fn main () {
+    let frames = std::env::args()
+        .filter_map(|argument| argument.parse().ok())
+        .next()
+        .unwrap_or(600);
+    let lockstep = std::env::args().any(|argument| argument == "lockstep");
+
+    for (name, rom) in ROMS.iter() {
+        let mut nes = Emulator::new(rom.to_vec(), 48_000.0).unwrap();
+        let start = Instant::now();
+
+        for _ in 0..frames {
+            if lockstep {
+                for _ in 0..nes.region().scanlines() {
+                    nes.cycle_until_scanline();
+                }
+            } else {
+                nes.cycle_until_frame();
+            }
+            nes.get_audio();
+        }
+
+        let elapsed = start.elapsed().as_secs_f64();
+        println!("{:<24} {} frames in {:.2}s = {:.1} fps", name, frames, elapsed, frames as f64 / elapsed);
+    }
+}
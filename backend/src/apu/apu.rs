@@ -1,9 +1,16 @@
 use crate::{
-    apu::Pulse,
-    cpu::{Cpu, /* Interrupt */},
-    clock::ClockDivider,
+    apu::{Pulse, Triangle, Noise, Dmc, NOISE_PERIOD_NTSC, NOISE_PERIOD_PAL, DMC_RATE_NTSC, DMC_RATE_PAL},
+    bus::{InterruptLines, IrqSource},
+    clock::{ClockDivider, ClockResampler, Region},
 };
 
+/**
+ * Frame counter steps, in CPU cycles: quarter, half, three quarters and end of the four-step and five-step sequences
+ * https://wiki.nesdev.org/w/index.php/APU_Frame_Counter
+ */
+const FRAME_STEPS_NTSC: [usize; 5] = [7457, 14913, 22371, 29829, 37281];
+const FRAME_STEPS_PAL: [usize; 5] = [8313, 16627, 24939, 33253, 41565];
+
 enum StatusFlag {
     DMCInterrupt    = 0b1000_0000,
     FrameInterrupt  = 0b0100_0000,
@@ -14,7 +21,7 @@ enum StatusFlag {
     Square1         = 0b0000_0001,
 }
 
-#[derive(Clone, PartialEq)]
+#[derive(Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 enum FrameCounterMode {
     FourStep    = 0,
     FiveStep    = 1,
@@ -23,90 +30,168 @@ enum FrameCounterMode {
 /**
  * https://wiki.nesdev.org/w/index.php/APU
  */
+#[derive(serde::Serialize, serde::Deserialize)]
 pub struct Apu {
     status: u8,
     mode: FrameCounterMode,
     irq_inhibit: bool,
     square_1: Pulse,
     square_2: Pulse,
-    // triangle_1,
-    // noise_1,
-    // sample_1,
-    buffer: Vec<f32>,
+    triangle_1: Triangle,
+    noise_1: Noise,
+    sample_1: Dmc,
+    #[serde(skip)]
+    buffer: Vec<f32>, // Samples not yet flushed are not part of the machine state
     frame: usize,
+    frame_steps: [usize; 5],
+    frame_reset: u8,
     pub clock: ClockDivider,
-    pub clock_sample: ClockDivider,
+    pub clock_sample: ClockResampler,
 }
 
 impl Apu {
-    pub fn new (sample_rate: f64) -> Self {
+    pub fn new (sample_rate: f64, region: Region) -> Self {
+        // Dendy keeps the NTSC APU periods
+        let (noise_periods, dmc_rates, frame_steps) = match region {
+            Region::PAL => (NOISE_PERIOD_PAL, DMC_RATE_PAL, FRAME_STEPS_PAL),
+            Region::NTSC | Region::Dendy => (NOISE_PERIOD_NTSC, DMC_RATE_NTSC, FRAME_STEPS_NTSC),
+        };
+
         Self {
             status: 0,
-            mode: FrameCounterMode::FiveStep,
+            mode: FrameCounterMode::FourStep,
             irq_inhibit: false,
             square_1: Pulse::new(1),
             square_2: Pulse::new(2),
+            triangle_1: Triangle::default(),
+            noise_1: Noise::new(noise_periods),
+            sample_1: Dmc::new(dmc_rates),
             buffer: vec![],
             frame: 0,
-            clock: ClockDivider::new(crate::clock::CLOCK_CPU_NTSC),
-            clock_sample: ClockDivider::new(sample_rate),
+            frame_steps,
+            frame_reset: 0,
+            clock: ClockDivider::new(region.cpu_divider()),
+            clock_sample: ClockResampler::new(sample_rate, region.master_clock()),
         }
     }
 
-    pub fn tick (&mut self, time: f64, cpu: &mut Cpu) {
-        if self.clock.tick(time) {
-            self.cycle(cpu);
+    pub fn tick (&mut self, interrupts: &mut InterruptLines) {
+        if self.clock.tick() {
+            self.cycle(interrupts);
         }
 
-        if self.clock_sample.tick(time) {
+        if self.clock_sample.tick() {
             self.sample();
         }
     }
 
-    pub fn cycle (&mut self, cpu: &mut Cpu) {
+    /**
+     * Count master clock cycles during which the APU doesn't cycle, only taking samples
+     */
+    pub fn skip (&mut self, master_cycles: u32) {
+        self.clock.advance(master_cycles);
+
+        let samples = self.clock_sample.advance(master_cycles);
+        if samples > 0 {
+            let sample = self.mix();
+            self.buffer.resize(self.buffer.len() + samples, sample);
+        }
+    }
+
+    /**
+     * Run master clock cycles, skipping straight to each APU cycle
+     */
+    pub fn run (&mut self, master_cycles: u32, interrupts: &mut InterruptLines) {
+        let mut remaining = master_cycles;
+
+        while remaining > 0 {
+            let idle = (self.clock.master_cycles_until(1) - 1).min(remaining as u64 - 1) as u32;
+
+            self.skip(idle);
+            self.tick(interrupts);
+            remaining -= idle + 1;
+        }
+    }
+
+    /**
+     * Cycles that can run before one the CPU could observe without accessing APU registers: the frame interrupt,
+     * and the DMC emptying its sample buffer, which requests a DMA fetch. At most a five-step sequence.
+     */
+    pub fn quiet_cycles (&self) -> usize {
+        let [.., four_step_end, five_step_end] = self.frame_steps;
+
+        let frame_interrupt = if self.mode == FrameCounterMode::FourStep && !self.irq_inhibit {
+            (four_step_end - 1).saturating_sub(self.frame + 1)
+        } else {
+            five_step_end
+        };
+        // A pending sequence restart moves the frame interrupt
+        let frame_reset = match self.frame_reset {
+            0 => usize::MAX,
+            cycles => cycles as usize - 1,
+        };
+
+        frame_interrupt.min(frame_reset).min(self.sample_1.quiet_cycles())
+    }
+
+    pub fn cycle (&mut self, interrupts: &mut InterruptLines) {
+        self.triangle_1.cycle_timer();
+        self.noise_1.cycle_timer();
+        self.sample_1.cycle_timer();
+
         if self.clock.cycles % 2 == 0 {
             self.square_1.cycle_timer();
             self.square_2.cycle_timer();
-            self.frame += 1;
+        }
+
+        if self.frame_reset > 0 {
+            self.frame_reset -= 1;
+
+            if self.frame_reset == 0 {
+                if self.mode == FrameCounterMode::FiveStep {
+                    self.cycle_quarter_frame();
+                    self.cycle_half_frame();
+                }
 
-            self.cycle_frame(cpu);
+                self.frame = 0;
+            }
         }
+
+        self.frame += 1;
+        self.cycle_frame();
+
+        // Both flags hold the IRQ line until acknowledged
+        interrupts.set_irq(IrqSource::FrameCounter, (self.status & StatusFlag::FrameInterrupt as u8) > 0);
+        interrupts.set_irq(IrqSource::Dmc, self.sample_1.interrupt);
     }
 
     /**
+     * Steps are expressed in CPU cycles since the sequence last restarted
      * https://wiki.nesdev.org/w/index.php/APU_Frame_Counter
      */
-    pub fn cycle_frame (&mut self, _cpu: &mut Cpu) {
+    pub fn cycle_frame (&mut self) {
+        let [quarter, half, three_quarters, four_step_end, five_step_end] = self.frame_steps;
+
         match self.mode {
             FrameCounterMode::FourStep => {
                 match self.frame {
-                    3729 => {
-                        self.square_1.cycle_envelope();
-                        self.square_2.cycle_envelope();
+                    frame if frame == quarter || frame == three_quarters => {
+                        self.cycle_quarter_frame();
                     },
-                    7457 => {
-                        self.square_1.cycle_envelope();
-                        self.square_1.cycle_length();
-                        self.square_1.cycle_sweep();
-                        self.square_2.cycle_envelope();
-                        self.square_2.cycle_length();
-                        self.square_2.cycle_sweep();
+                    frame if frame == half => {
+                        self.cycle_quarter_frame();
+                        self.cycle_half_frame();
                     },
-                    11186 => {
-                        self.square_1.cycle_envelope();
-                        self.square_2.cycle_envelope();
+                    frame if frame == four_step_end - 1 => {
+                        self.assert_frame_interrupt();
                     },
-                    14915 => {
-                        self.square_1.cycle_envelope();
-                        self.square_1.cycle_length();
-                        self.square_1.cycle_sweep();
-                        self.square_2.cycle_envelope();
-                        self.square_2.cycle_length();
-                        self.square_2.cycle_sweep();
-                        if !self.irq_inhibit {
-                            self.status |= StatusFlag::FrameInterrupt as u8;
-                            // cpu.interrupt_request(Interrupt::IRQ);
-                        }
+                    frame if frame == four_step_end => {
+                        self.cycle_quarter_frame();
+                        self.cycle_half_frame();
+                        self.assert_frame_interrupt();
+                    },
+                    frame if frame == four_step_end + 1 => {
+                        self.assert_frame_interrupt();
                         self.frame = 0;
                     },
                     _ => {},
@@ -114,29 +199,14 @@ impl Apu {
             },
             FrameCounterMode::FiveStep => {
                 match self.frame {
-                    3729 => {
-                        self.square_1.cycle_envelope();
-                        self.square_2.cycle_envelope();
-                    },
-                    7457 => {
-                        self.square_1.cycle_envelope();
-                        self.square_1.cycle_length();
-                        self.square_1.cycle_sweep();
-                        self.square_2.cycle_envelope();
-                        self.square_2.cycle_length();
-                        self.square_2.cycle_sweep();
+                    frame if frame == quarter || frame == three_quarters => {
+                        self.cycle_quarter_frame();
                     },
-                    11186 => {
-                        self.square_1.cycle_envelope();
-                        self.square_2.cycle_envelope();
+                    frame if frame == half || frame == five_step_end => {
+                        self.cycle_quarter_frame();
+                        self.cycle_half_frame();
                     },
-                    18641 => {
-                        self.square_1.cycle_envelope();
-                        self.square_1.cycle_length();
-                        self.square_1.cycle_sweep();
-                        self.square_2.cycle_envelope();
-                        self.square_2.cycle_length();
-                        self.square_2.cycle_sweep();
+                    frame if frame == five_step_end + 1 => {
                         self.frame = 0;
                     },
                     _ => {},
@@ -145,6 +215,37 @@ impl Apu {
         }
     }
 
+    /**
+     * The frame interrupt flag is held for the last three cycles of the four-step sequence
+     */
+    fn assert_frame_interrupt (&mut self) {
+        if !self.irq_inhibit {
+            self.status |= StatusFlag::FrameInterrupt as u8;
+        }
+    }
+
+    /**
+     * Clock envelopes and the triangle's linear counter
+     */
+    fn cycle_quarter_frame (&mut self) {
+        self.square_1.cycle_envelope();
+        self.square_2.cycle_envelope();
+        self.triangle_1.cycle_linear();
+        self.noise_1.cycle_envelope();
+    }
+
+    /**
+     * Clock length counters and sweep units
+     */
+    fn cycle_half_frame (&mut self) {
+        self.square_1.cycle_length();
+        self.square_1.cycle_sweep();
+        self.square_2.cycle_length();
+        self.square_2.cycle_sweep();
+        self.triangle_1.cycle_length();
+        self.noise_1.cycle_length();
+    }
+
     pub fn reset (&mut self) {
         self.status = 0;
         self.frame = 0;
@@ -156,7 +257,8 @@ impl Apu {
      */
     pub fn mix (&self) -> f32 {
         let pulse_output = 95.88 / (8128.0 / (self.square_1.output() + self.square_2.output()) as f32 + 100.0);
-        let tnd_output = 0.0;
+        let tnd = self.triangle_1.output() as f32 / 8227.0 + self.noise_1.output() as f32 / 12241.0 + self.sample_1.output() as f32 / 22638.0;
+        let tnd_output = if tnd > 0.0 { 159.79 / (1.0 / tnd + 100.0) } else { 0.0 };
         pulse_output + tnd_output
     }
 
@@ -176,13 +278,31 @@ impl Apu {
         buffer
     }
 
+    /**
+     * Address of the next DMC sample byte, when the CPU should be stalled to fetch it
+     */
+    pub fn dmc_request (&self) -> Option<u16> {
+        self.sample_1.request()
+    }
+
+    /**
+     * Deliver a DMC sample byte fetched by the CPU
+     */
+    pub fn dmc_fill (&mut self, data: u8) {
+        self.sample_1.fill(data);
+    }
+
     pub fn read (&mut self, address: u16) -> u8 {
         match address {
             // Status
             0x4015 => {
                 let status = (if self.square_1.length > 0 { 1 } else { 0 })
                     | (if self.square_2.length > 0 { 1 } else { 0 } << 1)
-                    | (if (self.status & StatusFlag::FrameInterrupt as u8) > 0 { 1 } else { 0 } << 6);
+                    | (if self.triangle_1.length > 0 { 1 } else { 0 } << 2)
+                    | (if self.noise_1.length > 0 { 1 } else { 0 } << 3)
+                    | (if self.sample_1.remaining > 0 { 1 } else { 0 } << 4)
+                    | (if (self.status & StatusFlag::FrameInterrupt as u8) > 0 { 1 } else { 0 } << 6)
+                    | (if self.sample_1.interrupt { StatusFlag::DMCInterrupt as u8 } else { 0 });
                 self.status &= !(StatusFlag::FrameInterrupt as u8);
                 status
             },
@@ -225,10 +345,47 @@ impl Apu {
             0x4007 => {
                 self.square_2.write_hi(data);
             },
+            // Triangle
+            0x4008 => {
+                self.triangle_1.write_ctrl(data);
+            },
+            0x400A => {
+                self.triangle_1.write_lo(data);
+            },
+            0x400B => {
+                self.triangle_1.write_hi(data);
+            },
+            // Noise
+            0x400C => {
+                self.noise_1.write_ctrl(data);
+            },
+            0x400E => {
+                self.noise_1.write_period(data);
+            },
+            0x400F => {
+                self.noise_1.write_length(data);
+            },
+            // DMC
+            0x4010 => {
+                self.sample_1.write_ctrl(data);
+            },
+            0x4011 => {
+                self.sample_1.write_output(data);
+            },
+            0x4012 => {
+                self.sample_1.write_address(data);
+            },
+            0x4013 => {
+                self.sample_1.write_length(data);
+            },
             // Status
             0x4015 => {
                 if (data & StatusFlag::Square1 as u8) > 0 { self.square_1.enable(); } else { self.square_1.disable(); }
                 if (data & StatusFlag::Square2 as u8) > 0 { self.square_2.enable(); } else { self.square_2.disable(); }
+                if (data & StatusFlag::Triangle as u8) > 0 { self.triangle_1.enable(); } else { self.triangle_1.disable(); }
+                if (data & StatusFlag::Noise as u8) > 0 { self.noise_1.enable(); } else { self.noise_1.disable(); }
+                if (data & StatusFlag::DMC as u8) > 0 { self.sample_1.enable(); } else { self.sample_1.disable(); }
+                self.sample_1.interrupt = false;
             },
             // Frame counter
             0x4017 => {
@@ -239,16 +396,10 @@ impl Apu {
                     self.status &= !(StatusFlag::FrameInterrupt as u8);
                 }
 
-                if self.mode == FrameCounterMode::FiveStep {
-                    self.square_1.cycle_envelope();
-                    self.square_1.cycle_length();
-                    self.square_1.cycle_sweep();
-                    self.square_2.cycle_envelope();
-                    self.square_2.cycle_length();
-                    self.square_2.cycle_sweep();
-                }
-
-                self.frame = 0;
+                // The sequence restarts 3 CPU cycles after a write made during an APU cycle, or 4 after
+                // one made between APU cycles. This cycle has not been clocked yet, so it counts too.
+                let during_apu_cycle = self.clock.cycles % 2 == 1;
+                self.frame_reset = if during_apu_cycle { 4 } else { 5 };
             },
             _ => {}, // panic!("Invalid APU write @ {:#x}", address),
         }
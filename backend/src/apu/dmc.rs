@@ -0,0 +1,181 @@
+/**
+ * Timer periods, in CPU cycles
+ * https://wiki.nesdev.org/w/index.php/APU_DMC
+ */
+pub const DMC_RATE_NTSC: [u16; 16] = [
+    428, 380, 340, 320, 286, 254, 226, 214,
+    190, 160, 142, 128, 106, 84, 72, 54,
+];
+pub const DMC_RATE_PAL: [u16; 16] = [
+    398, 354, 316, 298, 276, 236, 210, 198,
+    176, 148, 132, 118, 98, 78, 66, 50,
+];
+
+/**
+ * Delta modulation channel
+ * https://wiki.nesdev.org/w/index.php/APU_DMC
+ */
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct Dmc {
+    rates: [u16; 16],
+    pub interrupt: bool,
+    irq_enabled: bool,
+    loop_enabled: bool,
+    timer: u16,
+    timer_reload: u16,
+    output: u8, // 7-bit DAC
+    // Memory reader
+    sample_address: u16,
+    sample_length: u16,
+    address: u16,
+    pub remaining: u16,
+    buffer: Option<u8>,
+    // Output unit
+    shift: u8,
+    bits: u8,
+    silence: bool,
+}
+
+impl Dmc {
+    pub fn new (rates: [u16; 16]) -> Self {
+        Self {
+            rates,
+            interrupt: false,
+            irq_enabled: false,
+            loop_enabled: false,
+            timer: 0,
+            timer_reload: rates[0] - 1,
+            output: 0,
+            sample_address: 0xC000,
+            sample_length: 1,
+            address: 0xC000,
+            remaining: 0,
+            buffer: None,
+            shift: 0,
+            bits: 8,
+            silence: true,
+        }
+    }
+
+    /**
+     * Clocked on every CPU cycle, as periods are expressed in CPU cycles
+     */
+    pub fn cycle_timer (&mut self) {
+        if self.timer == 0 {
+            self.cycle_output();
+            self.timer = self.timer_reload;
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    fn cycle_output (&mut self) {
+        if !self.silence {
+            if (self.shift & 1) == 1 {
+                if self.output <= 125 {
+                    self.output += 2;
+                }
+            } else if self.output >= 2 {
+                self.output -= 2;
+            }
+        }
+
+        self.shift >>= 1;
+        self.bits -= 1;
+
+        // Start a new output cycle
+        if self.bits == 0 {
+            self.bits = 8;
+
+            match self.buffer.take() {
+                Some (data) => {
+                    self.silence = false;
+                    self.shift = data;
+                },
+                None => {
+                    self.silence = true;
+                },
+            }
+        }
+    }
+
+    /**
+     * Address of the next sample byte to fetch, if the sample buffer needs refilling
+     */
+    pub fn request (&self) -> Option<u16> {
+        if self.buffer.is_none() && self.remaining > 0 {
+            Some(self.address)
+        } else {
+            None
+        }
+    }
+
+    /**
+     * Cycles before the output unit empties the sample buffer while there are bytes left to fetch
+     */
+    pub fn quiet_cycles (&self) -> usize {
+        match self.buffer {
+            _ if self.remaining == 0 => usize::MAX,
+            None => 0,
+            Some (_) => self.timer as usize + (self.bits as usize - 1) * (self.timer_reload as usize + 1),
+        }
+    }
+
+    /**
+     * Fill the sample buffer with a byte fetched by DMA
+     */
+    pub fn fill (&mut self, data: u8) {
+        self.buffer = Some(data);
+        self.address = if self.address == 0xFFFF { 0x8000 } else { self.address + 1 };
+        self.remaining -= 1;
+
+        if self.remaining == 0 {
+            if self.loop_enabled {
+                self.restart();
+            } else if self.irq_enabled {
+                self.interrupt = true;
+            }
+        }
+    }
+
+    fn restart (&mut self) {
+        self.address = self.sample_address;
+        self.remaining = self.sample_length;
+    }
+
+    pub fn write_ctrl (&mut self, data: u8) {
+        self.irq_enabled = (data & 0b1000_0000) > 0;
+        self.loop_enabled = (data & 0b0100_0000) > 0;
+        self.timer_reload = self.rates[(data & 0b0000_1111) as usize] - 1;
+
+        if !self.irq_enabled {
+            self.interrupt = false;
+        }
+    }
+
+    pub fn write_output (&mut self, data: u8) {
+        self.output = data & 0b0111_1111;
+    }
+
+    pub fn write_address (&mut self, data: u8) {
+        self.sample_address = 0xC000 | (data as u16) << 6;
+    }
+
+    pub fn write_length (&mut self, data: u8) {
+        self.sample_length = (data as u16) << 4 | 1;
+    }
+
+    pub fn output (&self) -> u8 {
+        self.output
+    }
+
+    pub fn enable (&mut self) {
+        if self.remaining == 0 {
+            self.restart();
+        }
+    }
+
+    pub fn disable (&mut self) {
+        self.remaining = 0;
+    }
+}
@@ -1,8 +1,14 @@
 mod apu;
+mod dmc;
+mod noise;
 mod pulse;
+mod triangle;
 
 pub use apu::*;
+pub use dmc::*;
+pub use noise::*;
 pub use pulse::*;
+pub use triangle::*;
 
 /**
  * https://wiki.nesdev.org/w/index.php/APU_Length_Counter
@@ -0,0 +1,130 @@
+/**
+ * Timer periods, in CPU cycles
+ * https://wiki.nesdev.org/w/index.php/APU_Noise
+ */
+pub const NOISE_PERIOD_NTSC: [u16; 16] = [
+    4, 8, 16, 32, 64, 96, 128, 160,
+    202, 254, 380, 508, 762, 1016, 2034, 4068,
+];
+pub const NOISE_PERIOD_PAL: [u16; 16] = [
+    4, 8, 14, 30, 60, 88, 118, 148,
+    188, 236, 354, 472, 708, 944, 1890, 3778,
+];
+
+/**
+ * https://wiki.nesdev.org/w/index.php/APU_Noise
+ */
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct Noise {
+    periods: [u16; 16],
+    shift: u16, // 15-bit linear feedback shift register
+    mode: bool, // Short mode, feedback from bit 6 instead of bit 1
+    pub length: u8,
+    length_halt: bool,
+    enabled: bool,
+    timer: u16,
+    timer_reload: u16,
+    volume: u8,
+    volume_constant: u8,
+    envelope_start: bool,
+    envelope_timer: u8,
+    envelope_timer_reload: u8,
+    envelope_enabled: bool,
+}
+
+impl Noise {
+    pub fn new (periods: [u16; 16]) -> Self {
+        Self {
+            periods,
+            shift: 1,
+            mode: false,
+            length: 0,
+            length_halt: false,
+            enabled: false,
+            timer: 0,
+            timer_reload: periods[0] - 1,
+            volume: 0,
+            volume_constant: 0,
+            envelope_start: false,
+            envelope_timer: 0,
+            envelope_timer_reload: 0,
+            envelope_enabled: true,
+        }
+    }
+
+    /**
+     * Clocked on every CPU cycle, as periods are expressed in CPU cycles
+     */
+    pub fn cycle_timer (&mut self) {
+        if self.timer == 0 {
+            let feedback = (self.shift & 1) ^ ((self.shift >> if self.mode { 6 } else { 1 }) & 1);
+            self.shift = (self.shift >> 1) | (feedback << 14);
+            self.timer = self.timer_reload;
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    pub fn cycle_length (&mut self) {
+        if !self.length_halt && self.length > 0 {
+            self.length -= 1;
+        }
+    }
+
+    pub fn cycle_envelope (&mut self) {
+        if self.envelope_start {
+            self.volume = 15;
+            self.envelope_timer = self.envelope_timer_reload;
+            self.envelope_start = false;
+        } else if self.envelope_timer == 0 {
+            if self.volume > 0 {
+                self.volume -= 1;
+            } else if self.length_halt {
+                // The length counter halt flag doubles as the envelope loop flag
+                self.volume = 15;
+            }
+
+            self.envelope_timer = self.envelope_timer_reload;
+        } else {
+            self.envelope_timer -= 1;
+        }
+    }
+
+    pub fn write_ctrl (&mut self, data: u8) {
+        self.length_halt = (data & 0b0010_0000) > 0;
+        self.envelope_enabled = (data & 0b0001_0000) == 0;
+        self.envelope_timer_reload = data & 0b0000_1111;
+        self.volume_constant = data & 0b0000_1111;
+    }
+
+    pub fn write_period (&mut self, data: u8) {
+        self.mode = (data & 0b1000_0000) > 0;
+        self.timer_reload = self.periods[(data & 0b0000_1111) as usize] - 1;
+    }
+
+    pub fn write_length (&mut self, data: u8) {
+        if self.enabled {
+            self.length = super::LENGTH_TABLE[((data & 0b1111_1000) >> 3) as usize];
+        }
+        self.envelope_start = true;
+    }
+
+    pub fn output (&self) -> u8 {
+        if self.length == 0 || (self.shift & 1) == 1 {
+            0
+        } else if self.envelope_enabled {
+            self.volume
+        } else {
+            self.volume_constant
+        }
+    }
+
+    pub fn enable (&mut self) {
+        self.enabled = true;
+    }
+
+    pub fn disable (&mut self) {
+        self.enabled = false;
+        self.length = 0;
+    }
+}
@@ -8,7 +8,7 @@ const PULSE_TABLE: [u8; 4] = [
 /**
  * https://wiki.nesdev.org/w/index.php/APU_Pulse
  */
-#[derive(Clone)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct Pulse {
     id: u8,
     output: u8, // 0-15
@@ -0,0 +1,85 @@
+const TRIANGLE_TABLE: [u8; 32] = [
+    15, 14, 13, 12, 11, 10, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0,
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15,
+];
+
+/**
+ * https://wiki.nesdev.org/w/index.php/APU_Triangle
+ */
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Triangle {
+    sequence: u8, // 0-31
+    pub length: u8,
+    enabled: bool,
+    control: bool, // Also halts the length counter
+    timer: u16,
+    timer_reload: u16,
+    linear: u8,
+    linear_reload: u8,
+    linear_reload_flag: bool,
+}
+
+impl Triangle {
+    /**
+     * Clocked on every CPU cycle, unlike the pulse channels
+     */
+    pub fn cycle_timer (&mut self) {
+        if self.timer == 0 {
+            // Silence ultrasonic frequencies instead of letting them pop
+            if self.linear > 0 && self.length > 0 && self.timer_reload >= 2 {
+                self.sequence = (self.sequence + 1) % 32;
+            }
+            self.timer = self.timer_reload;
+        } else {
+            self.timer -= 1;
+        }
+    }
+
+    pub fn cycle_length (&mut self) {
+        if !self.control && self.length > 0 {
+            self.length -= 1;
+        }
+    }
+
+    pub fn cycle_linear (&mut self) {
+        if self.linear_reload_flag {
+            self.linear = self.linear_reload;
+        } else if self.linear > 0 {
+            self.linear -= 1;
+        }
+
+        if !self.control {
+            self.linear_reload_flag = false;
+        }
+    }
+
+    pub fn write_ctrl (&mut self, data: u8) {
+        self.control = (data & 0b1000_0000) > 0;
+        self.linear_reload = data & 0b0111_1111;
+    }
+
+    pub fn write_lo (&mut self, data: u8) {
+        self.timer_reload = (self.timer_reload & 0xFF00) | data as u16;
+    }
+
+    pub fn write_hi (&mut self, data: u8) {
+        if self.enabled {
+            self.length = super::LENGTH_TABLE[((data & 0b1111_1000) >> 3) as usize];
+        }
+        self.timer_reload = (self.timer_reload & 0x00FF) | (data as u16 & 0b0000_0111) << 8;
+        self.linear_reload_flag = true;
+    }
+
+    pub fn output (&self) -> u8 {
+        TRIANGLE_TABLE[self.sequence as usize]
+    }
+
+    pub fn enable (&mut self) {
+        self.enabled = true;
+    }
+
+    pub fn disable (&mut self) {
+        self.enabled = false;
+        self.length = 0;
+    }
+}
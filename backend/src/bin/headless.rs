@@ -0,0 +1,296 @@
+//! Native runner, to run ROMs without a browser (CI, scripting)
+//! Loads a ROM, runs it for a number of frames or until a memory condition is met while feeding scripted input,
+//! then dumps the final frame as PNG and the audio as WAV.
+
+use std::{fs, process};
+use wasm_nes::{Emulator, Movie, clock::Region, input::Button};
+
+const USAGE: &str = "Usage: nes-headless <rom> [options]
+
+Options:
+    --frames <n>            Frames to run, or at most when waiting for a condition (default 600, or the movie length)
+    --until <addr>=<value>  Stop once the byte at addr equals value, or differs with != (hex, e.g. 6000!=80)
+    --input <file>          Input script, one \"<frame> <buttons> [<buttons>]\" line per change for players 1 and 2,
+                            buttons being joined with + (e.g. \"120 Start\", \"300 A+Right -\"), held until the next line
+    --movie <file>          Play a movie (native or FM2) instead of an input script
+    --region <region>       Force the region: ntsc, pal or dendy (default from the ROM header)
+    --sample-rate <hz>      Audio sample rate (default 48000)
+    --png <file>            Save the final frame
+    --wav <file>            Save the audio, as 32-bit float mono
+
+Exits with 1 when the condition wasn't met in time or the movie desynced, 2 on invalid arguments or ROM";
+
+const BUTTONS: [&str; 8] = ["A", "B", "Select", "Start", "Up", "Down", "Left", "Right"];
+
+struct Options {
+    rom: String,
+    frames: usize,
+    until: Option<Condition>,
+    input: Vec<InputChange>,
+    movie: Option<Movie>,
+    region: Option<Region>,
+    sample_rate: f64,
+    png: Option<String>,
+    wav: Option<String>,
+}
+
+struct Condition {
+    address: u16,
+    value: u8,
+    equal: bool,
+}
+
+impl Condition {
+    fn parse (condition: &str) -> Result<Self, String> {
+        let (operator, equal) = if condition.contains("!=") { ("!=", false) } else { ("=", true) };
+        let mut parts = condition.splitn(2, operator);
+        let address = parts.next().and_then(|address| u16::from_str_radix(address.trim_start_matches('$'), 16).ok());
+        let value = parts.next().and_then(|value| u8::from_str_radix(value.trim_start_matches('$'), 16).ok());
+
+        match (address, value) {
+            (Some (address), Some (value)) => Ok(Self { address, value, equal }),
+            _ => Err(format!("Invalid condition \"{}\"", condition)),
+        }
+    }
+
+    fn met (&self, nes: &Emulator) -> bool {
+        matches!(nes.peek(self.address), Some (data) if (data == self.value) == self.equal)
+    }
+}
+
+/**
+ * Controller states taking effect at the start of a frame
+ */
+struct InputChange {
+    frame: usize,
+    players: [u8; 2],
+}
+
+fn parse_buttons (buttons: &str) -> Result<u8, String> {
+    if buttons == "-" {
+        return Ok(0);
+    }
+
+    buttons.split('+').try_fold(0, |state, name| {
+        BUTTONS.iter()
+            .position(|button| button.eq_ignore_ascii_case(name))
+            .map(|bit| state | 1 << bit)
+            .ok_or_else(|| format!("Unknown button \"{}\"", name))
+    })
+}
+
+fn parse_input (script: &str) -> Result<Vec<InputChange>, String> {
+    let mut changes: Vec<InputChange> = vec![];
+
+    for (number, line) in script.lines().enumerate() {
+        let line = line.split('#').next().unwrap().trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let error = |reason: String| format!("Input script line {}: {}", number + 1, reason);
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let frame = fields[0].parse().map_err(|_| error(format!("invalid frame \"{}\"", fields[0])))?;
+
+        if fields.len() > 3 {
+            return Err(error("expected a frame and up to 2 players".to_string()));
+        }
+        if matches!(changes.last(), Some (change) if change.frame > frame) {
+            return Err(error("frames must be in increasing order".to_string()));
+        }
+
+        let mut players = [0; 2];
+        for (player, buttons) in fields[1..].iter().enumerate() {
+            players[player] = parse_buttons(buttons).map_err(error)?;
+        }
+
+        changes.push(InputChange { frame, players });
+    }
+
+    Ok(changes)
+}
+
+fn parse_options (arguments: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        rom: String::new(),
+        frames: 0,
+        until: None,
+        input: vec![],
+        movie: None,
+        region: None,
+        sample_rate: 48_000.0,
+        png: None,
+        wav: None,
+    };
+    let mut arguments = arguments.iter();
+
+    while let Some(argument) = arguments.next() {
+        if !argument.starts_with("--") {
+            options.rom = argument.clone();
+            continue;
+        }
+
+        let value = arguments.next().ok_or_else(|| format!("Missing value for {}", argument))?;
+        let invalid = || format!("Invalid value \"{}\" for {}", value, argument);
+
+        match argument.as_str() {
+            "--frames" => options.frames = value.parse().map_err(|_| invalid())?,
+            "--until" => options.until = Some(Condition::parse(value)?),
+            "--input" => {
+                let script = fs::read_to_string(value).map_err(|error| format!("Can't read {}: {}", value, error))?;
+                options.input = parse_input(&script)?;
+            },
+            "--movie" => {
+                let movie = fs::read(value).map_err(|error| format!("Can't read {}: {}", value, error))?;
+                options.movie = Some(Movie::decode(&movie).map_err(|error| error.to_string())?);
+            },
+            "--region" => options.region = Some(match value.to_lowercase().as_str() {
+                "ntsc" => Region::NTSC,
+                "pal" => Region::PAL,
+                "dendy" => Region::Dendy,
+                _ => return Err(invalid()),
+            }),
+            "--sample-rate" => options.sample_rate = value.parse().map_err(|_| invalid())?,
+            "--png" => options.png = Some(value.clone()),
+            "--wav" => options.wav = Some(value.clone()),
+            _ => return Err(format!("Unknown option {}", argument)),
+        }
+    }
+
+    if options.rom.is_empty() {
+        return Err("Missing ROM".to_string());
+    }
+    if options.frames == 0 {
+        options.frames = options.movie.as_ref().map_or(600, |movie| movie.length as usize);
+    }
+
+    Ok(options)
+}
+
+fn set_controller (nes: &mut Emulator, player: usize, state: u8) {
+    let buttons = vec![Button::A, Button::B, Button::Select, Button::Start, Button::Up, Button::Down, Button::Left, Button::Right];
+
+    for (bit, button) in buttons.into_iter().enumerate() {
+        nes.update_controller(player, button, state & 1 << bit > 0);
+    }
+}
+
+/**
+ * http://soundfile.sapp.org/doc/WaveFormat/
+ */
+fn encode_wav (samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    let size = samples.len() as u32 * 4;
+    let mut wav = Vec::with_capacity(44 + size as usize);
+
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + size).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&3u16.to_le_bytes()); // IEEE float
+    wav.extend_from_slice(&1u16.to_le_bytes()); // Mono
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * 4).to_le_bytes()); // Byte rate
+    wav.extend_from_slice(&4u16.to_le_bytes()); // Block align
+    wav.extend_from_slice(&32u16.to_le_bytes()); // Bits per sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&size.to_le_bytes());
+
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+
+    wav
+}
+
+fn run (options: &Options) -> Result<bool, String> {
+    let rom = fs::read(&options.rom).map_err(|error| format!("Can't read {}: {}", options.rom, error))?;
+    let mut nes = match options.region {
+        Some (region) => Emulator::with_region(rom, options.sample_rate, region),
+        None => Emulator::new(rom, options.sample_rate),
+    }.map_err(|error| error.to_string())?;
+    let mut input = options.input.iter().peekable();
+    let mut audio = vec![];
+    let mut met = options.until.is_none();
+    let mut frame = 0;
+
+    if let Some(movie) = &options.movie {
+        nes.movie_play(movie.clone()).map_err(|error| error.to_string())?;
+    }
+
+    while frame < options.frames {
+        while let Some(change) = input.next_if(|change| change.frame <= frame) {
+            set_controller(&mut nes, 0, change.players[0]);
+            set_controller(&mut nes, 1, change.players[1]);
+        }
+
+        nes.cycle_until_frame();
+        audio.append(&mut nes.get_audio());
+        frame += 1;
+
+        if let Some(condition) = &options.until {
+            if condition.met(&nes) {
+                met = true;
+                break;
+            }
+        }
+    }
+
+    println!("Ran {} frames{}", frame, if met { "" } else { ", condition not met" });
+
+    if let Some(desync) = nes.movie_desync() {
+        println!("Movie desynced at frame {}", desync);
+        met = false;
+    }
+
+    if let Some(path) = &options.png {
+        image::save_buffer(path, nes.framebuffer(), 256, 240, image::ColorType::Rgba8)
+            .map_err(|error| format!("Can't write {}: {}", path, error))?;
+    }
+
+    if let Some(path) = &options.wav {
+        fs::write(path, encode_wav(&audio, options.sample_rate.round() as u32))
+            .map_err(|error| format!("Can't write {}: {}", path, error))?;
+    }
+
+    Ok(met)
+}
+
+fn main () {
+    let arguments: Vec<String> = std::env::args().skip(1).collect();
+
+    if arguments.is_empty() || arguments.iter().any(|argument| argument == "--help") {
+        println!("{}", USAGE);
+        return;
+    }
+
+    let options = parse_options(&arguments).unwrap_or_else(|error| {
+        eprintln!("{}\n\n{}", error, USAGE);
+        process::exit(2);
+    });
+
+    match run(&options) {
+        Ok (true) => {},
+        Ok (false) => process::exit(1),
+        Err (error) => {
+            eprintln!("{}", error);
+            process::exit(2);
+        },
+    }
+}
+
+#[test]
+fn script () {
+    let input = parse_input("# Skip the title screen\n120 Start\n122 -\n300 a+RIGHT Select\n").unwrap();
+
+    assert_eq!(input.iter().map(|change| (change.frame, change.players)).collect::<Vec<_>>(), vec![
+        (120, [0b0000_1000, 0]),
+        (122, [0, 0]),
+        (300, [0b1000_0001, 0b0000_0100]),
+    ]);
+    assert!(parse_input("10 Start\n5 -").is_err());
+    assert!(parse_input("10 Turbo").is_err());
+
+    let condition = Condition::parse("$6000!=80").unwrap();
+    assert_eq!((condition.address, condition.value, condition.equal), (0x6000, 0x80, false));
+    assert!(Condition::parse("6000").is_err());
+}
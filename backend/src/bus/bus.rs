@@ -11,47 +11,160 @@
 use crate::{
     ppu::Ppu,
     apu::Apu,
-    cartridge::Cartridge,
+    cartridge::{Cartridge, CartridgeError},
     input::Controller,
-    bus::Dma,
+    bus::{Dma, DmcDma, InterruptLines, IrqSource},
+    clock::Region,
+    Watch,
 };
 
+/**
+ * Master cycles between a CPU cycle's bus access and when it samples the interrupt lines
+ */
+const INTERRUPT_POLL_DELAY: i64 = 3;
+
+#[derive(serde::Serialize, serde::Deserialize)]
 pub struct Bus {
     pub wram: Vec<u8>, // 2 KiB, mirrored (max 11 bits)
     pub ppu: Ppu,
     pub apu: Apu,
-    pub cartridge: Cartridge,
+    #[serde(skip)]
+    pub cartridge: Cartridge, // Saved separately, without its ROM
     pub dma: Option<Dma>,
+    pub dmc_dma: Option<DmcDma>,
+    pub interrupts: InterruptLines,
     pub controllers: [Controller; 2],
     pub read_buffer: u8, // Open bus
+    pub ppu_lag: i64, // Master cycles the PPU is running behind the CPU, negative when it ran ahead to sample interrupts
+    #[serde(skip)]
+    pub ppu_deadline: u32, // Lag at which the PPU must catch up before the next CPU cycle
+    pub apu_lag: u32, // Master cycles the APU is running behind the CPU
+    #[serde(skip)]
+    pub apu_deadline: u32, // Lag at which the APU must catch up before the next CPU cycle
+    #[serde(skip)]
+    pub watch: Watch, // Debugger watchpoints
 }
 
 impl Bus {
-    pub fn new (rom: &Vec<u8>, sample_rate: f64) -> Bus {
-        Bus {
+    /**
+     * Runs on the region from the ROM header, unless one is given
+     */
+    pub fn new (rom: &[u8], sample_rate: f64, region: Option<Region>) -> Result<Bus, CartridgeError> {
+        let cartridge = Cartridge::new(rom)?;
+        let region = region.unwrap_or_else(|| cartridge.ines.timing.into());
+
+        Ok(Bus {
             wram: vec![0; 0x800],
-            ppu: Ppu::new(),
-            apu: Apu::new(sample_rate),
-            cartridge: Cartridge::new(rom),
+            ppu: Ppu::new(region),
+            apu: Apu::new(sample_rate, region),
+            cartridge,
             dma: None,
+            dmc_dma: None,
+            interrupts: InterruptLines::default(),
             controllers: [Controller::new(); 2],
             read_buffer: 0,
+            ppu_lag: 0,
+            ppu_deadline: 0,
+            apu_lag: 0,
+            apu_deadline: 0,
+            watch: Watch::default(),
+        })
+    }
+
+    /**
+     * Run the PPU and APU up to the current master cycle, at the end of an emulation step
+     */
+    pub fn catch_up (&mut self) {
+        self.catch_up_ppu();
+        self.catch_up_apu();
+    }
+
+    /**
+     * The PPU lags behind the CPU and only runs when something depends on it: a PPU register or mapper access,
+     * an event raising an interrupt, or the end of an emulation step. Runs it up to the current master cycle.
+     */
+    fn catch_up_ppu (&mut self) {
+        self.ppu.run(self.ppu_lag.max(0) as u32, &mut self.cartridge, &mut self.interrupts);
+        self.interrupts.set_irq(IrqSource::Mapper, self.cartridge.irq());
+        self.ppu_lag = self.ppu_lag.min(0);
+
+        let quiet_dots = self.ppu.quiet_dots(self.cartridge.watches_ppu());
+        self.ppu_deadline = self.ppu.clock.master_cycles_until(quiet_dots + 1) as u32;
+    }
+
+    /**
+     * The APU lags behind the CPU too: it runs on APU register accesses, before its interrupts and DMC fetches,
+     * and at the end of an emulation step. Audio samples come out the same, only later.
+     */
+    fn catch_up_apu (&mut self) {
+        self.apu.run(self.apu_lag, &mut self.interrupts);
+        self.apu_lag = 0;
+
+        let quiet_cycles = self.apu.quiet_cycles();
+        self.apu_deadline = self.apu.clock.master_cycles_until(quiet_cycles + 1) as u32;
+    }
+
+    /**
+     * Before each CPU cycle, catch the PPU and APU up if they owe a cycle the CPU could observe
+     */
+    pub fn sync (&mut self) {
+        if self.ppu_lag >= self.ppu_deadline as i64 {
+            self.catch_up_ppu();
+        }
+        if self.apu_lag >= self.apu_deadline {
+            self.catch_up_apu();
+        }
+    }
+
+    /**
+     * Interrupt lines are sampled at the end of the CPU cycle, after its bus access. When the PPU owes a dot by then,
+     * it runs ahead of the CPU to that point, and the lag stays negative until the next cycle's access.
+     */
+    pub fn sync_interrupts (&mut self) {
+        if self.ppu_lag + INTERRUPT_POLL_DELAY >= self.ppu_deadline as i64 {
+            self.ppu_lag += INTERRUPT_POLL_DELAY;
+            self.catch_up_ppu();
+            self.ppu_lag -= INTERRUPT_POLL_DELAY;
         }
     }
 
+    /**
+     * OAM DMA transfer, which goes through $2004
+     */
+    pub fn write_oam (&mut self, data: u8) {
+        self.catch_up_ppu();
+        self.ppu.write_oam(data);
+        self.ppu_deadline = 0;
+    }
+
     pub fn read (&mut self, address: u16) -> u8 {
         let data = match address {
             0x0000 ..= 0x1FFF => self.wram[address as usize % 0x800],
-            0x2000 ..= 0x3FFF => self.ppu.read(&self.cartridge, address),
-            0x4000 ..= 0x4015 => self.apu.read(address),
+            0x2000 ..= 0x3FFF => {
+                self.catch_up_ppu();
+                // Reads have side effects (flags, VRAM address), make sure they are seen by the next CPU cycle
+                self.ppu_deadline = 0;
+                self.ppu.read(&mut self.cartridge, &mut self.interrupts, address)
+            },
+            0x4000 ..= 0x4014 => self.read_buffer, // Write-only
+            0x4015 => {
+                self.catch_up_apu();
+                // Reading acknowledges the frame interrupt, make sure the next CPU cycle sees it
+                self.apu_deadline = 0;
+                self.apu.read(address)
+            },
             0x4016 => self.controllers[0].read() | (self.read_buffer & 0b1110_0000),
             0x4017 => self.controllers[1].read() | (self.read_buffer & 0b1110_0000),
-            0x4018 ..= 0x401F => panic!("Disabled functionality"),
-            0x4020 ..= 0xFFFF => self.cartridge.read_prg(address),
+            0x4018 ..= 0x401F => self.read_buffer, // Disabled functionality
+            0x4020 ..= 0xFFFF => self.cartridge.read_prg(address).unwrap_or(self.read_buffer),
         };
 
         self.read_buffer = data;
 
+        if !self.watch.is_empty() {
+            self.watch.check(address, data, false);
+        }
+
         data
     }
 
@@ -68,15 +181,23 @@ impl Bus {
     }
 
     pub fn write (&mut self, address: u16, data: u8) {
+        if !self.watch.is_empty() {
+            self.watch.check(address, data, true);
+        }
+
         match address {
             0x0000 ..= 0x1FFF => {
                 self.wram[address as usize % 0x800] = data;
             },
             0x2000 ..= 0x3FFF => {
-                self.ppu.write(&mut self.cartridge, address, data);
-            }
+                self.catch_up_ppu();
+                self.ppu.write(&mut self.cartridge, &mut self.interrupts, address, data);
+                self.ppu_deadline = 0;
+            },
             0x4000 ..= 0x4013 | 0x4015 | 0x4017 => {
+                self.catch_up_apu();
                 self.apu.write(address, data);
+                self.apu_deadline = 0;
             },
             0x4014 => {
                 self.dma = Some(Dma {
@@ -90,9 +211,12 @@ impl Bus {
                 self.controllers[0].write(data);
                 self.controllers[1].write(data);
             },
-            0x4018 ..= 0x401F => panic!("Disabled functionality"),
+            0x4018 ..= 0x401F => {}, // Disabled functionality
             0x4020 ..= 0xFFFF => {
+                // Mappers switch banks and acknowledge IRQs on writes
+                self.catch_up_ppu();
                 self.cartridge.write_prg(address, data);
+                self.ppu_deadline = 0;
             },
         };
     }
@@ -1,7 +1,17 @@
-#[derive(Debug, Copy, Clone, serde::Serialize)]
+#[derive(Debug, Copy, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Dma {
     pub page: u8,
     pub wait: bool,
     pub count: u8,
     pub read_buffer: u8,
 }
+
+/**
+ * DMC sample fetch, stalling the CPU
+ * https://wiki.nesdev.org/w/index.php/APU_DMC#Memory_reader
+ */
+#[derive(Debug, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DmcDma {
+    pub address: u16,
+    pub stall: u8,
+}
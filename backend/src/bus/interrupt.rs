@@ -0,0 +1,74 @@
+/**
+ * Devices able to pull the shared IRQ line low
+ * https://wiki.nesdev.org/w/index.php/IRQ
+ */
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize)]
+pub enum IrqSource {
+    FrameCounter    = 0b0000_0001,
+    Dmc             = 0b0000_0010,
+    Mapper          = 0b0000_0100,
+}
+
+/**
+ * Interrupt lines wired to the CPU
+ * IRQ is level-triggered: it stays asserted while any source holds it, until that source is acknowledged.
+ * NMI is edge-triggered: the falling edge is latched until the CPU services it.
+ * https://wiki.nesdev.com/w/index.php/CPU_interrupts
+ */
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct InterruptLines {
+    irq: u8, // One bit per IrqSource
+    nmi: bool,
+    nmi_level: bool,
+}
+
+impl InterruptLines {
+    pub fn assert_irq (&mut self, source: IrqSource) {
+        self.irq |= source as u8;
+    }
+
+    pub fn release_irq (&mut self, source: IrqSource) {
+        self.irq &= !(source as u8);
+    }
+
+    pub fn set_irq (&mut self, source: IrqSource, asserted: bool) {
+        if asserted {
+            self.assert_irq(source);
+        } else {
+            self.release_irq(source);
+        }
+    }
+
+    pub fn irq (&self) -> bool {
+        self.irq != 0
+    }
+
+    pub fn irq_from (&self, source: IrqSource) -> bool {
+        (self.irq & source as u8) != 0
+    }
+
+    /**
+     * Drive the NMI line, latching an edge when it becomes asserted
+     */
+    pub fn set_nmi (&mut self, asserted: bool) {
+        if asserted && !self.nmi_level {
+            self.nmi = true;
+        }
+
+        self.nmi_level = asserted;
+    }
+
+    /**
+     * Drop a latched edge the CPU has not sampled yet
+     */
+    pub fn cancel_nmi (&mut self) {
+        self.nmi = false;
+    }
+
+    /**
+     * Consume the latched NMI edge, if any
+     */
+    pub fn take_nmi (&mut self) -> bool {
+        std::mem::take(&mut self.nmi)
+    }
+}
@@ -1,5 +1,7 @@
 mod bus;
 mod dma;
+mod interrupt;
 
 pub use bus::*;
 pub use dma::*;
+pub use interrupt::*;
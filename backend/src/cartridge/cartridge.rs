@@ -1,22 +1,5 @@
-use std::{io::{Cursor, prelude::*}, fmt};
-use crate::cartridge::*;
-
-const PRG_BANK_SIZE: usize = 0x4000; // 16 KiB
-const CHR_BANK_SIZE: usize = 0x2000; // 8 KiB
-const RAM_BANK_SIZE: usize = 0x2000; // 8 KiB
-
-enum ControlFlag1 {
-    Vertical    =   0b0000_0001,
-    Ram         =   0b0000_0010,
-    Trainer     =   0b0000_0100,
-    FourScreen  =   0b0000_1000,
-    Mapper      =   0b1111_0000, // Lower nibble of mapper number
-}
-
-enum ControlFlag2 {
-    _Unused     =   0b0000_1111,
-    Mapper      =   0b1111_0000, // Upper nibble of mapper number
-}
+use std::{convert::TryInto, fmt};
+use crate::{cartridge::*, StateError};
 
 #[derive(Debug, PartialEq, Copy, Clone, serde::Serialize)]
 pub enum ChrType {
@@ -33,6 +16,16 @@ impl fmt::Display for ChrType {
     }
 }
 
+/**
+ * Cartridge contents that change while running. PRG-ROM is never part of a save state
+ */
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct CartridgeState {
+    prg_ram: Vec<u8>,
+    chr: Vec<u8>, // Mappers don't prevent writes to CHR-ROM
+    mapper: Vec<u8>,
+}
+
 pub struct Cartridge {
     pub prg_ram: Vec<u8>,
     pub prg_rom: Vec<u8>,
@@ -40,69 +33,82 @@ pub struct Cartridge {
     pub mirroring: Mirroring,
     pub mapper: Box<dyn Mapper>,
     pub ines: InesHeader,
+    pub sram_writes: u32, // Writes changing battery-backed PRG-RAM since power on
 }
 
 impl Cartridge {
-    pub fn new (rom: &Vec<u8>) -> Self {
-        let mut cursor = Cursor::new(rom);
-        let mut header = [0u8; 16];
-        cursor.read_exact(&mut header).expect("Could not read header");
+    pub fn new (rom: &[u8]) -> Result<Self, CartridgeError> {
+        let header: [u8; 16] = rom.get(0..16).ok_or(CartridgeError::TruncatedHeader)?.try_into().unwrap();
 
         if &header[0..4] != b"NES\x1A" {
-            panic!("Invalid header constant");
-        }
-
-        let prg_banks = header[4] as usize;
-        let chr_banks = header[5] as usize; 
-        let chr_type = if chr_banks > 0 { ChrType::ROM } else { ChrType::RAM };
-        let mapper = (header[6] & ControlFlag1::Mapper as u8) >> 4 | (header[7] & ControlFlag2::Mapper as u8);
-        let trainer = (header[6] & ControlFlag1::Trainer as u8) != 0;
-        let ram = (header[6] & ControlFlag1::Ram as u8) != 0;
-        let ram_size = header[8] as usize;
-        let mirroring = match (header[6] & ControlFlag1::FourScreen as u8 != 0, header[6] & ControlFlag1::Vertical as u8 != 0) {
-            (true, _)       => Mirroring::FourScreen,
-            (false, false)  => Mirroring::Horizontal,
-            (false, true)   => Mirroring::Vertical,
-        };
-
-        let mut cartridge = Cartridge {
-            prg_ram: vec![0; std::cmp::max(ram_size, 1) * RAM_BANK_SIZE], // Value 0 infers 8 KB for compatibility
-            prg_rom: vec![0; prg_banks * PRG_BANK_SIZE],
-            chr: vec![0; std::cmp::max(chr_banks, 1) * CHR_BANK_SIZE], // No distinction between CHR ROM and RAM
-            mirroring,
-            mapper: get_mapper(mapper),
-            ines: InesHeader {
-                prg_banks,
-                chr_banks,
-                chr_type,
-                mapper,
-                ram,
-                trainer,
-                mirroring,
-            },
-        };
+            return Err(CartridgeError::InvalidMagic);
+        }
+
+        let ines = InesHeader::parse(&header)?;
 
-        if trainer {
-            cursor.seek(std::io::SeekFrom::Current(512)).expect("Could not read trainer");
+        if ines.mirroring == Mirroring::FourScreen {
+            return Err(CartridgeError::UnsupportedFeature("four-screen mirroring"));
+        }
+        if ines.prg_rom_size == 0 {
+            return Err(CartridgeError::InvalidHeader("no PRG-ROM"));
+        }
+
+        let mapper = get_mapper(ines.mapper, ines.submapper)?;
+
+        if !mapper.supports_prg_rom(ines.prg_rom_size) {
+            return Err(CartridgeError::UnsupportedFeature("PRG-ROM size for this mapper"));
+        }
+        if ines.chr_rom_size % CHR_BANK_SIZE != 0 {
+            return Err(CartridgeError::UnsupportedFeature("CHR-ROM size not a multiple of 8 KiB"));
+        }
+
+        // The header can declare anything, so sizes are checked against the file before allocating
+        let prg_start = if ines.trainer { 16 + 512 } else { 16 };
+        if rom.len() < prg_start {
+            return Err(CartridgeError::TruncatedTrainer);
+        }
+        if rom.len() - prg_start < ines.prg_rom_size {
+            return Err(CartridgeError::TruncatedPrg { expected: ines.prg_rom_size, actual: rom.len() - prg_start });
         }
 
-        cursor.read_exact(cartridge.prg_rom.as_mut()).expect("Could not read PRG-ROM");
-        if chr_type == ChrType::ROM {
-            cursor.read_exact(cartridge.chr.as_mut()).expect("Could not read CHR-ROM");
+        let chr_start = prg_start + ines.prg_rom_size;
+        if ines.chr_type == ChrType::ROM && rom.len() - chr_start < ines.chr_rom_size {
+            return Err(CartridgeError::TruncatedChr { expected: ines.chr_rom_size, actual: rom.len() - chr_start });
         }
 
-        cartridge
+        Ok(Cartridge {
+            // Mappers index into PRG-RAM, so provide the usual 8 KB even when none is declared
+            prg_ram: vec![0; std::cmp::max(ines.prg_ram_size + ines.prg_nvram_size, RAM_BANK_SIZE)],
+            prg_rom: rom[prg_start .. chr_start].to_vec(),
+            // No distinction between CHR ROM and RAM
+            chr: match ines.chr_type {
+                ChrType::ROM => rom[chr_start .. chr_start + ines.chr_rom_size].to_vec(),
+                ChrType::RAM => vec![0; std::cmp::max(ines.chr_ram_size + ines.chr_nvram_size, CHR_BANK_SIZE)],
+            },
+            mirroring: ines.mirroring,
+            mapper,
+            ines,
+            sram_writes: 0,
+        })
     }
 
     pub fn read_chr (&self, address: u16) -> u8 {
         self.mapper.read_chr(address, &self.chr)
     }
 
+    /**
+     * Ignored by CHR-ROM
+     */
     pub fn write_chr (&mut self, address: u16, data: u8) {
-        self.mapper.write_chr(address, data, &mut self.chr);
+        if self.ines.chr_type == ChrType::RAM {
+            self.mapper.write_chr(address, data, &mut self.chr);
+        }
     }
 
-    pub fn read_prg (&self, address: u16) -> u8 {
+    /**
+     * None for open bus, where the cartridge doesn't drive the data bus
+     */
+    pub fn read_prg (&self, address: u16) -> Option<u8> {
         self.mapper.read_prg(address, &self.prg_ram, &self.prg_rom)
     }
 
@@ -112,10 +118,176 @@ impl Cartridge {
 
     pub fn write_prg (&mut self, address: u16, data: u8) {
         // println!("Write PRG @ {:#x} <- {:#x}", address, data);
-        self.mapper.write_prg(address, data, &mut self.prg_ram);
+        if self.ines.battery && (0x6000 ..= 0x7FFF).contains(&address) {
+            // Only count writes changing the saves, not those to protected RAM or storing the same value
+            let before = self.peek_prg(address);
+            self.mapper.write_prg(address, data, &mut self.prg_ram);
+
+            if self.peek_prg(address) != before {
+                self.sram_writes = self.sram_writes.wrapping_add(1);
+            }
+        } else {
+            self.mapper.write_prg(address, data, &mut self.prg_ram);
+        }
     }
 
     pub fn get_mirroring (&self) -> Mirroring {
         self.mapper.get_mirroring().unwrap_or(self.mirroring)
     }
+
+    /**
+     * Let the mapper observe PPU bus activity, e.g. to clock counters on A12 edges
+     */
+    pub fn notify_ppu_address (&mut self, address: u16, cycle: usize) {
+        self.mapper.notify_ppu_address(address, cycle);
+    }
+
+    /**
+     * Whether the mapper counts PPU bus activity to raise IRQs, in which case the PPU has to stay in step with the CPU while rendering
+     */
+    pub fn watches_ppu (&self) -> bool {
+        self.mapper.watches_ppu()
+    }
+
+    /**
+     * Whether the mapper is holding the IRQ line
+     */
+    pub fn irq (&self) -> bool {
+        self.mapper.irq()
+    }
+
+    /**
+     * Battery-backed PRG-RAM, holding game saves
+     */
+    pub fn sram (&self) -> Option<&[u8]> {
+        if self.ines.battery { Some(&self.prg_ram) } else { None }
+    }
+
+    pub fn set_sram (&mut self, sram: &[u8]) -> Result<(), StateError> {
+        if !self.ines.battery {
+            return Err(StateError::NoBattery);
+        }
+
+        if sram.len() != self.prg_ram.len() {
+            return Err(StateError::SramSize { expected: self.prg_ram.len(), actual: sram.len() });
+        }
+
+        self.prg_ram.copy_from_slice(sram);
+
+        Ok(())
+    }
+
+    /**
+     * FNV-1a hash of PRG-ROM, identifying the game a save state belongs to (CHR can be written to, even when declared as ROM)
+     */
+    pub fn checksum (&self) -> u32 {
+        self.prg_rom.iter().fold(0x811C_9DC5, |hash, &byte| (hash ^ byte as u32).wrapping_mul(0x0100_0193))
+    }
+
+    pub fn save_state (&self) -> CartridgeState {
+        CartridgeState {
+            prg_ram: self.prg_ram.clone(),
+            chr: self.chr.clone(),
+            mapper: self.mapper.save_state(),
+        }
+    }
+
+    /**
+     * Restore RAM and mapper registers, leaving the cartridge untouched if the state doesn't fit it
+     */
+    pub fn load_state (&mut self, state: CartridgeState) -> Result<(), StateError> {
+        if state.prg_ram.len() != self.prg_ram.len() {
+            return Err(StateError::Corrupted(format!("PRG-RAM size {} instead of {}", state.prg_ram.len(), self.prg_ram.len())));
+        }
+
+        if state.chr.len() != self.chr.len() {
+            return Err(StateError::Corrupted(format!("CHR size {} instead of {}", state.chr.len(), self.chr.len())));
+        }
+
+        let mut mapper = self.mapper.clone();
+        mapper.load_state(&state.mapper)?;
+
+        self.mapper = mapper;
+        self.prg_ram = state.prg_ram;
+        self.chr = state.chr;
+
+        // Game saves were replaced as well, and need persisting
+        if self.ines.battery {
+            self.sram_writes = self.sram_writes.wrapping_add(1);
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Cartridge {
+    /**
+     * Empty slot, holding the place of the real cartridge while a save state is restored
+     */
+    fn default () -> Self {
+        Cartridge {
+            prg_ram: vec![],
+            prg_rom: vec![],
+            chr: vec![],
+            mirroring: Mirroring::Horizontal,
+            mapper: Box::new(Mapper000::default()),
+            ines: InesHeader::parse(&[0; 16]).expect("An empty iNES header is valid"),
+            sram_writes: 0,
+        }
+    }
+}
+
+#[test]
+fn errors () {
+    let header = |mapper: u8| vec![b'N', b'E', b'S', 0x1A, 1, 1, mapper << 4, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+    assert_eq!(Cartridge::new(&[0; 8]).err(), Some(CartridgeError::TruncatedHeader));
+    assert_eq!(Cartridge::new(&[0; 16]).err(), Some(CartridgeError::InvalidMagic));
+    assert_eq!(Cartridge::new(&header(5)).err(), Some(CartridgeError::UnsupportedMapper(5)));
+    assert_eq!(Cartridge::new(&header(0)).err(), Some(CartridgeError::TruncatedPrg { expected: 0x4000, actual: 0 }));
+    assert_eq!(Cartridge::new(&[header(0), vec![0; 0x4000]].concat()).err(), Some(CartridgeError::TruncatedChr { expected: 0x2000, actual: 0 }));
+    assert!(Cartridge::new(&[header(0), vec![0; 0x6000]].concat()).is_ok());
+
+    // NES 2.0 header with PRG-ROM as 2^E * (MM * 2 + 1) bytes and no CHR-ROM
+    let nes2 = |mapper: u8, prg: u8| vec![b'N', b'E', b'S', 0x1A, prg, 0, mapper << 4, 0b0000_1000, 0, 0x0F, 0, 0, 0, 0, 0, 0];
+    let mut empty = header(0);
+    empty[4] = 0;
+    assert_eq!(Cartridge::new(&empty).err(), Some(CartridgeError::InvalidHeader("no PRG-ROM")));
+    // 2^63 bytes: more than the file has, or than fits in 32-bit memory
+    assert!(matches!(Cartridge::new(&nes2(0, 63 << 2)).err(), Some (CartridgeError::TruncatedPrg { .. }) | Some (CartridgeError::InvalidHeader(_))));
+    // MMC3 with a single 8 KiB bank, and AxROM with 16 KiB
+    assert_eq!(Cartridge::new(&[nes2(4, 13 << 2), vec![0; 0x2000]].concat()).err(), Some(CartridgeError::UnsupportedFeature("PRG-ROM size for this mapper")));
+    assert_eq!(Cartridge::new(&[nes2(7, 14 << 2), vec![0; 0x4000]].concat()).err(), Some(CartridgeError::UnsupportedFeature("PRG-ROM size for this mapper")));
+    assert!(Cartridge::new(&[nes2(4, 14 << 2), vec![0; 0x4000]].concat()).is_ok());
+}
+
+#[test]
+fn open_bus () {
+    let rom = [&[b'N', b'E', b'S', 0x1A, 2, 1, 4 << 4, 0, 0, 0, 0, 0, 0, 0, 0, 0][..], &[0xEA; 0x8000], &[0; 0x2000]].concat();
+    let mut cartridge = Cartridge::new(&rom).unwrap();
+
+    assert_eq!(cartridge.read_prg(0x5000), None);
+    assert_eq!(cartridge.read_prg(0x6000), Some(0));
+    assert_eq!(cartridge.read_prg(0x8000), Some(0xEA));
+    // MMC3 PRG-RAM disabled
+    cartridge.write_prg(0xA001, 0);
+    assert_eq!(cartridge.read_prg(0x6000), None);
+}
+
+#[test]
+fn sram_writes () {
+    let rom = [&[b'N', b'E', b'S', 0x1A, 2, 1, 4 << 4 | 0b0000_0010, 0, 0, 0, 0, 0, 0, 0, 0, 0][..], &[0xEA; 0x8000], &[0; 0x2000]].concat();
+    let mut cartridge = Cartridge::new(&rom).unwrap();
+
+    cartridge.write_prg(0x6000, 0x42);
+    assert_eq!(cartridge.sram_writes, 1);
+    // Same value
+    cartridge.write_prg(0x6000, 0x42);
+    assert_eq!(cartridge.sram_writes, 1);
+    // MMC3 PRG-RAM write protected, then disabled
+    cartridge.write_prg(0xA001, 0b1100_0000);
+    cartridge.write_prg(0x6000, 0x43);
+    cartridge.write_prg(0xA001, 0);
+    cartridge.write_prg(0x6000, 0x43);
+    assert_eq!(cartridge.sram_writes, 1);
 }
@@ -0,0 +1,44 @@
+use std::fmt;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+/**
+ * Reasons a ROM can't be loaded
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum CartridgeError {
+    InvalidMagic,
+    TruncatedHeader,
+    InvalidHeader(&'static str),
+    TruncatedTrainer,
+    TruncatedPrg { expected: usize, actual: usize },
+    TruncatedChr { expected: usize, actual: usize },
+    UnsupportedMapper(u16),
+    UnsupportedFeature(&'static str),
+}
+
+impl fmt::Display for CartridgeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CartridgeError::InvalidMagic                            => write!(f, "Not an iNES file (invalid header constant)"),
+            CartridgeError::TruncatedHeader                         => write!(f, "File is too short to contain an iNES header"),
+            CartridgeError::InvalidHeader(reason)                   => write!(f, "Invalid iNES header: {}", reason),
+            CartridgeError::TruncatedTrainer                        => write!(f, "File ends inside the trainer"),
+            CartridgeError::TruncatedPrg { expected, actual }       => write!(f, "Truncated PRG-ROM: expected {} bytes, found {}", expected, actual),
+            CartridgeError::TruncatedChr { expected, actual }       => write!(f, "Truncated CHR-ROM: expected {} bytes, found {}", expected, actual),
+            CartridgeError::UnsupportedMapper(mapper)               => write!(f, "Unsupported mapper ({})", mapper),
+            CartridgeError::UnsupportedFeature(feature)             => write!(f, "Unsupported feature: {}", feature),
+        }
+    }
+}
+
+impl std::error::Error for CartridgeError {}
+
+#[cfg(feature = "wasm")]
+impl From<CartridgeError> for JsValue {
+    fn from (error: CartridgeError) -> Self {
+        let js_error = js_sys::Error::new(&error.to_string());
+        js_error.set_name("CartridgeError");
+        js_error.into()
+    }
+}
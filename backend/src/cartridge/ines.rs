@@ -1,16 +1,194 @@
 /**
  * iNES format http://wiki.nesdev.com/w/index.php/INES
+ * NES 2.0 format https://wiki.nesdev.org/w/index.php/NES_2.0
  */
 
-use crate::cartridge::{ChrType, Mirroring};
+use crate::cartridge::{CartridgeError, ChrType, Mirroring};
+
+pub const PRG_BANK_SIZE: usize = 0x4000; // 16 KiB
+pub const CHR_BANK_SIZE: usize = 0x2000; // 8 KiB
+pub const RAM_BANK_SIZE: usize = 0x2000; // 8 KiB
+
+enum ControlFlag1 {
+    Vertical    =   0b0000_0001,
+    Battery     =   0b0000_0010,
+    Trainer     =   0b0000_0100,
+    FourScreen  =   0b0000_1000,
+    Mapper      =   0b1111_0000, // Mapper bits 0-3
+}
+
+enum ControlFlag2 {
+    _Console    =   0b0000_0011,
+    Nes2        =   0b0000_1100, // 0b10 identifies NES 2.0
+    Mapper      =   0b1111_0000, // Mapper bits 4-7
+}
+
+#[derive(Debug, PartialEq, Copy, Clone, serde::Serialize)]
+pub enum Timing {
+    NTSC,
+    PAL,
+    Multiple, // Runs on both NTSC and PAL
+    Dendy,
+}
 
 #[derive(Copy, Clone, serde::Serialize)]
 pub struct InesHeader {
-    pub prg_banks: usize,
-    pub chr_banks: usize,
+    pub nes2: bool,
+    pub mapper: u16,
+    pub submapper: u8,
+    pub prg_rom_size: usize,
+    pub chr_rom_size: usize,
     pub chr_type: ChrType,
-    pub mapper: u8,
+    pub prg_ram_size: usize,
+    pub prg_nvram_size: usize,
+    pub chr_ram_size: usize,
+    pub chr_nvram_size: usize,
     pub trainer: bool,
-    pub ram: bool,
+    pub battery: bool,
     pub mirroring: Mirroring,
+    pub timing: Timing,
+    pub expansion: u8, // Default expansion device
+}
+
+impl InesHeader {
+    /**
+     * Fails with an InvalidHeader error when a ROM size doesn't fit in memory
+     */
+    pub fn parse (header: &[u8; 16]) -> Result<Self, CartridgeError> {
+        let nes2 = (header[7] & ControlFlag2::Nes2 as u8) == 0b0000_1000;
+        // Some old dumps have garbage such as "DiskDude!" in bytes 7-15, so the upper mapper nibble can't be trusted
+        let archaic = !nes2 && header[12..16].iter().any(|&byte| byte != 0);
+        let mut mapper = ((header[6] & ControlFlag1::Mapper as u8) >> 4) as u16;
+
+        if !archaic {
+            mapper |= (header[7] & ControlFlag2::Mapper as u8) as u16;
+        }
+
+        let mirroring = match (header[6] & ControlFlag1::FourScreen as u8 != 0, header[6] & ControlFlag1::Vertical as u8 != 0) {
+            (true, _)       => Mirroring::FourScreen,
+            (false, false)  => Mirroring::Horizontal,
+            (false, true)   => Mirroring::Vertical,
+        };
+        let trainer = (header[6] & ControlFlag1::Trainer as u8) != 0;
+        let battery = (header[6] & ControlFlag1::Battery as u8) != 0;
+
+        if nes2 {
+            let prg_rom_size = InesHeader::rom_size(header[4], header[9] & 0b0000_1111, PRG_BANK_SIZE).ok_or(CartridgeError::InvalidHeader("PRG-ROM size overflows"))?;
+            let chr_rom_size = InesHeader::rom_size(header[5], header[9] >> 4, CHR_BANK_SIZE).ok_or(CartridgeError::InvalidHeader("CHR-ROM size overflows"))?;
+
+            Ok(InesHeader {
+                nes2,
+                mapper: mapper | ((header[8] & 0b0000_1111) as u16) << 8,
+                submapper: header[8] >> 4,
+                prg_rom_size,
+                chr_rom_size,
+                chr_type: if chr_rom_size > 0 { ChrType::ROM } else { ChrType::RAM },
+                prg_ram_size: InesHeader::ram_size(header[10] & 0b0000_1111),
+                prg_nvram_size: InesHeader::ram_size(header[10] >> 4),
+                chr_ram_size: InesHeader::ram_size(header[11] & 0b0000_1111),
+                chr_nvram_size: InesHeader::ram_size(header[11] >> 4),
+                trainer,
+                battery,
+                mirroring,
+                timing: match header[12] & 0b0000_0011 {
+                    0 => Timing::NTSC,
+                    1 => Timing::PAL,
+                    2 => Timing::Multiple,
+                    3 => Timing::Dendy,
+                    _ => unreachable!(),
+                },
+                expansion: header[15] & 0b0011_1111,
+            })
+        } else {
+            let chr_rom_size = header[5] as usize * CHR_BANK_SIZE;
+            // Value 0 infers 8 KB for compatibility
+            let ram_size = std::cmp::max(header[8] as usize, 1) * RAM_BANK_SIZE;
+
+            Ok(InesHeader {
+                nes2,
+                mapper,
+                submapper: 0,
+                prg_rom_size: header[4] as usize * PRG_BANK_SIZE,
+                chr_rom_size,
+                chr_type: if chr_rom_size > 0 { ChrType::ROM } else { ChrType::RAM },
+                prg_ram_size: if battery { 0 } else { ram_size },
+                prg_nvram_size: if battery { ram_size } else { 0 },
+                chr_ram_size: if chr_rom_size > 0 { 0 } else { CHR_BANK_SIZE },
+                chr_nvram_size: 0,
+                trainer,
+                battery,
+                mirroring,
+                timing: if !archaic && (header[9] & 0b0000_0001) > 0 { Timing::PAL } else { Timing::NTSC },
+                expansion: 0,
+            })
+        }
+    }
+
+    /**
+     * ROM size from its LSB and MSB nibble, either as a bank count or as 2^E * (MM * 2 + 1) bytes when the MSB nibble is $F
+     * None when it overflows, since E goes up to 63
+     */
+    fn rom_size (lsb: u8, msb: u8, bank_size: usize) -> Option<usize> {
+        if msb == 0b0000_1111 {
+            let exponent = lsb >> 2;
+            let multiplier = (lsb & 0b0000_0011) as usize * 2 + 1;
+
+            1usize.checked_shl(exponent as u32)?.checked_mul(multiplier)
+        } else {
+            ((msb as usize) << 8 | lsb as usize).checked_mul(bank_size)
+        }
+    }
+
+    /**
+     * RAM size from its shift count, 0 meaning none
+     */
+    fn ram_size (shift: u8) -> usize {
+        if shift == 0 { 0 } else { 64 << shift }
+    }
+}
+
+#[test]
+fn nes2 () {
+    let header = InesHeader::parse(&[
+        b'N', b'E', b'S', 0x1A,
+        0x02, // 2 * 16 KiB PRG-ROM
+        0x00, // CHR-RAM
+        0b0100_0011, // Mapper 4, battery, vertical mirroring
+        0b0000_1000, // NES 2.0
+        0b0001_0001, // Submapper 1, mapper 260
+        0x00,
+        0x70, // 8 KiB PRG-NVRAM
+        0x07, // 8 KiB CHR-RAM
+        0x01, // PAL
+        0x00,
+        0x00,
+        0x01, // Standard controllers
+    ]).unwrap();
+
+    assert!(header.nes2);
+    assert_eq!(header.mapper, 260);
+    assert_eq!(header.submapper, 1);
+    assert_eq!(header.prg_rom_size, 0x8000);
+    assert_eq!(header.chr_rom_size, 0);
+    assert_eq!(header.chr_type, ChrType::RAM);
+    assert_eq!(header.prg_ram_size, 0);
+    assert_eq!(header.prg_nvram_size, 0x2000);
+    assert_eq!(header.chr_ram_size, 0x2000);
+    assert!(header.battery);
+    assert_eq!(header.mirroring, Mirroring::Vertical);
+    assert_eq!(header.timing, Timing::PAL);
+    assert_eq!(header.expansion, 1);
+
+    // Exponent-multiplier notation: 2^10 * 3 bytes
+    assert_eq!(InesHeader::rom_size(0b0010_1001, 0x0F, PRG_BANK_SIZE), Some(3072));
+    // 2^32 only fits in 64-bit memory, 2^63 * 3 nowhere
+    use std::convert::TryFrom;
+    assert_eq!(InesHeader::rom_size(0b1000_0000, 0x0F, PRG_BANK_SIZE), usize::try_from(1u64 << 32).ok());
+    assert_eq!(InesHeader::rom_size(0b1111_1101, 0x0F, PRG_BANK_SIZE), None);
+
+    let mut overflowing = [b'N', b'E', b'S', 0x1A, 0b1111_1101, 0, 0, 0b0000_1000, 0, 0x0F, 0, 0, 0, 0, 0, 0];
+    assert_eq!(InesHeader::parse(&overflowing).err(), Some(CartridgeError::InvalidHeader("PRG-ROM size overflows")));
+    overflowing[9] = 0xF0;
+    overflowing[5] = 0b1111_1101;
+    assert_eq!(InesHeader::parse(&overflowing).err(), Some(CartridgeError::InvalidHeader("CHR-ROM size overflows")));
 }
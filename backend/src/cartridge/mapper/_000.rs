@@ -4,7 +4,7 @@
 
 use crate::cartridge::{Mirroring, Bank};
 
-#[derive(Default)]
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct Mapper000 {}
 
 impl super::Mapper for Mapper000 {
@@ -16,16 +16,16 @@ impl super::Mapper for Mapper000 {
         chr[address as usize] = data;
     }
 
-    fn read_prg (&self, address: u16, prg_ram: &Vec<u8>, prg_rom: &Vec<u8>) -> u8 {
+    fn read_prg (&self, address: u16, prg_ram: &Vec<u8>, prg_rom: &Vec<u8>) -> Option<u8> {
         match address {
             0x4020 ..= 0x5FFF => {
-                0
+                None // Open bus
             },
             0x6000 ..= 0x7FFF => {
-                prg_ram[(address as usize - 0x6000) % prg_ram.len()]
+                Some(prg_ram[(address as usize - 0x6000) % prg_ram.len()])
             },
             0x8000 ..= 0xFFFF => {
-                prg_rom[(address as usize - 0x8000) % prg_rom.len()]
+                Some(prg_rom[(address as usize - 0x8000) % prg_rom.len()])
             },
             _ => panic!("Invalid PRG read {:#x}", address),
         }
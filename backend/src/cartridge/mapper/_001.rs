@@ -4,6 +4,7 @@
 
 use crate::cartridge::{Mirroring, Bank};
 
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct Mapper001 {
     prg_bank: u8,
     chr_bank_0: u8,
@@ -17,58 +18,75 @@ impl Mapper001 {
     const PRG_WINDOW_LARGE: usize   = 0x8000; // 32 KiB
     const CHR_WINDOW: usize         = 0x1000; // 4 KiB
     const CHR_WINDOW_LARGE: usize   = 0x2000; // 8 KiB
-}
 
-impl super::Mapper for Mapper001 {
-    fn read_chr (&self, address: u16, chr: &Vec<u8>) -> u8 {
-        match (self.ctrl & 0b0001_0000) >> 4 {
-            0 => chr[((self.chr_bank_0 & 0b0001_1110) as usize * Mapper001::CHR_WINDOW_LARGE) + (address as usize % Mapper001::CHR_WINDOW_LARGE)],
+    /**
+     * Offset in CHR of an address, banks wrapping around to the ones there are
+     */
+    fn chr_address (&self, address: u16, chr: &[u8]) -> usize {
+        let mapped_address = match (self.ctrl & 0b0001_0000) >> 4 {
+            0 => ((self.chr_bank_0 & 0b0001_1110) as usize * Mapper001::CHR_WINDOW_LARGE) + (address as usize % Mapper001::CHR_WINDOW_LARGE),
             1 => match address {
-                0x0000 ..= 0x0FFF => chr[(self.chr_bank_0 as usize * Mapper001::CHR_WINDOW) + (address as usize % Mapper001::CHR_WINDOW)],
-                0x1000 ..= 0x1FFF => chr[(self.chr_bank_1 as usize * Mapper001::CHR_WINDOW) + (address as usize % Mapper001::CHR_WINDOW)],
+                0x0000 ..= 0x0FFF => (self.chr_bank_0 as usize * Mapper001::CHR_WINDOW) + (address as usize % Mapper001::CHR_WINDOW),
+                0x1000 ..= 0x1FFF => (self.chr_bank_1 as usize * Mapper001::CHR_WINDOW) + (address as usize % Mapper001::CHR_WINDOW),
                 _ => unreachable!(),
             },
             _ => unreachable!(),
-        }
+        };
+
+        mapped_address % chr.len()
     }
 
-    fn write_chr (&mut self, address: u16, data: u8, chr: &mut Vec<u8>) {
-        let mapped_address = match (self.ctrl & 0b0001_0000) >> 4 {
-            0 => ((self.chr_bank_0 & 0b0001_1110) as usize * Mapper001::CHR_WINDOW_LARGE) + (address as usize % Mapper001::CHR_WINDOW_LARGE),
-            1 => match address {
-                0x0000 ..= 0x0FFF => (self.chr_bank_0 as usize * Mapper001::CHR_WINDOW) + (address as usize % Mapper001::CHR_WINDOW),
-                0x1000 ..= 0x1FFF => (self.chr_bank_1 as usize * Mapper001::CHR_WINDOW) + (address as usize % Mapper001::CHR_WINDOW),
+    /**
+     * Offset in PRG-ROM of an address in $8000-$FFFF, banks wrapping around to the ones there are
+     */
+    fn prg_address (&self, address: u16, prg_rom: &[u8]) -> usize {
+        let mapped_address = match (self.ctrl & 0b0000_1100) >> 2 {
+            0b00 | 0b01 => ((self.prg_bank & 0b0001_1110) as usize * Mapper001::PRG_WINDOW_LARGE) + (address as usize % Mapper001::PRG_WINDOW_LARGE),
+            0b10 => match address {
+                0x8000 ..= 0xBFFF => address as usize % Mapper001::PRG_WINDOW,
+                0xC000 ..= 0xFFFF => (self.prg_bank as usize * Mapper001::PRG_WINDOW) + (address as usize % Mapper001::PRG_WINDOW),
+                _ => unreachable!(),
+            },
+            0b11 => match address {
+                0x8000 ..= 0xBFFF => (self.prg_bank as usize * Mapper001::PRG_WINDOW) + (address as usize % Mapper001::PRG_WINDOW),
+                0xC000 ..= 0xFFFF => (prg_rom.len() - Mapper001::PRG_WINDOW) + (address as usize % Mapper001::PRG_WINDOW),
                 _ => unreachable!(),
             },
             _ => unreachable!(),
         };
 
+        mapped_address % prg_rom.len()
+    }
+}
+
+impl super::Mapper for Mapper001 {
+    fn read_chr (&self, address: u16, chr: &Vec<u8>) -> u8 {
+        chr[self.chr_address(address, chr)]
+    }
+
+    fn write_chr (&mut self, address: u16, data: u8, chr: &mut Vec<u8>) {
+        let mapped_address = self.chr_address(address, chr);
+
         chr[mapped_address] = data;
     }
 
-    fn read_prg (&self, address: u16, prg_ram: &Vec<u8>, prg_rom: &Vec<u8>) -> u8 {
+    /**
+     * Whole banks, the last one being fixed
+     */
+    fn supports_prg_rom (&self, size: usize) -> bool {
+        size >= Mapper001::PRG_WINDOW && size.is_multiple_of(Mapper001::PRG_WINDOW)
+    }
+
+    fn read_prg (&self, address: u16, prg_ram: &Vec<u8>, prg_rom: &Vec<u8>) -> Option<u8> {
         match address {
             0x4020 ..= 0x5FFF => {
-                0
+                None // Open bus
             },
             0x6000 ..= 0x7FFF => {
-                prg_ram[(address as usize - 0x6000) % prg_ram.len()]
+                Some(prg_ram[(address as usize - 0x6000) % prg_ram.len()])
             },
             0x8000 ..= 0xFFFF => {
-                match (self.ctrl & 0b0000_1100) >> 2 {
-                    0b00 | 0b01 => prg_rom[((self.prg_bank & 0b0001_1110) as usize * Mapper001::PRG_WINDOW_LARGE) + (address as usize % Mapper001::PRG_WINDOW_LARGE)],
-                    0b10 => match address {
-                        0x8000 ..= 0xBFFF => prg_rom[address as usize % Mapper001::PRG_WINDOW],
-                        0xC000 ..= 0xFFFF => prg_rom[(self.prg_bank as usize * Mapper001::PRG_WINDOW) + (address as usize % Mapper001::PRG_WINDOW)],
-                        _ => unreachable!(),
-                    },
-                    0b11 => match address {
-                        0x8000 ..= 0xBFFF => prg_rom[(self.prg_bank as usize * Mapper001::PRG_WINDOW) + (address as usize % Mapper001::PRG_WINDOW)],
-                        0xC000 ..= 0xFFFF => prg_rom[(prg_rom.len() - Mapper001::PRG_WINDOW) + (address as usize % Mapper001::PRG_WINDOW)],
-                        _ => unreachable!(),
-                    },
-                    _ => unreachable!(),
-                }
+                Some(prg_rom[self.prg_address(address, prg_rom)])
             },
             _ => panic!("Invalid PRG read {:#x}", address),
         }
@@ -77,20 +95,7 @@ impl super::Mapper for Mapper001 {
     fn peek_prg (&self, address: u16, prg_ram: &Vec<u8>, prg_rom: &Vec<u8>) -> Option<u8> {
         match address {
             0x6000 ..= 0x7FFF => Some(prg_ram[(address as usize - 0x6000) % prg_ram.len()]),
-            0x8000 ..= 0xFFFF => Some(match (self.ctrl & 0b0000_1100) >> 2 {
-                0b00 | 0b01 => prg_rom[((self.prg_bank & 0b0001_1110) as usize * Mapper001::PRG_WINDOW_LARGE) + (address as usize % Mapper001::PRG_WINDOW_LARGE)],
-                0b10 => match address {
-                    0x8000 ..= 0xBFFF => prg_rom[address as usize % Mapper001::PRG_WINDOW],
-                    0xC000 ..= 0xFFFF => prg_rom[(self.prg_bank as usize * Mapper001::PRG_WINDOW) + (address as usize % Mapper001::PRG_WINDOW)],
-                    _ => unreachable!(),
-                },
-                0b11 => match address {
-                    0x8000 ..= 0xBFFF => prg_rom[(self.prg_bank as usize * Mapper001::PRG_WINDOW) + (address as usize % Mapper001::PRG_WINDOW)],
-                    0xC000 ..= 0xFFFF => prg_rom[(prg_rom.len() - Mapper001::PRG_WINDOW) + (address as usize % Mapper001::PRG_WINDOW)],
-                    _ => unreachable!(),
-                },
-                _ => unreachable!(),
-            }),
+            0x8000 ..= 0xFFFF => Some(prg_rom[self.prg_address(address, prg_rom)]),
             _ => None,
         }
     }
@@ -4,12 +4,20 @@
 
 use crate::cartridge::{Mirroring, Bank};
 
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct Mapper002 {
     prg_bank: u8,
 }
 
 impl Mapper002 {
     const PRG_WINDOW: usize = 0x4000; // 16 KiB
+
+    /**
+     * Switchable bank, wrapped to the banks there are
+     */
+    fn prg_bank (&self, prg_rom: &[u8]) -> usize {
+        self.prg_bank as usize % (prg_rom.len() / Mapper002::PRG_WINDOW)
+    }
 }
 
 impl super::Mapper for Mapper002 {
@@ -21,16 +29,23 @@ impl super::Mapper for Mapper002 {
         chr[address as usize] = data;
     }
 
-    fn read_prg (&self, address: u16, _prg_ram: &Vec<u8>, prg_rom: &Vec<u8>) -> u8 {
+    /**
+     * Whole banks, the last one being fixed
+     */
+    fn supports_prg_rom (&self, size: usize) -> bool {
+        size >= Mapper002::PRG_WINDOW && size.is_multiple_of(Mapper002::PRG_WINDOW)
+    }
+
+    fn read_prg (&self, address: u16, _prg_ram: &Vec<u8>, prg_rom: &Vec<u8>) -> Option<u8> {
         match address {
-            0x4020 ..= 0x5FFF => {
-                0
+            0x4020 ..= 0x7FFF => {
+                None // Open bus
             },
             0x8000 ..= 0xBFFF => {
-                prg_rom[(self.prg_bank as usize * Mapper002::PRG_WINDOW) + (address as usize % Mapper002::PRG_WINDOW)]
+                Some(prg_rom[(self.prg_bank(prg_rom) * Mapper002::PRG_WINDOW) + (address as usize % Mapper002::PRG_WINDOW)])
             },
             0xC000 ..= 0xFFFF => {
-                prg_rom[(prg_rom.len() - Mapper002::PRG_WINDOW) + (address as usize % Mapper002::PRG_WINDOW)]
+                Some(prg_rom[(prg_rom.len() - Mapper002::PRG_WINDOW) + (address as usize % Mapper002::PRG_WINDOW)])
             },
             _ => panic!("Invalid PRG read {:#x}", address),
         }
@@ -38,7 +53,7 @@ impl super::Mapper for Mapper002 {
 
     fn peek_prg (&self, address: u16, _prg_ram: &Vec<u8>, prg_rom: &Vec<u8>) -> Option<u8> {
         match address {
-            0x8000 ..= 0xBFFF => Some(prg_rom[(self.prg_bank as usize * Mapper002::PRG_WINDOW) + (address as usize % Mapper002::PRG_WINDOW)]),
+            0x8000 ..= 0xBFFF => Some(prg_rom[(self.prg_bank(prg_rom) * Mapper002::PRG_WINDOW) + (address as usize % Mapper002::PRG_WINDOW)]),
             0xC000 ..= 0xFFFF => Some(prg_rom[(prg_rom.len() - Mapper002::PRG_WINDOW) + (address as usize % Mapper002::PRG_WINDOW)]),
             _ => None,
         }
@@ -59,7 +74,7 @@ impl super::Mapper for Mapper002 {
 
     fn get_current_prg (&self, prg_rom: &Vec<u8>) -> Vec<Bank> {
         vec![
-            Bank { number: self.prg_bank, size: Mapper002::PRG_WINDOW }, 
+            Bank { number: self.prg_bank(prg_rom) as u8, size: Mapper002::PRG_WINDOW },
             Bank { number: (prg_rom.len() / Mapper002::PRG_WINDOW) as u8 - 1, size: Mapper002::PRG_WINDOW },
         ]
     }
@@ -70,7 +85,7 @@ impl super::Mapper for Mapper002 {
 
     fn get_bank_at (&self, prg_rom: &Vec<u8>, address: u16) -> u8 {
         match address {
-            0x8000 ..= 0xBFFF => self.prg_bank,
+            0x8000 ..= 0xBFFF => self.prg_bank(prg_rom) as u8,
             0xC000 ..= 0xFFFF => (prg_rom.len() / Mapper002::PRG_WINDOW) as u8 - 1,
             _ => unreachable!(),
         }
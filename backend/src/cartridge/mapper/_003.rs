@@ -4,30 +4,40 @@
 
 use crate::cartridge::{Mirroring, Bank};
 
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct Mapper003 {
     chr_bank: u8,
 }
 
 impl Mapper003 {
     const CHR_WINDOW: usize = 0x2000; // 8 KiB
+
+    /**
+     * Selected bank, wrapped to the banks there are
+     */
+    fn chr_bank (&self, chr: &[u8]) -> usize {
+        self.chr_bank as usize % (chr.len() / Mapper003::CHR_WINDOW)
+    }
 }
 
 impl super::Mapper for Mapper003 {
     fn read_chr (&self, address: u16, chr: &Vec<u8>) -> u8 {
-        chr[(self.chr_bank as usize * Mapper003::CHR_WINDOW) + (address as usize % Mapper003::CHR_WINDOW)]
+        chr[(self.chr_bank(chr) * Mapper003::CHR_WINDOW) + (address as usize % Mapper003::CHR_WINDOW)]
     }
 
     fn write_chr (&mut self, address: u16, data: u8, chr: &mut Vec<u8>) {
-        chr[(self.chr_bank as usize * Mapper003::CHR_WINDOW) + (address as usize % Mapper003::CHR_WINDOW)] = data;
+        let bank = self.chr_bank(chr);
+
+        chr[(bank * Mapper003::CHR_WINDOW) + (address as usize % Mapper003::CHR_WINDOW)] = data;
     }
 
-    fn read_prg (&self, address: u16, _prg_ram: &Vec<u8>, prg_rom: &Vec<u8>) -> u8 {
+    fn read_prg (&self, address: u16, _prg_ram: &Vec<u8>, prg_rom: &Vec<u8>) -> Option<u8> {
         match address {
-            0x4020 ..= 0x5FFF => {
-                0
+            0x4020 ..= 0x7FFF => {
+                None // Open bus
             },
             0x8000 ..= 0xFFFF => {
-                prg_rom[(address as usize - 0x8000) % prg_rom.len()]
+                Some(prg_rom[(address as usize - 0x8000) % prg_rom.len()])
             },
             _ => panic!("Invalid PRG read {:#x}", address),
         }
@@ -57,8 +67,8 @@ impl super::Mapper for Mapper003 {
         vec![Bank { number: 0, size: prg_rom.len() }]
     }
 
-    fn get_current_chr (&self, _chr: &Vec<u8>) -> Vec<Bank> {
-        vec![Bank { number: self.chr_bank, size: Mapper003::CHR_WINDOW }]
+    fn get_current_chr (&self, chr: &Vec<u8>) -> Vec<Bank> {
+        vec![Bank { number: self.chr_bank(chr) as u8, size: Mapper003::CHR_WINDOW }]
     }
 
     fn get_bank_at (&self, _prg_rom: &Vec<u8>, address: u16) -> u8 {
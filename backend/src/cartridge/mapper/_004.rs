@@ -0,0 +1,250 @@
+//! https://wiki.nesdev.org/w/index.php/MMC3
+
+use crate::cartridge::{Mirroring, Bank};
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct Mapper004 {
+    registers: [u8; 8], // R0-R5 select CHR banks, R6-R7 select PRG banks
+    bank_select: u8,
+    mirroring: u8,
+    ram_protect: u8,
+    irq_latch: u8,
+    irq_counter: u8,
+    irq_reload: bool,
+    irq_enabled: bool,
+    irq: bool,
+    a12: bool,
+    a12_low_since: usize, // PPU cycle A12 last went low
+    old_irq: bool, // MMC3A and MMC6: no IRQ when a counter that reached 0 is reloaded with 0
+}
+
+impl Mapper004 {
+    const PRG_WINDOW: usize = 0x2000; // 8 KiB
+    const CHR_WINDOW: usize = 0x0400; // 1 KiB
+    const A12_FILTER: usize = 10; // A12 must stay low for about 3 CPU cycles before a rise is counted
+
+    /**
+     * NES 2.0 submappers 1 (MMC6, whose 1 KiB PRG-RAM isn't emulated) and 4 (MMC3A) have the old IRQ behavior
+     */
+    pub fn new (submapper: u8) -> Self {
+        Mapper004 {
+            old_irq: matches!(submapper, 1 | 4),
+            ..Mapper004::default()
+        }
+    }
+
+    /**
+     * Bank number mapped in the 8 KiB PRG window containing the address
+     */
+    fn prg_bank (&self, address: u16, prg_rom: &[u8]) -> usize {
+        let banks = prg_rom.len() / Mapper004::PRG_WINDOW;
+        let second_last = banks - 2;
+        let bank = match ((self.bank_select & 0b0100_0000) >> 6, address) {
+            (0, 0x8000 ..= 0x9FFF) => self.registers[6] as usize,
+            (1, 0x8000 ..= 0x9FFF) => second_last,
+            (_, 0xA000 ..= 0xBFFF) => self.registers[7] as usize,
+            (0, 0xC000 ..= 0xDFFF) => second_last,
+            (1, 0xC000 ..= 0xDFFF) => self.registers[6] as usize,
+            (_, 0xE000 ..= 0xFFFF) => banks - 1,
+            _ => unreachable!(),
+        };
+
+        bank % banks
+    }
+
+    /**
+     * Bank number mapped in the 1 KiB CHR window containing the address
+     */
+    fn chr_bank (&self, address: u16, chr: &[u8]) -> usize {
+        // Inversion swaps the 2 KiB and 1 KiB halves of the pattern tables
+        let address = if (self.bank_select & 0b1000_0000) > 0 { address ^ 0x1000 } else { address };
+        let bank = match address {
+            0x0000 ..= 0x07FF => (self.registers[0] & 0b1111_1110) as usize + (address as usize / Mapper004::CHR_WINDOW) % 2,
+            0x0800 ..= 0x0FFF => (self.registers[1] & 0b1111_1110) as usize + (address as usize / Mapper004::CHR_WINDOW) % 2,
+            0x1000 ..= 0x13FF => self.registers[2] as usize,
+            0x1400 ..= 0x17FF => self.registers[3] as usize,
+            0x1800 ..= 0x1BFF => self.registers[4] as usize,
+            0x1C00 ..= 0x1FFF => self.registers[5] as usize,
+            _ => unreachable!(),
+        };
+
+        bank % (chr.len() / Mapper004::CHR_WINDOW)
+    }
+
+    fn ram_enabled (&self) -> bool {
+        (self.ram_protect & 0b1000_0000) > 0
+    }
+
+    fn ram_writable (&self) -> bool {
+        self.ram_enabled() && (self.ram_protect & 0b0100_0000) == 0
+    }
+
+    /**
+     * Clock the scanline counter, asserting IRQ when it reaches 0
+     * https://wiki.nesdev.org/w/index.php/MMC3#IRQ_Specifics
+     */
+    fn clock_counter (&mut self) {
+        // The old behavior only raises IRQ when decrementing to 0, or when reloading after a write to $C001
+        let raises = !self.old_irq || self.irq_counter > 0 || self.irq_reload;
+
+        if self.irq_counter == 0 || self.irq_reload {
+            self.irq_counter = self.irq_latch;
+            self.irq_reload = false;
+        } else {
+            self.irq_counter -= 1;
+        }
+
+        if self.irq_counter == 0 && self.irq_enabled && raises {
+            self.irq = true;
+        }
+    }
+}
+
+impl super::Mapper for Mapper004 {
+    fn read_chr (&self, address: u16, chr: &Vec<u8>) -> u8 {
+        chr[(self.chr_bank(address, chr) * Mapper004::CHR_WINDOW) + (address as usize % Mapper004::CHR_WINDOW)]
+    }
+
+    fn write_chr (&mut self, address: u16, data: u8, chr: &mut Vec<u8>) {
+        let mapped_address = (self.chr_bank(address, chr) * Mapper004::CHR_WINDOW) + (address as usize % Mapper004::CHR_WINDOW);
+        chr[mapped_address] = data;
+    }
+
+    /**
+     * Whole banks, the last two being fixed
+     */
+    fn supports_prg_rom (&self, size: usize) -> bool {
+        size >= 2 * Mapper004::PRG_WINDOW && size.is_multiple_of(Mapper004::PRG_WINDOW)
+    }
+
+    fn read_prg (&self, address: u16, prg_ram: &Vec<u8>, prg_rom: &Vec<u8>) -> Option<u8> {
+        match address {
+            0x4020 ..= 0x5FFF => {
+                None // Open bus
+            },
+            0x6000 ..= 0x7FFF => {
+                if self.ram_enabled() {
+                    Some(prg_ram[(address as usize - 0x6000) % prg_ram.len()])
+                } else {
+                    None // Open bus
+                }
+            },
+            0x8000 ..= 0xFFFF => {
+                Some(prg_rom[(self.prg_bank(address, prg_rom) * Mapper004::PRG_WINDOW) + (address as usize % Mapper004::PRG_WINDOW)])
+            },
+            _ => panic!("Invalid PRG read {:#x}", address),
+        }
+    }
+
+    fn peek_prg (&self, address: u16, prg_ram: &Vec<u8>, prg_rom: &Vec<u8>) -> Option<u8> {
+        match address {
+            0x6000 ..= 0x7FFF if self.ram_enabled() => Some(prg_ram[(address as usize - 0x6000) % prg_ram.len()]),
+            0x8000 ..= 0xFFFF => Some(prg_rom[(self.prg_bank(address, prg_rom) * Mapper004::PRG_WINDOW) + (address as usize % Mapper004::PRG_WINDOW)]),
+            _ => None,
+        }
+    }
+
+    fn write_prg (&mut self, address: u16, data: u8, prg_ram: &mut Vec<u8>) {
+        match (address, address % 2) {
+            (0x6000 ..= 0x7FFF, _) => {
+                if self.ram_writable() {
+                    let index = (address as usize - 0x6000) % prg_ram.len();
+                    prg_ram[index] = data;
+                }
+            },
+            (0x8000 ..= 0x9FFF, 0) => {
+                self.bank_select = data;
+            },
+            (0x8000 ..= 0x9FFF, 1) => {
+                self.registers[(self.bank_select & 0b0000_0111) as usize] = data;
+            },
+            (0xA000 ..= 0xBFFF, 0) => {
+                self.mirroring = data;
+            },
+            (0xA000 ..= 0xBFFF, 1) => {
+                self.ram_protect = data;
+            },
+            (0xC000 ..= 0xDFFF, 0) => {
+                self.irq_latch = data;
+            },
+            (0xC000 ..= 0xDFFF, 1) => {
+                self.irq_counter = 0;
+                self.irq_reload = true;
+            },
+            (0xE000 ..= 0xFFFF, 0) => {
+                // Disabling also acknowledges any pending interrupt
+                self.irq_enabled = false;
+                self.irq = false;
+            },
+            (0xE000 ..= 0xFFFF, 1) => {
+                self.irq_enabled = true;
+            },
+            _ => log::warn!("Invalid PRG write {:#x}", address),
+        }
+    }
+
+    fn get_mirroring (&self) -> Option<Mirroring> {
+        match self.mirroring & 0b0000_0001 {
+            0 => Some(Mirroring::Vertical),
+            1 => Some(Mirroring::Horizontal),
+            _ => unreachable!(),
+        }
+    }
+
+    fn notify_ppu_address (&mut self, address: u16, cycle: usize) {
+        let a12 = (address & 0x1000) > 0;
+
+        if a12 && !self.a12 && cycle.wrapping_sub(self.a12_low_since) >= Mapper004::A12_FILTER {
+            self.clock_counter();
+        } else if !a12 && self.a12 {
+            self.a12_low_since = cycle;
+        }
+
+        self.a12 = a12;
+    }
+
+    fn irq (&self) -> bool {
+        self.irq
+    }
+
+    fn watches_ppu (&self) -> bool {
+        true
+    }
+
+    fn get_current_prg (&self, prg_rom: &Vec<u8>) -> Vec<Bank> {
+        [0x8000, 0xA000, 0xC000, 0xE000].iter().map(|&address| Bank {
+            number: self.prg_bank(address, prg_rom) as u8,
+            size: Mapper004::PRG_WINDOW,
+        }).collect()
+    }
+
+    fn get_current_chr (&self, chr: &Vec<u8>) -> Vec<Bank> {
+        (0 .. 8).map(|window| Bank {
+            number: self.chr_bank(window * Mapper004::CHR_WINDOW as u16, chr) as u8,
+            size: Mapper004::CHR_WINDOW,
+        }).collect()
+    }
+
+    fn get_bank_at (&self, prg_rom: &Vec<u8>, address: u16) -> u8 {
+        self.prg_bank(address, prg_rom) as u8
+    }
+}
+
+impl Default for Mapper004 {
+    fn default () -> Self {
+        Mapper004 {
+            registers: [0, 2, 4, 5, 6, 7, 0, 1],
+            bank_select: 0,
+            mirroring: 0,
+            ram_protect: 0b1000_0000,
+            irq_latch: 0,
+            irq_counter: 0,
+            irq_reload: false,
+            irq_enabled: false,
+            irq: false,
+            a12: false,
+            a12_low_since: 0,
+            old_irq: false,
+        }
+    }
+}
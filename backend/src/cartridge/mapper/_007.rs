@@ -4,6 +4,7 @@
 
 use crate::cartridge::{Mirroring, Bank};
 
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct Mapper007 {
     prg_bank: u8,
     mirroring: Option<Mirroring>,
@@ -11,6 +12,13 @@ pub struct Mapper007 {
 
 impl Mapper007 {
     const PRG_WINDOW: usize = 0x8000; // 32 KiB
+
+    /**
+     * Selected bank, wrapped to the banks there are
+     */
+    fn prg_bank (&self, prg_rom: &[u8]) -> usize {
+        self.prg_bank as usize % (prg_rom.len() / Mapper007::PRG_WINDOW)
+    }
 }
 
 impl super::Mapper for Mapper007 {
@@ -22,13 +30,20 @@ impl super::Mapper for Mapper007 {
         chr[address as usize] = data;
     }
 
-    fn read_prg (&self, address: u16, _prg_ram: &Vec<u8>, prg_rom: &Vec<u8>) -> u8 {
+    /**
+     * Whole 32 KiB banks
+     */
+    fn supports_prg_rom (&self, size: usize) -> bool {
+        size >= Mapper007::PRG_WINDOW && size.is_multiple_of(Mapper007::PRG_WINDOW)
+    }
+
+    fn read_prg (&self, address: u16, _prg_ram: &Vec<u8>, prg_rom: &Vec<u8>) -> Option<u8> {
         match address {
-            0x4020 ..= 0x5FFF => {
-                0
+            0x4020 ..= 0x7FFF => {
+                None // Open bus
             },
             0x8000 ..= 0xFFFF => {
-                prg_rom[(self.prg_bank as usize * Mapper007::PRG_WINDOW) + (address as usize % Mapper007::PRG_WINDOW)]
+                Some(prg_rom[(self.prg_bank(prg_rom) * Mapper007::PRG_WINDOW) + (address as usize % Mapper007::PRG_WINDOW)])
             },
             _ => panic!("Invalid PRG read {:#x}", address),
         }
@@ -36,7 +51,7 @@ impl super::Mapper for Mapper007 {
 
     fn peek_prg (&self, address: u16, _prg_ram: &Vec<u8>, prg_rom: &Vec<u8>) -> Option<u8> {
         match address {
-            0x8000 ..= 0xFFFF => Some(prg_rom[(self.prg_bank as usize * Mapper007::PRG_WINDOW) + (address as usize % Mapper007::PRG_WINDOW)]),
+            0x8000 ..= 0xFFFF => Some(prg_rom[(self.prg_bank(prg_rom) * Mapper007::PRG_WINDOW) + (address as usize % Mapper007::PRG_WINDOW)]),
             _ => None,
         }
     }
@@ -45,7 +60,7 @@ impl super::Mapper for Mapper007 {
         match address {
             0x8000 ..= 0xFFFF => {
                 self.prg_bank = data & 0b0000_0111; // Max. 8 * 32 KiB = 256 KiB PRG
-                self.mirroring = if (data & 0b0001_0000) > 0 { Some(Mirroring::OneScreenLower) } else { None }
+                self.mirroring = Some(if (data & 0b0001_0000) > 0 { Mirroring::OneScreenUpper } else { Mirroring::OneScreenLower });
             },
             _ => log::warn!("Invalid PRG write {:#x}", address),
         }
@@ -55,17 +70,17 @@ impl super::Mapper for Mapper007 {
         self.mirroring
     }
 
-    fn get_current_prg (&self, _prg_rom: &Vec<u8>) -> Vec<Bank> {
-        vec![Bank { number: self.prg_bank, size: Mapper007::PRG_WINDOW }]
+    fn get_current_prg (&self, prg_rom: &Vec<u8>) -> Vec<Bank> {
+        vec![Bank { number: self.prg_bank(prg_rom) as u8, size: Mapper007::PRG_WINDOW }]
     }
 
     fn get_current_chr (&self, chr: &Vec<u8>) -> Vec<Bank> {
         vec![Bank { number: 0, size: chr.len() }]
     }
 
-    fn get_bank_at (&self, _prg_rom: &Vec<u8>, address: u16) -> u8 {
+    fn get_bank_at (&self, prg_rom: &Vec<u8>, address: u16) -> u8 {
         match address {
-            0x8000 ..= 0xFFFF => self.prg_bank,
+            0x8000 ..= 0xFFFF => self.prg_bank(prg_rom) as u8,
             _ => unreachable!(),
         }
     }
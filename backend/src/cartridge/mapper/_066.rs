@@ -4,6 +4,7 @@
 
 use crate::cartridge::{Mirroring, Bank};
 
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct Mapper066 {
     prg_bank: u8,
     chr_bank: u8,
@@ -12,24 +13,44 @@ pub struct Mapper066 {
 impl Mapper066 {
     const PRG_WINDOW: usize = 0x8000; // 32 KiB
     const CHR_WINDOW: usize = 0x2000; // 8 KiB
+
+    /**
+     * Selected banks, wrapped to the banks there are
+     */
+    fn prg_bank (&self, prg_rom: &[u8]) -> usize {
+        self.prg_bank as usize % (prg_rom.len() / Mapper066::PRG_WINDOW)
+    }
+
+    fn chr_bank (&self, chr: &[u8]) -> usize {
+        self.chr_bank as usize % (chr.len() / Mapper066::CHR_WINDOW)
+    }
 }
 
 impl super::Mapper for Mapper066 {
     fn read_chr (&self, address: u16, chr: &Vec<u8>) -> u8 {
-        chr[(self.chr_bank as usize * Mapper066::CHR_WINDOW) + (address as usize % Mapper066::CHR_WINDOW)]
+        chr[(self.chr_bank(chr) * Mapper066::CHR_WINDOW) + (address as usize % Mapper066::CHR_WINDOW)]
     }
 
     fn write_chr (&mut self, address: u16, data: u8, chr: &mut Vec<u8>) {
-        chr[(self.chr_bank as usize * Mapper066::CHR_WINDOW) + (address as usize % Mapper066::CHR_WINDOW)] = data;
+        let bank = self.chr_bank(chr);
+
+        chr[(bank * Mapper066::CHR_WINDOW) + (address as usize % Mapper066::CHR_WINDOW)] = data;
+    }
+
+    /**
+     * Whole 32 KiB banks
+     */
+    fn supports_prg_rom (&self, size: usize) -> bool {
+        size >= Mapper066::PRG_WINDOW && size.is_multiple_of(Mapper066::PRG_WINDOW)
     }
 
-    fn read_prg (&self, address: u16, _prg_ram: &Vec<u8>, prg_rom: &Vec<u8>) -> u8 {
+    fn read_prg (&self, address: u16, _prg_ram: &Vec<u8>, prg_rom: &Vec<u8>) -> Option<u8> {
         match address {
-            0x4020 ..= 0x5FFF => {
-                0
+            0x4020 ..= 0x7FFF => {
+                None // Open bus
             },
             0x8000 ..= 0xFFFF => {
-                prg_rom[(self.prg_bank as usize * Mapper066::PRG_WINDOW) + (address as usize % Mapper066::PRG_WINDOW)]
+                Some(prg_rom[(self.prg_bank(prg_rom) * Mapper066::PRG_WINDOW) + (address as usize % Mapper066::PRG_WINDOW)])
             },
             _ => panic!("Invalid PRG read {:#x}", address),
         }
@@ -37,7 +58,7 @@ impl super::Mapper for Mapper066 {
 
     fn peek_prg (&self, address: u16, _prg_ram: &Vec<u8>, prg_rom: &Vec<u8>) -> Option<u8> {
         match address {
-            0x8000 ..= 0xFFFF => Some(prg_rom[(self.prg_bank as usize * Mapper066::PRG_WINDOW) + (address as usize % Mapper066::PRG_WINDOW)]),
+            0x8000 ..= 0xFFFF => Some(prg_rom[(self.prg_bank(prg_rom) * Mapper066::PRG_WINDOW) + (address as usize % Mapper066::PRG_WINDOW)]),
             _ => None,
         }
     }
@@ -56,17 +77,17 @@ impl super::Mapper for Mapper066 {
         None
     }
 
-    fn get_current_prg (&self, _prg_rom: &Vec<u8>) -> Vec<Bank> {
-        vec![Bank { number: self.prg_bank, size: Mapper066::PRG_WINDOW }]
+    fn get_current_prg (&self, prg_rom: &Vec<u8>) -> Vec<Bank> {
+        vec![Bank { number: self.prg_bank(prg_rom) as u8, size: Mapper066::PRG_WINDOW }]
     }
 
-    fn get_current_chr (&self, _chr: &Vec<u8>) -> Vec<Bank> {
-        vec![Bank { number: self.chr_bank, size: Mapper066::CHR_WINDOW }]
+    fn get_current_chr (&self, chr: &Vec<u8>) -> Vec<Bank> {
+        vec![Bank { number: self.chr_bank(chr) as u8, size: Mapper066::CHR_WINDOW }]
     }
 
-    fn get_bank_at (&self, _prg_rom: &Vec<u8>, address: u16) -> u8 {
+    fn get_bank_at (&self, prg_rom: &Vec<u8>, address: u16) -> u8 {
         match address {
-            0x8000 ..= 0xFFFF => self.prg_bank,
+            0x8000 ..= 0xFFFF => self.prg_bank(prg_rom) as u8,
             _ => unreachable!(),
         }
     }
@@ -2,36 +2,68 @@
  * https://wiki.nesdev.org/w/index.php/Mapper
  */
 
-use crate::cartridge;
+use serde::{Serialize, de::DeserializeOwned};
+use crate::{cartridge, StateError};
 
-pub fn get_mapper (id: u8) -> Box<dyn Mapper> {
+/**
+ * Mapper for an iNES mapper number, and a NES 2.0 submapper for the variants that need telling apart (0 otherwise)
+ */
+pub fn get_mapper (id: u16, submapper: u8) -> Result<Box<dyn Mapper>, cartridge::CartridgeError> {
     match id {
-        0   => Box::new(cartridge::Mapper000::default()),
-        1   => Box::new(cartridge::Mapper001::default()),
-        2   => Box::new(cartridge::Mapper002::default()),
-        3   => Box::new(cartridge::Mapper003::default()),
-        7   => Box::new(cartridge::Mapper007::default()),
-        66  => Box::new(cartridge::Mapper066::default()),
-        _   => unimplemented!("Unsupported mapper ({})", id),
+        0   => Ok(Box::new(cartridge::Mapper000::default())),
+        1   => Ok(Box::new(cartridge::Mapper001::default())),
+        2   => Ok(Box::new(cartridge::Mapper002::default())),
+        3   => Ok(Box::new(cartridge::Mapper003::default())),
+        4   => Ok(Box::new(cartridge::Mapper004::new(submapper))),
+        7   => Ok(Box::new(cartridge::Mapper007::default())),
+        66  => Ok(Box::new(cartridge::Mapper066::default())),
+        _   => Err(cartridge::CartridgeError::UnsupportedMapper(id)),
+    }
+}
+
+/**
+ * Save state support, implemented for every mapper that is Clone and (de)serializable
+ */
+pub trait MapperState {
+    fn clone_box (&self) -> Box<dyn Mapper>;
+    fn save_state (&self) -> Vec<u8>;
+    fn load_state (&mut self, state: &[u8]) -> Result<(), StateError>;
+}
+
+impl<T> MapperState for T where T: 'static + Mapper + Clone + Serialize + DeserializeOwned {
+    fn clone_box (&self) -> Box<dyn Mapper> {
+        Box::new(self.clone())
+    }
+
+    fn save_state (&self) -> Vec<u8> {
+        bincode::serialize(self).expect("Mapper state is serializable")
+    }
+
+    fn load_state (&mut self, state: &[u8]) -> Result<(), StateError> {
+        *self = bincode::deserialize(state)?;
+        Ok(())
     }
 }
 
-pub trait Mapper {
+pub trait Mapper: MapperState {
     fn read_chr (&self, address: u16, chr: &Vec<u8>) -> u8;
     fn write_chr (&mut self, address: u16, data: u8, chr: &mut Vec<u8>);
-    fn read_prg (&self, address: u16, prg_ram: &Vec<u8>, prg_rom: &Vec<u8>) -> u8;
+    fn read_prg (&self, address: u16, prg_ram: &Vec<u8>, prg_rom: &Vec<u8>) -> Option<u8>; // None when nothing drives the bus
     fn peek_prg (&self, address: u16, prg_ram: &Vec<u8>, prg_rom: &Vec<u8>) -> Option<u8>;
     fn write_prg (&mut self, address: u16, data: u8, prg_ram: &mut Vec<u8>);
     fn get_mirroring (&self) -> Option<cartridge::Mirroring>;
+    fn notify_ppu_address (&mut self, _address: u16, _cycle: usize) {} // Address driven on the PPU bus, at a PPU clock count
+    fn irq (&self) -> bool { false }
+    fn watches_ppu (&self) -> bool { false } // Whether notify_ppu_address can raise an IRQ, so the PPU can't run ahead of the CPU
+    fn supports_prg_rom (&self, _size: usize) -> bool { true } // Whether PRG-ROM of that size can be banked, checked when loading
     // Debug utilities
     fn get_current_prg (&self, prg_rom: &Vec<u8>) -> Vec<cartridge::Bank>;
     fn get_current_chr (&self, chr: &Vec<u8>) -> Vec<cartridge::Bank>;
     fn get_bank_at (&self, prg_rom: &Vec<u8>, address: u16) -> u8;
 }
 
-// We can now implement Clone manually by forwarding to clone_box.
 impl Clone for Box<dyn Mapper> {
-    fn clone(&self) -> Box<dyn Mapper> {
-        todo!()
+    fn clone (&self) -> Box<dyn Mapper> {
+        self.clone_box()
     }
 }
@@ -8,6 +8,7 @@ mod _000;
 mod _001;
 mod _002;
 mod _003;
+mod _004;
 mod _007;
 mod _066;
 
@@ -17,5 +18,6 @@ pub use _000::*;
 pub use _001::*;
 pub use _002::*;
 pub use _003::*;
+pub use _004::*;
 pub use _007::*;
 pub use _066::*;
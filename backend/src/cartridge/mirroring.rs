@@ -1,6 +1,6 @@
 use std::fmt;
 
-#[derive(Debug, PartialEq, Copy, Clone, serde::Serialize)]
+#[derive(Debug, PartialEq, Copy, Clone, serde::Serialize, serde::Deserialize)]
 pub enum Mirroring {
     OneScreenLower,
     OneScreenUpper,
@@ -1,9 +1,11 @@
 mod cartridge;
+mod error;
 mod ines;
 mod mapper;
 mod mirroring;
 
 pub use cartridge::*;
+pub use error::*;
 pub use ines::*;
 pub use mapper::*;
 pub use mirroring::*;
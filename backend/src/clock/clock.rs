@@ -1,58 +1,159 @@
 /**
  * https://wiki.nesdev.org/w/index.php/Cycle_reference_chart
- * IDEA: memoize step ?
  */
 
 pub const CLOCK_MASTER_NTSC: f64    = 21_477_272.0;
-pub const CLOCK_PPU_NTSC: f64       = CLOCK_MASTER_NTSC / 4.0;
-pub const CLOCK_CPU_NTSC: f64       = CLOCK_MASTER_NTSC / 12.0;
+pub const DIVIDER_PPU_NTSC: u8      = 4;
+pub const DIVIDER_CPU_NTSC: u8      = 12;
 
 pub const CLOCK_MASTER_PAL: f64     = 26_601_712.5;
-pub const CLOCK_PPU_PAL: f64        = CLOCK_MASTER_PAL / 5.0;
-pub const CLOCK_CPU_PAL: f64        = CLOCK_MASTER_PAL / 16.0;
+pub const DIVIDER_PPU_PAL: u8       = 5;
+pub const DIVIDER_CPU_PAL: u8       = 16;
 
-/// A structure that holds the emulated time passed.
-#[derive(Copy, Clone, serde::Serialize)]
+pub const CLOCK_MASTER_DENDY: f64   = 26_601_712.5;
+pub const DIVIDER_PPU_DENDY: u8     = 5;
+pub const DIVIDER_CPU_DENDY: u8     = 15;
+
+/// A structure that counts master clock cycles, from which every other clock is derived.
+#[derive(Copy, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Clock {
     pub rate: f64,
-    pub time: f64,
+    pub cycles: u64,
 }
 
 impl Clock {
     pub fn new (rate: f64) -> Self {
         Self {
             rate,
-            time: 0.0,
+            cycles: 0,
         }
     }
 
     pub fn tick (&mut self) {
-        self.time += 1.0 / self.rate;
+        self.cycles += 1;
     }
 
     pub fn reset (&mut self) {
-        self.time = 0.0;
+        self.cycles = 0;
+    }
+
+    /**
+     * Emulated time passed, in seconds
+     */
+    pub fn time (&self) -> f64 {
+        self.cycles as f64 / self.rate
     }
 }
 
-/// A structure that represents passed time and is cycle-aware
-#[derive(Copy, Clone, serde::Serialize)]
+/// A clock running once every `divider` master clock cycles
+#[derive(Copy, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ClockDivider {
-    pub rate: f64,
+    pub divider: u8,
+    pub counter: u8, // Master cycles since the last cycle
     pub cycles: usize,
 }
 
 impl ClockDivider {
-    pub fn new (rate: f64) -> Self {
+    pub fn new (divider: u8) -> Self {
         Self {
-            rate,
+            divider,
+            counter: 0,
             cycles: 0,
         }
     }
 
-    pub fn tick (&mut self, clock: f64) -> bool {
-        let previous = self.cycles;
-        self.cycles = (clock / (1.0 / self.rate)) as usize;
-        self.cycles != previous
+    /**
+     * Count one master cycle, returning whether a cycle of this clock elapsed
+     */
+    pub fn tick (&mut self) -> bool {
+        self.counter += 1;
+
+        if self.counter == self.divider {
+            self.counter = 0;
+            self.cycles += 1;
+            true
+        } else {
+            false
+        }
     }
+
+    /**
+     * Count several master cycles at once, returning how many cycles of this clock elapsed
+     */
+    pub fn advance (&mut self, master_cycles: u32) -> usize {
+        let total = self.counter as u32 + master_cycles;
+        let cycles = (total / self.divider as u32) as usize;
+
+        self.counter = (total % self.divider as u32) as u8;
+        self.cycles += cycles;
+        cycles
+    }
+
+    /**
+     * Master cycles to count until the given number of cycles of this clock elapsed
+     */
+    pub fn master_cycles_until (&self, cycles: usize) -> u64 {
+        (cycles as u64 * self.divider as u64).saturating_sub(self.counter as u64)
+    }
+}
+
+/// A clock running at an arbitrary rate (e.g. audio samples), which isn't a whole fraction of the master clock.
+/// Rates are accumulated as integers in half-hertz (PAL master clock has a half) so that it doesn't drift.
+#[derive(Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ClockResampler {
+    pub step: u64, // Rate, in half-hertz
+    pub period: u64, // Master clock rate, in half-hertz
+    pub accumulator: u64,
+    pub cycles: usize,
+}
+
+impl ClockResampler {
+    pub fn new (rate: f64, master: f64) -> Self {
+        Self {
+            step: (rate * 2.0).round() as u64,
+            period: (master * 2.0).round() as u64,
+            accumulator: 0,
+            cycles: 0,
+        }
+    }
+
+    pub fn tick (&mut self) -> bool {
+        self.accumulator += self.step;
+
+        if self.accumulator >= self.period {
+            self.accumulator -= self.period;
+            self.cycles += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /**
+     * Count several master cycles at once, returning how many cycles of this clock elapsed
+     */
+    pub fn advance (&mut self, master_cycles: u32) -> usize {
+        self.accumulator += self.step * master_cycles as u64;
+
+        let cycles = (self.accumulator / self.period) as usize;
+        self.accumulator %= self.period;
+        self.cycles += cycles;
+        cycles
+    }
+}
+
+#[test]
+fn dividers () {
+    let mut cpu = ClockDivider::new(DIVIDER_CPU_NTSC);
+    let mut sample = ClockResampler::new(1.0, 2.5); // Half-hertz master rate, like PAL
+
+    assert_eq!((0 .. 120).filter(|_| cpu.tick()).count(), 10);
+    assert_eq!(cpu.advance(5), 0);
+    assert_eq!(cpu.master_cycles_until(2), 19);
+    assert_eq!(cpu.advance(19), 2);
+    assert_eq!((cpu.counter, cpu.cycles), (0, 12));
+    assert_eq!((0 .. 25).filter(|_| sample.tick()).count(), 10);
+    assert_eq!((sample.accumulator, sample.cycles), (0, 10));
+    assert_eq!(sample.advance(6), 2);
+    assert_eq!((sample.accumulator, sample.cycles), (2, 12));
 }
@@ -1,3 +1,5 @@
 mod clock;
+mod timing;
 
 pub use clock::*;
+pub use timing::*;
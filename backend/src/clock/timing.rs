@@ -1,22 +1,84 @@
-pub struct Timing {
-    pub name: String,
-    pub master: f64,
-    pub ppu: f64,
-    pub cpu: f64,
-}
+//! Console regions, differing in clock rates and frame length
+//! https://wiki.nesdev.org/w/index.php/Cycle_reference_chart
 
-pub const NTSC: Timing = Timing {
-    name: "NTSC",
-    master: 21_477_272.0,
-    ppu:    21_477_272.0 / 4.0,
-    cpu:    21_477_272.0 / 12.0,
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+use crate::{
+    cartridge::Timing,
+    clock::*,
 };
 
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, PartialEq, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub enum Region {
+    NTSC,
+    PAL,
+    Dendy, // Famiclone with PAL clocks but NTSC-like vblank and APU
+}
+
+impl Region {
+    pub fn master_clock (&self) -> f64 {
+        match self {
+            Region::NTSC    => CLOCK_MASTER_NTSC,
+            Region::PAL     => CLOCK_MASTER_PAL,
+            Region::Dendy   => CLOCK_MASTER_DENDY,
+        }
+    }
+
+    /**
+     * Master clock cycles per CPU cycle
+     */
+    pub fn cpu_divider (&self) -> u8 {
+        match self {
+            Region::NTSC    => DIVIDER_CPU_NTSC,
+            Region::PAL     => DIVIDER_CPU_PAL,
+            Region::Dendy   => DIVIDER_CPU_DENDY,
+        }
+    }
+
+    /**
+     * Master clock cycles per PPU cycle
+     */
+    pub fn ppu_divider (&self) -> u8 {
+        match self {
+            Region::NTSC    => DIVIDER_PPU_NTSC,
+            Region::PAL     => DIVIDER_PPU_PAL,
+            Region::Dendy   => DIVIDER_PPU_DENDY,
+        }
+    }
+
+    /**
+     * Scanlines per frame, the last one being the pre-render scanline
+     */
+    pub fn scanlines (&self) -> u16 {
+        match self {
+            Region::NTSC    => 262,
+            Region::PAL     => 312,
+            Region::Dendy   => 312,
+        }
+    }
 
-enum Mode { ntsc, pal }
+    /**
+     * Scanline on which vblank starts: PAL has a longer vblank, while Dendy has a longer post-render period
+     */
+    pub fn vblank_scanline (&self) -> u16 {
+        match self {
+            Region::NTSC    => 241,
+            Region::PAL     => 241,
+            Region::Dendy   => 291,
+        }
+    }
+}
 
-impl Mode {
-    fn value (&self) -> f64 {
-        21_477_272.0
+/**
+ * Region the ROM is meant for, NTSC being preferred for multi-region ROMs
+ */
+impl From<Timing> for Region {
+    fn from (timing: Timing) -> Self {
+        match timing {
+            Timing::NTSC | Timing::Multiple => Region::NTSC,
+            Timing::PAL                     => Region::PAL,
+            Timing::Dendy                   => Region::Dendy,
+        }
     }
 }
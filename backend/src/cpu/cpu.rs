@@ -1,13 +1,14 @@
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 use crate::{
-    cpu::{Interrupt, INTERRUPT_LATENCY},
-    bus::Bus,
-    clock::ClockDivider,
+    cpu::{Interrupt, Instruction},
+    bus::{Bus, DmcDma},
+    clock::{ClockDivider, Region},
 };
 
 pub const MEMORY_RAM_STACK_START: u16 = 0x100;
 
-#[wasm_bindgen(js_name = CpuStatusFlag)]
+#[cfg_attr(feature = "wasm", wasm_bindgen(js_name = CpuStatusFlag))]
 pub enum StatusFlag {
     Carry               = 0b0000_0001,
     Zero                = 0b0000_0010,
@@ -22,6 +23,7 @@ pub enum StatusFlag {
 /**
  * MOS 6502 CPU
  */
+#[derive(serde::Serialize, serde::Deserialize)]
 pub struct Cpu {
     pub pc: u16,
     pub sp: u8,
@@ -29,13 +31,24 @@ pub struct Cpu {
     pub x: u8,
     pub y: u8,
     pub status: u8,
-    pub cycles: usize,
-    pub interrupt: Option<Interrupt>,
+    pub cycles: usize, // Cycles spent in the current instruction, 0 on instruction boundaries
+    pub interrupt: Option<Interrupt>, // Sequence in progress, or reset waiting for the next cycle
+    pub halted: bool, // Jammed by KIL until reset
     pub clock: ClockDivider,
+    // Instruction state, carried between cycles
+    pub (super) instruction: Instruction,
+    pub (super) address: u16, // Effective address
+    pub (super) pointer: u8, // Zero page pointer of indirect modes
+    pub (super) data: u8,
+    pub (super) crossed: bool, // Indexing crossed a page
+    // Interrupt polling
+    pub (super) nmi_pending: bool,
+    pub (super) poll: Option<Interrupt>, // Sampled at the end of the current cycle
+    pub (super) last_poll: Option<Interrupt>, // Sampled at the end of the previous cycle
 }
 
 impl Cpu {
-    pub fn new () -> Cpu {
+    pub fn new (region: Region) -> Cpu {
         Cpu {
             pc: 0x34,
             sp: 0,
@@ -45,12 +58,50 @@ impl Cpu {
             status: StatusFlag::Unused as u8,
             cycles: 0,
             interrupt: None,
-            clock: ClockDivider::new(crate::clock::CLOCK_CPU_NTSC),
+            halted: false,
+            clock: ClockDivider::new(region.cpu_divider()),
+            instruction: Instruction::from_opcode(0xEA), // NOP
+            address: 0,
+            pointer: 0,
+            data: 0,
+            crossed: false,
+            nmi_pending: false,
+            poll: None,
+            last_poll: None,
         }
     }
 
-    pub fn tick (&mut self, time: f64, bus: &mut Bus) {
-        if self.clock.tick(time) {
+    /**
+     * Returns whether an instruction or interrupt sequence completed
+     */
+    pub fn tick (&mut self, bus: &mut Bus) -> bool {
+        if self.clock.tick() {
+            bus.sync();
+
+            // DMC sample fetches halt the CPU, and take priority over OAM DMA
+            if bus.dmc_dma.is_none() {
+                if let Some(address) = bus.apu.dmc_request() {
+                    bus.dmc_dma = Some(DmcDma {
+                        address,
+                        stall: if bus.dma.is_some() { 2 } else { 4 },
+                    });
+                }
+            }
+
+            if let Some(mut dmc_dma) = bus.dmc_dma {
+                dmc_dma.stall -= 1;
+
+                if dmc_dma.stall == 0 {
+                    let data = bus.read(dmc_dma.address);
+                    bus.apu.dmc_fill(data);
+                    bus.dmc_dma = None;
+                } else {
+                    bus.dmc_dma = Some(dmc_dma);
+                }
+
+                return false;
+            }
+
             let mut dma = bus.dma;
     
             match dma {
@@ -65,7 +116,7 @@ impl Cpu {
                             let address = ((status.page as u16) << 8) + status.count as u16;
                             status.read_buffer = bus.read(address);
                         } else {
-                            bus.ppu.write_oam(status.read_buffer);
+                            bus.write_oam(status.read_buffer);
     
                             if status.count < u8::MAX {
                                 status.count += 1;
@@ -78,26 +129,82 @@ impl Cpu {
                     bus.dma = dma;
                 },
                 None => {
-                    self.cycle(bus);
+                    return self.cycle(bus);
                 },
             }
         }
+
+        false
     }
 
     /**
      * Run a single clock cycle
+     * Returns whether an instruction or interrupt sequence completed
      */
-    pub fn cycle (&mut self, bus: &mut Bus) {
-        if self.cycles == 0 {
-            if let Some(interrupt) = self.interrupt {
-                self.interrupt(interrupt, bus);
-                self.interrupt = None;
-            } else {
-                self.execute(bus);
+    pub fn cycle (&mut self, bus: &mut Bus) -> bool {
+        if self.halted {
+            return false;
+        }
+
+        self.cycles += 1;
+
+        let done = if self.cycles == 1 {
+            // Interrupts polled before the last cycle of the previous instruction replace the next one
+            if self.interrupt.is_none() {
+                self.interrupt = self.last_poll;
+            }
+
+            match self.interrupt {
+                Some (_) => {
+                    bus.read(self.pc); // Opcode fetch, discarded
+                },
+                None => self.fetch_opcode(bus),
             }
+
+            false
+        } else {
+            match self.interrupt {
+                Some (interrupt) => self.interrupt_cycle(bus, interrupt, false),
+                None => self.instruction_cycle(bus),
+            }
+        };
+
+        if done {
+            self.cycles = 0;
+            self.interrupt = None;
         }
 
-        self.cycles -= 1;
+        bus.sync_interrupts();
+        self.poll_interrupts(bus);
+
+        done
+    }
+
+    /**
+     * On instruction boundaries, the interrupt sequence that runs next instead of the instruction at PC
+     */
+    pub fn pending_interrupt (&self) -> Option<Interrupt> {
+        self.interrupt.or(self.last_poll)
+    }
+
+    /**
+     * Sample interrupt lines at the end of each cycle. NMI takes priority, and an IRQ is left pending on the line, not dropped,
+     * while interrupts are disabled. What was sampled on the penultimate cycle of an instruction decides whether an interrupt follows it.
+     * https://wiki.nesdev.com/w/index.php/CPU_interrupts
+     */
+    fn poll_interrupts (&mut self, bus: &mut Bus) {
+        if bus.interrupts.take_nmi() {
+            self.nmi_pending = true;
+        }
+
+        self.last_poll = self.poll;
+        self.poll = if self.nmi_pending {
+            Some(Interrupt::NMI)
+        } else if bus.interrupts.irq() && !self.get_flag(StatusFlag::DisableInterrupt) {
+            Some(Interrupt::IRQ)
+        } else {
+            None
+        };
     }
 
     pub fn push_stack (&mut self, bus: &mut Bus, data: u8) {
@@ -122,37 +229,9 @@ impl Cpu {
         (self.status & flag as u8) != 0
     }
 
-    pub fn interrupt (&mut self, interrupt: Interrupt, bus: &mut Bus) {
-        // log::debug!("Interrupt {:?}", interrupt);
-        match interrupt {
-            Interrupt::NMI | Interrupt::IRQ => {
-                if self.get_flag(StatusFlag::DisableInterrupt) && interrupt == Interrupt::IRQ {
-                    log::warn!("Attempted to IRQ but was disabled");
-                    return;
-                }
-
-                let (hi, lo) = ((self.pc >> 8) as u8, self.pc as u8);
-                self.push_stack(bus, hi);
-                self.push_stack(bus, lo);
-                self.push_stack(bus, self.status | (StatusFlag::Unused as u8));
-                self.set_flag(StatusFlag::DisableInterrupt, true);
-                self.cycles = INTERRUPT_LATENCY;
-            },
-            Interrupt::RESET => {
-                self.sp = self.sp.wrapping_sub(3);
-                self.set_flag(StatusFlag::DisableInterrupt, true);
-                self.cycles = INTERRUPT_LATENCY;
-            },
-        };
-
-        self.pc = (bus.read(interrupt as u16 + 1) as u16) << 8 | bus.read(interrupt as u16) as u16;
-    }
-
-    pub fn interrupt_request (&mut self, interrupt: Interrupt) {
-        self.interrupt = Some(interrupt);
-    }
-
     pub fn reset (&mut self) {
-        self.interrupt_request(Interrupt::RESET);
+        self.halted = false;
+        self.cycles = 0;
+        self.interrupt = Some(Interrupt::RESET);
     }
 }
@@ -1,14 +1,17 @@
 use crate::{
     bus::Bus,
-    cpu::{Cpu, StatusFlag, Interrupt},
+    cpu::{Cpu, StatusFlag, Interrupt, MEMORY_RAM_STACK_START},
 };
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum Operator {
     ADC,
+    ALR,
     ANC,
     AND,
+    ARR,
     ASL,
+    AXS,
     BCC,
     BCS,
     BEQ,
@@ -20,12 +23,13 @@ pub enum Operator {
     BVC,
     BVS,
     CLC,
+    CLD,
     CLI,
     CLV,
-    CLD,
     CMP,
     CPX,
     CPY,
+    DCP,
     DEC,
     DEX,
     DEY,
@@ -33,12 +37,14 @@ pub enum Operator {
     INC,
     INX,
     INY,
+    ISC,
     JMP,
     JSR,
     KIL,
+    LAS,
     LAX,
-    LDX,
     LDA,
+    LDX,
     LDY,
     LSR,
     NOP,
@@ -50,8 +56,10 @@ pub enum Operator {
     RLA,
     ROL,
     ROR,
+    RRA,
     RTI,
     RTS,
+    SAX,
     SBC,
     SEC,
     SED,
@@ -60,6 +68,7 @@ pub enum Operator {
     SHX,
     SHY,
     SLO,
+    SRE,
     STA,
     STX,
     STY,
@@ -70,10 +79,10 @@ pub enum Operator {
     TXA,
     TXS,
     TYA,
-    ___,
+    XAA,
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum AddressingMode {
     Implied,        // ∅
     Accumulator,    // = A
@@ -90,323 +99,333 @@ pub enum AddressingMode {
     IndirectY,      // = P0(PTR(ADDR[u8]) + Y)
 }
 
-#[derive(Copy, Clone, PartialEq)]
-pub enum Operand {
-    None,
-    Byte (u8),
-    Address (u16),
-}
-
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Instruction {
     pub opcode: u8,
     pub operator: Operator,
     pub mode: AddressingMode,
-    pub cycles: usize,
     pub illegal: bool,
-    pub extra_on_page_cross: bool,
 }
 
-const INSTRUCTIONS: [Instruction; 256] = [
-    Instruction { opcode: 0x00, operator: Operator::BRK, mode: AddressingMode::Implied,     cycles: 7, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x01, operator: Operator::ORA, mode: AddressingMode::IndirectX,   cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x02, operator: Operator::KIL, mode: AddressingMode::Implied,     cycles: 1, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x03, operator: Operator::SLO, mode: AddressingMode::IndirectX,   cycles: 1, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x04, operator: Operator::NOP, mode: AddressingMode::ZeroPage,    cycles: 3, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x05, operator: Operator::ORA, mode: AddressingMode::ZeroPage,    cycles: 3, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x06, operator: Operator::ASL, mode: AddressingMode::ZeroPage,    cycles: 5, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x07, operator: Operator::SLO, mode: AddressingMode::ZeroPage,    cycles: 5, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x08, operator: Operator::PHP, mode: AddressingMode::Implied,     cycles: 3, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x09, operator: Operator::ORA, mode: AddressingMode::Immediate,   cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x0A, operator: Operator::ASL, mode: AddressingMode::Accumulator, cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x0B, operator: Operator::ANC, mode: AddressingMode::Immediate,   cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x0C, operator: Operator::NOP, mode: AddressingMode::Absolute,    cycles: 4, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x0D, operator: Operator::ORA, mode: AddressingMode::Absolute,    cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x0E, operator: Operator::ASL, mode: AddressingMode::Absolute,    cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x0F, operator: Operator::SLO, mode: AddressingMode::Absolute,    cycles: 6, illegal: true,      extra_on_page_cross: false  },
-
-    Instruction { opcode: 0x10, operator: Operator::BPL, mode: AddressingMode::Relative,    cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x11, operator: Operator::ORA, mode: AddressingMode::IndirectY,   cycles: 5, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0x12, operator: Operator::KIL, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x13, operator: Operator::SLO, mode: AddressingMode::IndirectY,   cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x14, operator: Operator::NOP, mode: AddressingMode::ZeroPageX,   cycles: 4, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x15, operator: Operator::ORA, mode: AddressingMode::ZeroPageX,   cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x16, operator: Operator::ASL, mode: AddressingMode::ZeroPageX,   cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x17, operator: Operator::SLO, mode: AddressingMode::ZeroPageX,   cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x18, operator: Operator::CLC, mode: AddressingMode::Implied,     cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x19, operator: Operator::ORA, mode: AddressingMode::AbsoluteY,   cycles: 4, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0x1A, operator: Operator::NOP, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x1B, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x1C, operator: Operator::NOP, mode: AddressingMode::AbsoluteX,   cycles: 4, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x1D, operator: Operator::ORA, mode: AddressingMode::AbsoluteX,   cycles: 4, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0x1E, operator: Operator::ASL, mode: AddressingMode::AbsoluteX,   cycles: 7, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x1F, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-
-    Instruction { opcode: 0x20, operator: Operator::JSR, mode: AddressingMode::Absolute,    cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x21, operator: Operator::AND, mode: AddressingMode::IndirectX,   cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x22, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x23, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x24, operator: Operator::BIT, mode: AddressingMode::ZeroPage,    cycles: 3, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x25, operator: Operator::AND, mode: AddressingMode::ZeroPage,    cycles: 3, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x26, operator: Operator::ROL, mode: AddressingMode::ZeroPage,    cycles: 5, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x27, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x28, operator: Operator::PLP, mode: AddressingMode::Implied,     cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x29, operator: Operator::AND, mode: AddressingMode::Immediate,   cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x2A, operator: Operator::ROL, mode: AddressingMode::Accumulator, cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x2B, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x2C, operator: Operator::BIT, mode: AddressingMode::Absolute,    cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x2D, operator: Operator::AND, mode: AddressingMode::Absolute,    cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x2E, operator: Operator::ROL, mode: AddressingMode::Absolute,    cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x2F, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-
-    Instruction { opcode: 0x30, operator: Operator::BMI, mode: AddressingMode::Relative,    cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x31, operator: Operator::AND, mode: AddressingMode::IndirectY,   cycles: 5, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0x32, operator: Operator::KIL, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x33, operator: Operator::RLA, mode: AddressingMode::IndirectX,   cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x34, operator: Operator::NOP, mode: AddressingMode::ZeroPageX,   cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x35, operator: Operator::AND, mode: AddressingMode::ZeroPageX,   cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x36, operator: Operator::ROL, mode: AddressingMode::ZeroPageX,   cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x37, operator: Operator::RLA, mode: AddressingMode::ZeroPageX,   cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x38, operator: Operator::SEC, mode: AddressingMode::Implied,     cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x39, operator: Operator::AND, mode: AddressingMode::AbsoluteY,   cycles: 4, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0x3A, operator: Operator::NOP, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x3B, operator: Operator::RLA, mode: AddressingMode::AbsoluteY,   cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x3C, operator: Operator::NOP, mode: AddressingMode::AbsoluteX,   cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x3D, operator: Operator::AND, mode: AddressingMode::AbsoluteX,   cycles: 4, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0x3E, operator: Operator::ROL, mode: AddressingMode::AbsoluteX,   cycles: 7, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x3F, operator: Operator::RLA, mode: AddressingMode::AbsoluteX,   cycles: 2, illegal: true,      extra_on_page_cross: false  },
-
-    Instruction { opcode: 0x40, operator: Operator::RTI, mode: AddressingMode::Implied,     cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x41, operator: Operator::EOR, mode: AddressingMode::IndirectX,   cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x42, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x43, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x44, operator: Operator::NOP, mode: AddressingMode::ZeroPage,    cycles: 3, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x45, operator: Operator::EOR, mode: AddressingMode::ZeroPage,    cycles: 3, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x46, operator: Operator::LSR, mode: AddressingMode::ZeroPage,    cycles: 5, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x47, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x48, operator: Operator::PHA, mode: AddressingMode::Implied,     cycles: 3, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x49, operator: Operator::EOR, mode: AddressingMode::Immediate,   cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x4A, operator: Operator::LSR, mode: AddressingMode::Accumulator, cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x4B, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x4C, operator: Operator::JMP, mode: AddressingMode::Absolute,    cycles: 3, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x4D, operator: Operator::EOR, mode: AddressingMode::Absolute,    cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x4E, operator: Operator::LSR, mode: AddressingMode::Absolute,    cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x4F, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-
-    Instruction { opcode: 0x50, operator: Operator::BVC, mode: AddressingMode::Relative,    cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x51, operator: Operator::EOR, mode: AddressingMode::IndirectY,   cycles: 5, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0x52, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x53, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x54, operator: Operator::NOP, mode: AddressingMode::ZeroPageX,   cycles: 4, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x55, operator: Operator::EOR, mode: AddressingMode::ZeroPageX,   cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x56, operator: Operator::LSR, mode: AddressingMode::ZeroPageX,   cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x57, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x58, operator: Operator::CLI, mode: AddressingMode::Implied,     cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x59, operator: Operator::EOR, mode: AddressingMode::AbsoluteY,   cycles: 4, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0x5A, operator: Operator::NOP, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x5B, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x5C, operator: Operator::NOP, mode: AddressingMode::AbsoluteX,   cycles: 4, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x5D, operator: Operator::EOR, mode: AddressingMode::AbsoluteX,   cycles: 4, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0x5E, operator: Operator::LSR, mode: AddressingMode::AbsoluteX,   cycles: 7, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x5F, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-
-    Instruction { opcode: 0x60, operator: Operator::RTS, mode: AddressingMode::Implied,     cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x61, operator: Operator::ADC, mode: AddressingMode::IndirectX,   cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x62, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x63, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x64, operator: Operator::NOP, mode: AddressingMode::ZeroPage,    cycles: 3, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x65, operator: Operator::ADC, mode: AddressingMode::ZeroPage,    cycles: 3, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x66, operator: Operator::ROR, mode: AddressingMode::ZeroPage,    cycles: 5, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x67, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x68, operator: Operator::PLA, mode: AddressingMode::Implied,     cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x69, operator: Operator::ADC, mode: AddressingMode::Immediate,   cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x6A, operator: Operator::ROR, mode: AddressingMode::Accumulator, cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x6B, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x6C, operator: Operator::JMP, mode: AddressingMode::Indirect,    cycles: 5, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x6D, operator: Operator::ADC, mode: AddressingMode::Absolute,    cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x6E, operator: Operator::ROR, mode: AddressingMode::Absolute,    cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x6F, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-
-    Instruction { opcode: 0x70, operator: Operator::BVS, mode: AddressingMode::Relative,    cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x71, operator: Operator::ADC, mode: AddressingMode::IndirectY,   cycles: 5, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0x72, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x73, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x74, operator: Operator::NOP, mode: AddressingMode::ZeroPageX,   cycles: 4, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x75, operator: Operator::ADC, mode: AddressingMode::ZeroPageX,   cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x76, operator: Operator::ROR, mode: AddressingMode::ZeroPageX,   cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x77, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x78, operator: Operator::SEI, mode: AddressingMode::Implied,     cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x79, operator: Operator::ADC, mode: AddressingMode::AbsoluteY,   cycles: 4, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0x7A, operator: Operator::NOP, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x7B, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x7C, operator: Operator::NOP, mode: AddressingMode::AbsoluteX,   cycles: 4, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x7D, operator: Operator::ADC, mode: AddressingMode::AbsoluteX,   cycles: 4, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0x7E, operator: Operator::ROR, mode: AddressingMode::AbsoluteX,   cycles: 7, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x7F, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-
-    Instruction { opcode: 0x80, operator: Operator::NOP, mode: AddressingMode::Immediate,   cycles: 3, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x81, operator: Operator::STA, mode: AddressingMode::IndirectX,   cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x82, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x83, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x84, operator: Operator::STY, mode: AddressingMode::ZeroPage,    cycles: 3, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x85, operator: Operator::STA, mode: AddressingMode::ZeroPage,    cycles: 3, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x86, operator: Operator::STX, mode: AddressingMode::ZeroPage,    cycles: 3, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x87, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x88, operator: Operator::DEY, mode: AddressingMode::Implied,     cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x89, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x8A, operator: Operator::TXA, mode: AddressingMode::Implied,     cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x8B, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x8C, operator: Operator::STY, mode: AddressingMode::Absolute,    cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x8D, operator: Operator::STA, mode: AddressingMode::Absolute,    cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x8E, operator: Operator::STX, mode: AddressingMode::Absolute,    cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x8F, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-
-    Instruction { opcode: 0x90, operator: Operator::BCC, mode: AddressingMode::Relative,    cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x91, operator: Operator::STA, mode: AddressingMode::IndirectY,   cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x92, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x93, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x94, operator: Operator::STY, mode: AddressingMode::ZeroPageX,   cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x95, operator: Operator::STA, mode: AddressingMode::ZeroPageX,   cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x96, operator: Operator::STX, mode: AddressingMode::ZeroPageY,   cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x97, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x98, operator: Operator::TYA, mode: AddressingMode::Implied,     cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x99, operator: Operator::STA, mode: AddressingMode::AbsoluteY,   cycles: 5, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x9A, operator: Operator::TXS, mode: AddressingMode::Implied,     cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x9B, operator: Operator::TAS, mode: AddressingMode::AbsoluteY,   cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x9C, operator: Operator::SHY, mode: AddressingMode::AbsoluteX,   cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x9D, operator: Operator::STA, mode: AddressingMode::AbsoluteX,   cycles: 5, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0x9E, operator: Operator::SHX, mode: AddressingMode::AbsoluteY,   cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0x9F, operator: Operator::SHA, mode: AddressingMode::AbsoluteY,   cycles: 2, illegal: true,      extra_on_page_cross: false  },
-
-    Instruction { opcode: 0xA0, operator: Operator::LDY, mode: AddressingMode::Immediate,   cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xA1, operator: Operator::LDA, mode: AddressingMode::IndirectX,   cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xA2, operator: Operator::LDX, mode: AddressingMode::Immediate,   cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xA3, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xA4, operator: Operator::LDY, mode: AddressingMode::ZeroPage,    cycles: 3, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xA5, operator: Operator::LDA, mode: AddressingMode::ZeroPage,    cycles: 3, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xA6, operator: Operator::LDX, mode: AddressingMode::ZeroPage,    cycles: 3, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xA7, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xA8, operator: Operator::TAY, mode: AddressingMode::Implied,     cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xA9, operator: Operator::LDA, mode: AddressingMode::Immediate,   cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xAA, operator: Operator::TAX, mode: AddressingMode::Implied,     cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xAB, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xAC, operator: Operator::LDY, mode: AddressingMode::Absolute,    cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xAD, operator: Operator::LDA, mode: AddressingMode::Absolute,    cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xAE, operator: Operator::LDX, mode: AddressingMode::Absolute,    cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xAF, operator: Operator::LAX, mode: AddressingMode::Absolute,    cycles: 2, illegal: true,      extra_on_page_cross: false  },
-
-    Instruction { opcode: 0xB0, operator: Operator::BCS, mode: AddressingMode::Relative,    cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xB1, operator: Operator::LDA, mode: AddressingMode::IndirectY,   cycles: 5, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0xB2, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xB3, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xB4, operator: Operator::LDY, mode: AddressingMode::ZeroPageX,   cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xB5, operator: Operator::LDA, mode: AddressingMode::ZeroPageX,   cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xB6, operator: Operator::LDX, mode: AddressingMode::ZeroPageY,   cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xB7, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xB8, operator: Operator::CLV, mode: AddressingMode::Implied,     cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xB9, operator: Operator::LDA, mode: AddressingMode::AbsoluteY,   cycles: 4, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0xBA, operator: Operator::TSX, mode: AddressingMode::Implied,     cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xBB, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xBC, operator: Operator::LDY, mode: AddressingMode::AbsoluteX,   cycles: 4, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0xBD, operator: Operator::LDA, mode: AddressingMode::AbsoluteX,   cycles: 4, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0xBE, operator: Operator::LDX, mode: AddressingMode::AbsoluteY,   cycles: 4, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0xBF, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-
-    Instruction { opcode: 0xC0, operator: Operator::CPY, mode: AddressingMode::Immediate,   cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xC1, operator: Operator::CMP, mode: AddressingMode::IndirectX,   cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xC2, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xC3, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xC4, operator: Operator::CPY, mode: AddressingMode::ZeroPage,    cycles: 3, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xC5, operator: Operator::CMP, mode: AddressingMode::ZeroPage,    cycles: 3, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xC6, operator: Operator::DEC, mode: AddressingMode::ZeroPage,    cycles: 5, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xC7, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xC8, operator: Operator::INY, mode: AddressingMode::Implied,     cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xC9, operator: Operator::CMP, mode: AddressingMode::Immediate,   cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xCA, operator: Operator::DEX, mode: AddressingMode::Implied,     cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xCB, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xCC, operator: Operator::CPY, mode: AddressingMode::Absolute,    cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xCD, operator: Operator::CMP, mode: AddressingMode::Absolute,    cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xCE, operator: Operator::DEC, mode: AddressingMode::Absolute,    cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xCF, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-
-    Instruction { opcode: 0xD0, operator: Operator::BNE, mode: AddressingMode::Relative,    cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xD1, operator: Operator::CMP, mode: AddressingMode::IndirectY,   cycles: 5, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0xD2, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xD3, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xD4, operator: Operator::NOP, mode: AddressingMode::ZeroPageX,   cycles: 4, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xD5, operator: Operator::CMP, mode: AddressingMode::ZeroPageX,   cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xD6, operator: Operator::DEC, mode: AddressingMode::ZeroPageX,   cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xD7, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xD8, operator: Operator::CLD, mode: AddressingMode::Implied,     cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xD9, operator: Operator::CMP, mode: AddressingMode::AbsoluteY,   cycles: 4, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0xDA, operator: Operator::NOP, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xDB, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xDC, operator: Operator::NOP, mode: AddressingMode::AbsoluteX,   cycles: 4, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xDD, operator: Operator::CMP, mode: AddressingMode::AbsoluteX,   cycles: 4, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0xDE, operator: Operator::DEC, mode: AddressingMode::AbsoluteX,   cycles: 7, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xDF, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-
-    Instruction { opcode: 0xE0, operator: Operator::CPX, mode: AddressingMode::Immediate,   cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xE1, operator: Operator::SBC, mode: AddressingMode::IndirectX,   cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xE2, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xE3, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xE4, operator: Operator::CPX, mode: AddressingMode::ZeroPage,    cycles: 3, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xE5, operator: Operator::SBC, mode: AddressingMode::ZeroPage,    cycles: 3, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xE6, operator: Operator::INC, mode: AddressingMode::ZeroPage,    cycles: 5, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xE7, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xE8, operator: Operator::INX, mode: AddressingMode::Implied,     cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xE9, operator: Operator::SBC, mode: AddressingMode::Immediate,   cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xEA, operator: Operator::NOP, mode: AddressingMode::Implied,     cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xEB, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xEC, operator: Operator::CPX, mode: AddressingMode::Absolute,    cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xED, operator: Operator::SBC, mode: AddressingMode::Absolute,    cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xEE, operator: Operator::INC, mode: AddressingMode::Absolute,    cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xEF, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
+/**
+ * How an instruction uses its effective address
+ */
+#[derive(Copy, Clone, PartialEq)]
+enum Access {
+    Read,
+    Write,
+    ReadModifyWrite,
+}
 
-    Instruction { opcode: 0xF0, operator: Operator::BEQ, mode: AddressingMode::Relative,    cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xF1, operator: Operator::SBC, mode: AddressingMode::IndirectY,   cycles: 5, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0xF2, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xF3, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xF4, operator: Operator::NOP, mode: AddressingMode::ZeroPageX,   cycles: 4, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xF5, operator: Operator::SBC, mode: AddressingMode::ZeroPageX,   cycles: 4, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xF6, operator: Operator::INC, mode: AddressingMode::ZeroPageX,   cycles: 6, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xF7, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xF8, operator: Operator::SED, mode: AddressingMode::Implied,     cycles: 2, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xF9, operator: Operator::SBC, mode: AddressingMode::AbsoluteY,   cycles: 4, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0xFA, operator: Operator::NOP, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xFB, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xFC, operator: Operator::NOP, mode: AddressingMode::AbsoluteX,   cycles: 4, illegal: true,      extra_on_page_cross: false  },
-    Instruction { opcode: 0xFD, operator: Operator::SBC, mode: AddressingMode::AbsoluteX,   cycles: 4, illegal: false,     extra_on_page_cross: true   },
-    Instruction { opcode: 0xFE, operator: Operator::INC, mode: AddressingMode::AbsoluteX,   cycles: 7, illegal: false,     extra_on_page_cross: false  },
-    Instruction { opcode: 0xFF, operator: Operator::___, mode: AddressingMode::Implied,     cycles: 2, illegal: true,      extra_on_page_cross: false  },
+const INSTRUCTIONS: [Instruction; 256] = [
+    Instruction { opcode: 0x00, operator: Operator::BRK, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0x01, operator: Operator::ORA, mode: AddressingMode::IndirectX,   illegal: false },
+    Instruction { opcode: 0x02, operator: Operator::KIL, mode: AddressingMode::Implied,     illegal: true  },
+    Instruction { opcode: 0x03, operator: Operator::SLO, mode: AddressingMode::IndirectX,   illegal: true  },
+    Instruction { opcode: 0x04, operator: Operator::NOP, mode: AddressingMode::ZeroPage,    illegal: true  },
+    Instruction { opcode: 0x05, operator: Operator::ORA, mode: AddressingMode::ZeroPage,    illegal: false },
+    Instruction { opcode: 0x06, operator: Operator::ASL, mode: AddressingMode::ZeroPage,    illegal: false },
+    Instruction { opcode: 0x07, operator: Operator::SLO, mode: AddressingMode::ZeroPage,    illegal: true  },
+    Instruction { opcode: 0x08, operator: Operator::PHP, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0x09, operator: Operator::ORA, mode: AddressingMode::Immediate,   illegal: false },
+    Instruction { opcode: 0x0A, operator: Operator::ASL, mode: AddressingMode::Accumulator, illegal: false },
+    Instruction { opcode: 0x0B, operator: Operator::ANC, mode: AddressingMode::Immediate,   illegal: true  },
+    Instruction { opcode: 0x0C, operator: Operator::NOP, mode: AddressingMode::Absolute,    illegal: true  },
+    Instruction { opcode: 0x0D, operator: Operator::ORA, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0x0E, operator: Operator::ASL, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0x0F, operator: Operator::SLO, mode: AddressingMode::Absolute,    illegal: true  },
+
+    Instruction { opcode: 0x10, operator: Operator::BPL, mode: AddressingMode::Relative,    illegal: false },
+    Instruction { opcode: 0x11, operator: Operator::ORA, mode: AddressingMode::IndirectY,   illegal: false },
+    Instruction { opcode: 0x12, operator: Operator::KIL, mode: AddressingMode::Implied,     illegal: true  },
+    Instruction { opcode: 0x13, operator: Operator::SLO, mode: AddressingMode::IndirectY,   illegal: true  },
+    Instruction { opcode: 0x14, operator: Operator::NOP, mode: AddressingMode::ZeroPageX,   illegal: true  },
+    Instruction { opcode: 0x15, operator: Operator::ORA, mode: AddressingMode::ZeroPageX,   illegal: false },
+    Instruction { opcode: 0x16, operator: Operator::ASL, mode: AddressingMode::ZeroPageX,   illegal: false },
+    Instruction { opcode: 0x17, operator: Operator::SLO, mode: AddressingMode::ZeroPageX,   illegal: true  },
+    Instruction { opcode: 0x18, operator: Operator::CLC, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0x19, operator: Operator::ORA, mode: AddressingMode::AbsoluteY,   illegal: false },
+    Instruction { opcode: 0x1A, operator: Operator::NOP, mode: AddressingMode::Implied,     illegal: true  },
+    Instruction { opcode: 0x1B, operator: Operator::SLO, mode: AddressingMode::AbsoluteY,   illegal: true  },
+    Instruction { opcode: 0x1C, operator: Operator::NOP, mode: AddressingMode::AbsoluteX,   illegal: true  },
+    Instruction { opcode: 0x1D, operator: Operator::ORA, mode: AddressingMode::AbsoluteX,   illegal: false },
+    Instruction { opcode: 0x1E, operator: Operator::ASL, mode: AddressingMode::AbsoluteX,   illegal: false },
+    Instruction { opcode: 0x1F, operator: Operator::SLO, mode: AddressingMode::AbsoluteX,   illegal: true  },
+
+    Instruction { opcode: 0x20, operator: Operator::JSR, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0x21, operator: Operator::AND, mode: AddressingMode::IndirectX,   illegal: false },
+    Instruction { opcode: 0x22, operator: Operator::KIL, mode: AddressingMode::Implied,     illegal: true  },
+    Instruction { opcode: 0x23, operator: Operator::RLA, mode: AddressingMode::IndirectX,   illegal: true  },
+    Instruction { opcode: 0x24, operator: Operator::BIT, mode: AddressingMode::ZeroPage,    illegal: false },
+    Instruction { opcode: 0x25, operator: Operator::AND, mode: AddressingMode::ZeroPage,    illegal: false },
+    Instruction { opcode: 0x26, operator: Operator::ROL, mode: AddressingMode::ZeroPage,    illegal: false },
+    Instruction { opcode: 0x27, operator: Operator::RLA, mode: AddressingMode::ZeroPage,    illegal: true  },
+    Instruction { opcode: 0x28, operator: Operator::PLP, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0x29, operator: Operator::AND, mode: AddressingMode::Immediate,   illegal: false },
+    Instruction { opcode: 0x2A, operator: Operator::ROL, mode: AddressingMode::Accumulator, illegal: false },
+    Instruction { opcode: 0x2B, operator: Operator::ANC, mode: AddressingMode::Immediate,   illegal: true  },
+    Instruction { opcode: 0x2C, operator: Operator::BIT, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0x2D, operator: Operator::AND, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0x2E, operator: Operator::ROL, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0x2F, operator: Operator::RLA, mode: AddressingMode::Absolute,    illegal: true  },
+
+    Instruction { opcode: 0x30, operator: Operator::BMI, mode: AddressingMode::Relative,    illegal: false },
+    Instruction { opcode: 0x31, operator: Operator::AND, mode: AddressingMode::IndirectY,   illegal: false },
+    Instruction { opcode: 0x32, operator: Operator::KIL, mode: AddressingMode::Implied,     illegal: true  },
+    Instruction { opcode: 0x33, operator: Operator::RLA, mode: AddressingMode::IndirectY,   illegal: true  },
+    Instruction { opcode: 0x34, operator: Operator::NOP, mode: AddressingMode::ZeroPageX,   illegal: true  },
+    Instruction { opcode: 0x35, operator: Operator::AND, mode: AddressingMode::ZeroPageX,   illegal: false },
+    Instruction { opcode: 0x36, operator: Operator::ROL, mode: AddressingMode::ZeroPageX,   illegal: false },
+    Instruction { opcode: 0x37, operator: Operator::RLA, mode: AddressingMode::ZeroPageX,   illegal: true  },
+    Instruction { opcode: 0x38, operator: Operator::SEC, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0x39, operator: Operator::AND, mode: AddressingMode::AbsoluteY,   illegal: false },
+    Instruction { opcode: 0x3A, operator: Operator::NOP, mode: AddressingMode::Implied,     illegal: true  },
+    Instruction { opcode: 0x3B, operator: Operator::RLA, mode: AddressingMode::AbsoluteY,   illegal: true  },
+    Instruction { opcode: 0x3C, operator: Operator::NOP, mode: AddressingMode::AbsoluteX,   illegal: true  },
+    Instruction { opcode: 0x3D, operator: Operator::AND, mode: AddressingMode::AbsoluteX,   illegal: false },
+    Instruction { opcode: 0x3E, operator: Operator::ROL, mode: AddressingMode::AbsoluteX,   illegal: false },
+    Instruction { opcode: 0x3F, operator: Operator::RLA, mode: AddressingMode::AbsoluteX,   illegal: true  },
+
+    Instruction { opcode: 0x40, operator: Operator::RTI, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0x41, operator: Operator::EOR, mode: AddressingMode::IndirectX,   illegal: false },
+    Instruction { opcode: 0x42, operator: Operator::KIL, mode: AddressingMode::Implied,     illegal: true  },
+    Instruction { opcode: 0x43, operator: Operator::SRE, mode: AddressingMode::IndirectX,   illegal: true  },
+    Instruction { opcode: 0x44, operator: Operator::NOP, mode: AddressingMode::ZeroPage,    illegal: true  },
+    Instruction { opcode: 0x45, operator: Operator::EOR, mode: AddressingMode::ZeroPage,    illegal: false },
+    Instruction { opcode: 0x46, operator: Operator::LSR, mode: AddressingMode::ZeroPage,    illegal: false },
+    Instruction { opcode: 0x47, operator: Operator::SRE, mode: AddressingMode::ZeroPage,    illegal: true  },
+    Instruction { opcode: 0x48, operator: Operator::PHA, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0x49, operator: Operator::EOR, mode: AddressingMode::Immediate,   illegal: false },
+    Instruction { opcode: 0x4A, operator: Operator::LSR, mode: AddressingMode::Accumulator, illegal: false },
+    Instruction { opcode: 0x4B, operator: Operator::ALR, mode: AddressingMode::Immediate,   illegal: true  },
+    Instruction { opcode: 0x4C, operator: Operator::JMP, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0x4D, operator: Operator::EOR, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0x4E, operator: Operator::LSR, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0x4F, operator: Operator::SRE, mode: AddressingMode::Absolute,    illegal: true  },
+
+    Instruction { opcode: 0x50, operator: Operator::BVC, mode: AddressingMode::Relative,    illegal: false },
+    Instruction { opcode: 0x51, operator: Operator::EOR, mode: AddressingMode::IndirectY,   illegal: false },
+    Instruction { opcode: 0x52, operator: Operator::KIL, mode: AddressingMode::Implied,     illegal: true  },
+    Instruction { opcode: 0x53, operator: Operator::SRE, mode: AddressingMode::IndirectY,   illegal: true  },
+    Instruction { opcode: 0x54, operator: Operator::NOP, mode: AddressingMode::ZeroPageX,   illegal: true  },
+    Instruction { opcode: 0x55, operator: Operator::EOR, mode: AddressingMode::ZeroPageX,   illegal: false },
+    Instruction { opcode: 0x56, operator: Operator::LSR, mode: AddressingMode::ZeroPageX,   illegal: false },
+    Instruction { opcode: 0x57, operator: Operator::SRE, mode: AddressingMode::ZeroPageX,   illegal: true  },
+    Instruction { opcode: 0x58, operator: Operator::CLI, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0x59, operator: Operator::EOR, mode: AddressingMode::AbsoluteY,   illegal: false },
+    Instruction { opcode: 0x5A, operator: Operator::NOP, mode: AddressingMode::Implied,     illegal: true  },
+    Instruction { opcode: 0x5B, operator: Operator::SRE, mode: AddressingMode::AbsoluteY,   illegal: true  },
+    Instruction { opcode: 0x5C, operator: Operator::NOP, mode: AddressingMode::AbsoluteX,   illegal: true  },
+    Instruction { opcode: 0x5D, operator: Operator::EOR, mode: AddressingMode::AbsoluteX,   illegal: false },
+    Instruction { opcode: 0x5E, operator: Operator::LSR, mode: AddressingMode::AbsoluteX,   illegal: false },
+    Instruction { opcode: 0x5F, operator: Operator::SRE, mode: AddressingMode::AbsoluteX,   illegal: true  },
+
+    Instruction { opcode: 0x60, operator: Operator::RTS, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0x61, operator: Operator::ADC, mode: AddressingMode::IndirectX,   illegal: false },
+    Instruction { opcode: 0x62, operator: Operator::KIL, mode: AddressingMode::Implied,     illegal: true  },
+    Instruction { opcode: 0x63, operator: Operator::RRA, mode: AddressingMode::IndirectX,   illegal: true  },
+    Instruction { opcode: 0x64, operator: Operator::NOP, mode: AddressingMode::ZeroPage,    illegal: true  },
+    Instruction { opcode: 0x65, operator: Operator::ADC, mode: AddressingMode::ZeroPage,    illegal: false },
+    Instruction { opcode: 0x66, operator: Operator::ROR, mode: AddressingMode::ZeroPage,    illegal: false },
+    Instruction { opcode: 0x67, operator: Operator::RRA, mode: AddressingMode::ZeroPage,    illegal: true  },
+    Instruction { opcode: 0x68, operator: Operator::PLA, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0x69, operator: Operator::ADC, mode: AddressingMode::Immediate,   illegal: false },
+    Instruction { opcode: 0x6A, operator: Operator::ROR, mode: AddressingMode::Accumulator, illegal: false },
+    Instruction { opcode: 0x6B, operator: Operator::ARR, mode: AddressingMode::Immediate,   illegal: true  },
+    Instruction { opcode: 0x6C, operator: Operator::JMP, mode: AddressingMode::Indirect,    illegal: false },
+    Instruction { opcode: 0x6D, operator: Operator::ADC, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0x6E, operator: Operator::ROR, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0x6F, operator: Operator::RRA, mode: AddressingMode::Absolute,    illegal: true  },
+
+    Instruction { opcode: 0x70, operator: Operator::BVS, mode: AddressingMode::Relative,    illegal: false },
+    Instruction { opcode: 0x71, operator: Operator::ADC, mode: AddressingMode::IndirectY,   illegal: false },
+    Instruction { opcode: 0x72, operator: Operator::KIL, mode: AddressingMode::Implied,     illegal: true  },
+    Instruction { opcode: 0x73, operator: Operator::RRA, mode: AddressingMode::IndirectY,   illegal: true  },
+    Instruction { opcode: 0x74, operator: Operator::NOP, mode: AddressingMode::ZeroPageX,   illegal: true  },
+    Instruction { opcode: 0x75, operator: Operator::ADC, mode: AddressingMode::ZeroPageX,   illegal: false },
+    Instruction { opcode: 0x76, operator: Operator::ROR, mode: AddressingMode::ZeroPageX,   illegal: false },
+    Instruction { opcode: 0x77, operator: Operator::RRA, mode: AddressingMode::ZeroPageX,   illegal: true  },
+    Instruction { opcode: 0x78, operator: Operator::SEI, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0x79, operator: Operator::ADC, mode: AddressingMode::AbsoluteY,   illegal: false },
+    Instruction { opcode: 0x7A, operator: Operator::NOP, mode: AddressingMode::Implied,     illegal: true  },
+    Instruction { opcode: 0x7B, operator: Operator::RRA, mode: AddressingMode::AbsoluteY,   illegal: true  },
+    Instruction { opcode: 0x7C, operator: Operator::NOP, mode: AddressingMode::AbsoluteX,   illegal: true  },
+    Instruction { opcode: 0x7D, operator: Operator::ADC, mode: AddressingMode::AbsoluteX,   illegal: false },
+    Instruction { opcode: 0x7E, operator: Operator::ROR, mode: AddressingMode::AbsoluteX,   illegal: false },
+    Instruction { opcode: 0x7F, operator: Operator::RRA, mode: AddressingMode::AbsoluteX,   illegal: true  },
+
+    Instruction { opcode: 0x80, operator: Operator::NOP, mode: AddressingMode::Immediate,   illegal: true  },
+    Instruction { opcode: 0x81, operator: Operator::STA, mode: AddressingMode::IndirectX,   illegal: false },
+    Instruction { opcode: 0x82, operator: Operator::NOP, mode: AddressingMode::Immediate,   illegal: true  },
+    Instruction { opcode: 0x83, operator: Operator::SAX, mode: AddressingMode::IndirectX,   illegal: true  },
+    Instruction { opcode: 0x84, operator: Operator::STY, mode: AddressingMode::ZeroPage,    illegal: false },
+    Instruction { opcode: 0x85, operator: Operator::STA, mode: AddressingMode::ZeroPage,    illegal: false },
+    Instruction { opcode: 0x86, operator: Operator::STX, mode: AddressingMode::ZeroPage,    illegal: false },
+    Instruction { opcode: 0x87, operator: Operator::SAX, mode: AddressingMode::ZeroPage,    illegal: true  },
+    Instruction { opcode: 0x88, operator: Operator::DEY, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0x89, operator: Operator::NOP, mode: AddressingMode::Immediate,   illegal: true  },
+    Instruction { opcode: 0x8A, operator: Operator::TXA, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0x8B, operator: Operator::XAA, mode: AddressingMode::Immediate,   illegal: true  },
+    Instruction { opcode: 0x8C, operator: Operator::STY, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0x8D, operator: Operator::STA, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0x8E, operator: Operator::STX, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0x8F, operator: Operator::SAX, mode: AddressingMode::Absolute,    illegal: true  },
+
+    Instruction { opcode: 0x90, operator: Operator::BCC, mode: AddressingMode::Relative,    illegal: false },
+    Instruction { opcode: 0x91, operator: Operator::STA, mode: AddressingMode::IndirectY,   illegal: false },
+    Instruction { opcode: 0x92, operator: Operator::KIL, mode: AddressingMode::Implied,     illegal: true  },
+    Instruction { opcode: 0x93, operator: Operator::SHA, mode: AddressingMode::IndirectY,   illegal: true  },
+    Instruction { opcode: 0x94, operator: Operator::STY, mode: AddressingMode::ZeroPageX,   illegal: false },
+    Instruction { opcode: 0x95, operator: Operator::STA, mode: AddressingMode::ZeroPageX,   illegal: false },
+    Instruction { opcode: 0x96, operator: Operator::STX, mode: AddressingMode::ZeroPageY,   illegal: false },
+    Instruction { opcode: 0x97, operator: Operator::SAX, mode: AddressingMode::ZeroPageY,   illegal: true  },
+    Instruction { opcode: 0x98, operator: Operator::TYA, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0x99, operator: Operator::STA, mode: AddressingMode::AbsoluteY,   illegal: false },
+    Instruction { opcode: 0x9A, operator: Operator::TXS, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0x9B, operator: Operator::TAS, mode: AddressingMode::AbsoluteY,   illegal: true  },
+    Instruction { opcode: 0x9C, operator: Operator::SHY, mode: AddressingMode::AbsoluteX,   illegal: true  },
+    Instruction { opcode: 0x9D, operator: Operator::STA, mode: AddressingMode::AbsoluteX,   illegal: false },
+    Instruction { opcode: 0x9E, operator: Operator::SHX, mode: AddressingMode::AbsoluteY,   illegal: true  },
+    Instruction { opcode: 0x9F, operator: Operator::SHA, mode: AddressingMode::AbsoluteY,   illegal: true  },
+
+    Instruction { opcode: 0xA0, operator: Operator::LDY, mode: AddressingMode::Immediate,   illegal: false },
+    Instruction { opcode: 0xA1, operator: Operator::LDA, mode: AddressingMode::IndirectX,   illegal: false },
+    Instruction { opcode: 0xA2, operator: Operator::LDX, mode: AddressingMode::Immediate,   illegal: false },
+    Instruction { opcode: 0xA3, operator: Operator::LAX, mode: AddressingMode::IndirectX,   illegal: true  },
+    Instruction { opcode: 0xA4, operator: Operator::LDY, mode: AddressingMode::ZeroPage,    illegal: false },
+    Instruction { opcode: 0xA5, operator: Operator::LDA, mode: AddressingMode::ZeroPage,    illegal: false },
+    Instruction { opcode: 0xA6, operator: Operator::LDX, mode: AddressingMode::ZeroPage,    illegal: false },
+    Instruction { opcode: 0xA7, operator: Operator::LAX, mode: AddressingMode::ZeroPage,    illegal: true  },
+    Instruction { opcode: 0xA8, operator: Operator::TAY, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0xA9, operator: Operator::LDA, mode: AddressingMode::Immediate,   illegal: false },
+    Instruction { opcode: 0xAA, operator: Operator::TAX, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0xAB, operator: Operator::LAX, mode: AddressingMode::Immediate,   illegal: true  },
+    Instruction { opcode: 0xAC, operator: Operator::LDY, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0xAD, operator: Operator::LDA, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0xAE, operator: Operator::LDX, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0xAF, operator: Operator::LAX, mode: AddressingMode::Absolute,    illegal: true  },
+
+    Instruction { opcode: 0xB0, operator: Operator::BCS, mode: AddressingMode::Relative,    illegal: false },
+    Instruction { opcode: 0xB1, operator: Operator::LDA, mode: AddressingMode::IndirectY,   illegal: false },
+    Instruction { opcode: 0xB2, operator: Operator::KIL, mode: AddressingMode::Implied,     illegal: true  },
+    Instruction { opcode: 0xB3, operator: Operator::LAX, mode: AddressingMode::IndirectY,   illegal: true  },
+    Instruction { opcode: 0xB4, operator: Operator::LDY, mode: AddressingMode::ZeroPageX,   illegal: false },
+    Instruction { opcode: 0xB5, operator: Operator::LDA, mode: AddressingMode::ZeroPageX,   illegal: false },
+    Instruction { opcode: 0xB6, operator: Operator::LDX, mode: AddressingMode::ZeroPageY,   illegal: false },
+    Instruction { opcode: 0xB7, operator: Operator::LAX, mode: AddressingMode::ZeroPageY,   illegal: true  },
+    Instruction { opcode: 0xB8, operator: Operator::CLV, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0xB9, operator: Operator::LDA, mode: AddressingMode::AbsoluteY,   illegal: false },
+    Instruction { opcode: 0xBA, operator: Operator::TSX, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0xBB, operator: Operator::LAS, mode: AddressingMode::AbsoluteY,   illegal: true  },
+    Instruction { opcode: 0xBC, operator: Operator::LDY, mode: AddressingMode::AbsoluteX,   illegal: false },
+    Instruction { opcode: 0xBD, operator: Operator::LDA, mode: AddressingMode::AbsoluteX,   illegal: false },
+    Instruction { opcode: 0xBE, operator: Operator::LDX, mode: AddressingMode::AbsoluteY,   illegal: false },
+    Instruction { opcode: 0xBF, operator: Operator::LAX, mode: AddressingMode::AbsoluteY,   illegal: true  },
+
+    Instruction { opcode: 0xC0, operator: Operator::CPY, mode: AddressingMode::Immediate,   illegal: false },
+    Instruction { opcode: 0xC1, operator: Operator::CMP, mode: AddressingMode::IndirectX,   illegal: false },
+    Instruction { opcode: 0xC2, operator: Operator::NOP, mode: AddressingMode::Immediate,   illegal: true  },
+    Instruction { opcode: 0xC3, operator: Operator::DCP, mode: AddressingMode::IndirectX,   illegal: true  },
+    Instruction { opcode: 0xC4, operator: Operator::CPY, mode: AddressingMode::ZeroPage,    illegal: false },
+    Instruction { opcode: 0xC5, operator: Operator::CMP, mode: AddressingMode::ZeroPage,    illegal: false },
+    Instruction { opcode: 0xC6, operator: Operator::DEC, mode: AddressingMode::ZeroPage,    illegal: false },
+    Instruction { opcode: 0xC7, operator: Operator::DCP, mode: AddressingMode::ZeroPage,    illegal: true  },
+    Instruction { opcode: 0xC8, operator: Operator::INY, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0xC9, operator: Operator::CMP, mode: AddressingMode::Immediate,   illegal: false },
+    Instruction { opcode: 0xCA, operator: Operator::DEX, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0xCB, operator: Operator::AXS, mode: AddressingMode::Immediate,   illegal: true  },
+    Instruction { opcode: 0xCC, operator: Operator::CPY, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0xCD, operator: Operator::CMP, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0xCE, operator: Operator::DEC, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0xCF, operator: Operator::DCP, mode: AddressingMode::Absolute,    illegal: true  },
+
+    Instruction { opcode: 0xD0, operator: Operator::BNE, mode: AddressingMode::Relative,    illegal: false },
+    Instruction { opcode: 0xD1, operator: Operator::CMP, mode: AddressingMode::IndirectY,   illegal: false },
+    Instruction { opcode: 0xD2, operator: Operator::KIL, mode: AddressingMode::Implied,     illegal: true  },
+    Instruction { opcode: 0xD3, operator: Operator::DCP, mode: AddressingMode::IndirectY,   illegal: true  },
+    Instruction { opcode: 0xD4, operator: Operator::NOP, mode: AddressingMode::ZeroPageX,   illegal: true  },
+    Instruction { opcode: 0xD5, operator: Operator::CMP, mode: AddressingMode::ZeroPageX,   illegal: false },
+    Instruction { opcode: 0xD6, operator: Operator::DEC, mode: AddressingMode::ZeroPageX,   illegal: false },
+    Instruction { opcode: 0xD7, operator: Operator::DCP, mode: AddressingMode::ZeroPageX,   illegal: true  },
+    Instruction { opcode: 0xD8, operator: Operator::CLD, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0xD9, operator: Operator::CMP, mode: AddressingMode::AbsoluteY,   illegal: false },
+    Instruction { opcode: 0xDA, operator: Operator::NOP, mode: AddressingMode::Implied,     illegal: true  },
+    Instruction { opcode: 0xDB, operator: Operator::DCP, mode: AddressingMode::AbsoluteY,   illegal: true  },
+    Instruction { opcode: 0xDC, operator: Operator::NOP, mode: AddressingMode::AbsoluteX,   illegal: true  },
+    Instruction { opcode: 0xDD, operator: Operator::CMP, mode: AddressingMode::AbsoluteX,   illegal: false },
+    Instruction { opcode: 0xDE, operator: Operator::DEC, mode: AddressingMode::AbsoluteX,   illegal: false },
+    Instruction { opcode: 0xDF, operator: Operator::DCP, mode: AddressingMode::AbsoluteX,   illegal: true  },
+
+    Instruction { opcode: 0xE0, operator: Operator::CPX, mode: AddressingMode::Immediate,   illegal: false },
+    Instruction { opcode: 0xE1, operator: Operator::SBC, mode: AddressingMode::IndirectX,   illegal: false },
+    Instruction { opcode: 0xE2, operator: Operator::NOP, mode: AddressingMode::Immediate,   illegal: true  },
+    Instruction { opcode: 0xE3, operator: Operator::ISC, mode: AddressingMode::IndirectX,   illegal: true  },
+    Instruction { opcode: 0xE4, operator: Operator::CPX, mode: AddressingMode::ZeroPage,    illegal: false },
+    Instruction { opcode: 0xE5, operator: Operator::SBC, mode: AddressingMode::ZeroPage,    illegal: false },
+    Instruction { opcode: 0xE6, operator: Operator::INC, mode: AddressingMode::ZeroPage,    illegal: false },
+    Instruction { opcode: 0xE7, operator: Operator::ISC, mode: AddressingMode::ZeroPage,    illegal: true  },
+    Instruction { opcode: 0xE8, operator: Operator::INX, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0xE9, operator: Operator::SBC, mode: AddressingMode::Immediate,   illegal: false },
+    Instruction { opcode: 0xEA, operator: Operator::NOP, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0xEB, operator: Operator::SBC, mode: AddressingMode::Immediate,   illegal: true  },
+    Instruction { opcode: 0xEC, operator: Operator::CPX, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0xED, operator: Operator::SBC, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0xEE, operator: Operator::INC, mode: AddressingMode::Absolute,    illegal: false },
+    Instruction { opcode: 0xEF, operator: Operator::ISC, mode: AddressingMode::Absolute,    illegal: true  },
+
+    Instruction { opcode: 0xF0, operator: Operator::BEQ, mode: AddressingMode::Relative,    illegal: false },
+    Instruction { opcode: 0xF1, operator: Operator::SBC, mode: AddressingMode::IndirectY,   illegal: false },
+    Instruction { opcode: 0xF2, operator: Operator::KIL, mode: AddressingMode::Implied,     illegal: true  },
+    Instruction { opcode: 0xF3, operator: Operator::ISC, mode: AddressingMode::IndirectY,   illegal: true  },
+    Instruction { opcode: 0xF4, operator: Operator::NOP, mode: AddressingMode::ZeroPageX,   illegal: true  },
+    Instruction { opcode: 0xF5, operator: Operator::SBC, mode: AddressingMode::ZeroPageX,   illegal: false },
+    Instruction { opcode: 0xF6, operator: Operator::INC, mode: AddressingMode::ZeroPageX,   illegal: false },
+    Instruction { opcode: 0xF7, operator: Operator::ISC, mode: AddressingMode::ZeroPageX,   illegal: true  },
+    Instruction { opcode: 0xF8, operator: Operator::SED, mode: AddressingMode::Implied,     illegal: false },
+    Instruction { opcode: 0xF9, operator: Operator::SBC, mode: AddressingMode::AbsoluteY,   illegal: false },
+    Instruction { opcode: 0xFA, operator: Operator::NOP, mode: AddressingMode::Implied,     illegal: true  },
+    Instruction { opcode: 0xFB, operator: Operator::ISC, mode: AddressingMode::AbsoluteY,   illegal: true  },
+    Instruction { opcode: 0xFC, operator: Operator::NOP, mode: AddressingMode::AbsoluteX,   illegal: true  },
+    Instruction { opcode: 0xFD, operator: Operator::SBC, mode: AddressingMode::AbsoluteX,   illegal: false },
+    Instruction { opcode: 0xFE, operator: Operator::INC, mode: AddressingMode::AbsoluteX,   illegal: false },
+    Instruction { opcode: 0xFF, operator: Operator::ISC, mode: AddressingMode::AbsoluteX,   illegal: true  },
 ];
 
-impl Bus {
-    pub fn fetch_instruction (&mut self, address: u16) -> (Instruction, Operand, u16) {
-        let opcode = self.read(address);
-        let instruction = INSTRUCTIONS[opcode as usize];
-        let operand = match instruction.mode {
+impl Instruction {
+    pub fn from_opcode (opcode: u8) -> Self {
+        INSTRUCTIONS[opcode as usize]
+    }
+
+    /**
+     * Bytes taken by the opcode and its operand
+     */
+    pub fn length (&self) -> u16 {
+        match self.mode {
             AddressingMode::Implied     |
-            AddressingMode::Accumulator => Operand::None,
+            AddressingMode::Accumulator => 1,
             AddressingMode::Immediate   |
             AddressingMode::Relative    |
             AddressingMode::ZeroPage    |
             AddressingMode::ZeroPageX   |
             AddressingMode::ZeroPageY   |
             AddressingMode::IndirectX   |
-            AddressingMode::IndirectY   => Operand::Byte(self.read(address.wrapping_add(1))),
+            AddressingMode::IndirectY   => 2,
             AddressingMode::Absolute    |
             AddressingMode::AbsoluteX   |
             AddressingMode::AbsoluteY   |
-            AddressingMode::Indirect    => Operand::Address((self.read(address.wrapping_add(2)) as u16) << 8 | self.read(address.wrapping_add(1)) as u16),
-        };
-        let read = match operand {
-            Operand::None               => 1,
-            Operand::Byte (_)           => 2,
-            Operand::Address (_)        => 3,
-        };
+            AddressingMode::Indirect    => 3,
+        }
+    }
 
-        (instruction, operand, read)
+    fn access (&self) -> Access {
+        match self.operator {
+            Operator::SAX | Operator::SHA | Operator::SHX | Operator::SHY |
+            Operator::STA | Operator::STX | Operator::STY | Operator::TAS => Access::Write,
+            Operator::ASL | Operator::DCP | Operator::DEC | Operator::INC |
+            Operator::ISC | Operator::LSR | Operator::RLA | Operator::ROL |
+            Operator::ROR | Operator::RRA | Operator::SLO | Operator::SRE => Access::ReadModifyWrite,
+            _ => Access::Read,
+        }
     }
 }
 
@@ -416,961 +435,482 @@ fn same_page (a: u16, b: u16) -> bool {
     (a & 0xFF00) == (b & 0xFF00)
 }
 
+/**
+ * Instructions run one bus access per cycle, in the order the 6502 performs them, including dummy reads and writes
+ * http://nesdev.org/6502_cpu.txt
+ */
 impl Cpu {
-    /**
-     * Interpret the encoded operand, notably read effective addresses
-     */
-    fn decode (&mut self, bus: &mut Bus, instruction: Instruction, operand: Operand) -> Operand {
-        let (operand_decoded, page_crossed) = match (instruction.mode, operand) {
-            (AddressingMode::Implied,       Operand::None)              => (Operand::None, false),
-            (AddressingMode::Accumulator,   Operand::None)              => (Operand::Byte(self.a), false),
-            (AddressingMode::Immediate,     Operand::Byte (byte))       => (Operand::Byte(byte), false),
-            (AddressingMode::Relative,      Operand::Byte (byte))       => (Operand::Address(self.pc.wrapping_add(byte as i8 as u16)), false),
-            (AddressingMode::Absolute,      Operand::Address (address)) => (Operand::Address(address), false),
-            (AddressingMode::AbsoluteX,     Operand::Address (address)) => (
-                Operand::Address(address.wrapping_add(self.x as u16)),
-                !same_page(address.wrapping_add(self.x as u16), address)
-            ),
-            (AddressingMode::AbsoluteY,     Operand::Address (address)) => (
-                Operand::Address(address.wrapping_add(self.y as u16)),
-                !same_page(address.wrapping_add(self.y as u16), address)
-            ),
-            (AddressingMode::ZeroPage,      Operand::Byte (address))    => (Operand::Address(address as u16), false),
-            (AddressingMode::ZeroPageX,     Operand::Byte (address))    => (Operand::Address(address.wrapping_add(self.x) as u16), false),
-            (AddressingMode::ZeroPageY,     Operand::Byte (address))    => (Operand::Address(address.wrapping_add(self.y) as u16), false),
-            (AddressingMode::Indirect,      Operand::Address (ptr))     => {
-                // Simulate fetch error @ page boundary
-                let page = ptr & 0xFF00;
-                let address = (bus.read(page | (ptr as u8).wrapping_add(1) as u16) as u16) << 8 | bus.read(ptr) as u16;
-
-                (Operand::Address(address), false)
-            },
-            (AddressingMode::IndirectX,     Operand::Byte (ptr))        => {
-                let address = (bus.read(ptr.wrapping_add(self.x + 1) as u16) as u16) << 8 | bus.read(ptr.wrapping_add(self.x) as u16) as u16;
-                (Operand::Address(address), false)
-            },
-            (AddressingMode::IndirectY,     Operand::Byte (ptr))        => {
-                let address = (bus.read(ptr.wrapping_add(1) as u16) as u16) << 8 | bus.read(ptr as u16) as u16;
-
-                (
-                    Operand::Address(address.wrapping_add(self.y as u16)),
-                    !same_page(address.wrapping_add(self.y as u16), address)
-                )
-            },
-            (_, _) => unreachable!(),
-        };
-
-        if instruction.illegal {
-            log::warn!("Encountered illegal instruction: {:02X} ({:?} {:?})", instruction.opcode, instruction.operator, instruction.mode);
-        }
-
-        if instruction.extra_on_page_cross && page_crossed {
-            self.cycles += 1;
-        }
-
-        operand_decoded
+    fn fetch (&mut self, bus: &mut Bus) -> u8 {
+        let data = bus.read(self.pc);
+        self.pc = self.pc.wrapping_add(1);
+        data
     }
 
     /**
-     * Fetch, decode and execute next instruction
+     * First cycle of every instruction
      */
-    pub fn execute (&mut self, bus: &mut Bus) {
-        let (instruction, operand, read) = bus.fetch_instruction(self.pc);
-        self.pc += read;
+    pub (super) fn fetch_opcode (&mut self, bus: &mut Bus) {
+        self.instruction = Instruction::from_opcode(self.fetch(bus));
+        self.crossed = false;
 
-        let operand = self.decode(bus, instruction, operand);
-
-        // log::trace!("PC:{:04X} A:{:02X} X:{:02X} Y:{:02X} P:{:08b} SP:{:02X} PPU:---,--- CYC:{}", self.pc, self.a, self.x, self.y, self.status, self.sp, self.clock.cycles);
-        // log::trace!("{:02X}", instruction.opcode);
-
-        match instruction.operator {
-            Operator::ADC => { self.adc(operand, bus); },
-            Operator::AND => { self.and(operand, bus); },
-            Operator::ASL => { self.asl(operand, bus); },
-            Operator::BCC => { self.bcc(operand, bus); },
-            Operator::BCS => { self.bcs(operand, bus); },
-            Operator::BEQ => { self.beq(operand, bus); },
-            Operator::BIT => { self.bit(operand, bus); },
-            Operator::BMI => { self.bmi(operand, bus); },
-            Operator::BNE => { self.bne(operand, bus); },
-            Operator::BPL => { self.bpl(operand, bus); },
-            Operator::BRK => { self.brk(operand, bus); },
-            Operator::BVC => { self.bvc(operand, bus); },
-            Operator::BVS => { self.bvs(operand, bus); },
-            Operator::CLC => { self.clc(operand, bus); },
-            Operator::CLI => { self.cli(operand, bus); },
-            Operator::CLV => { self.clv(operand, bus); },
-            Operator::CLD => { self.cld(operand, bus); },
-            Operator::CMP => { self.cmp(operand, bus); },
-            Operator::CPX => { self.cpx(operand, bus); },
-            Operator::CPY => { self.cpy(operand, bus); },
-            Operator::DEC => { self.dec(operand, bus); },
-            Operator::DEX => { self.dex(operand, bus); },
-            Operator::DEY => { self.dey(operand, bus); },
-            Operator::EOR => { self.eor(operand, bus); },
-            Operator::INC => { self.inc(operand, bus); },
-            Operator::INX => { self.inx(operand, bus); },
-            Operator::INY => { self.iny(operand, bus); },
-            Operator::JMP => { self.jmp(operand, bus); },
-            Operator::JSR => { self.jsr(operand, bus); },
-            Operator::LDX => { self.ldx(operand, bus); },
-            Operator::LDA => { self.lda(operand, bus); },
-            Operator::LDY => { self.ldy(operand, bus); },
-            Operator::LSR => { self.lsr(operand, bus); },
-            Operator::NOP => { self.nop(operand, bus); },
-            Operator::ORA => { self.ora(operand, bus); },
-            Operator::PHA => { self.pha(operand, bus); },
-            Operator::PHP => { self.php(operand, bus); },
-            Operator::PLA => { self.pla(operand, bus); },
-            Operator::PLP => { self.plp(operand, bus); },
-            Operator::ROL => { self.rol(operand, bus); },
-            Operator::ROR => { self.ror(operand, bus); },
-            Operator::RTI => { self.rti(operand, bus); },
-            Operator::RTS => { self.rts(operand, bus); },
-            Operator::SBC => { self.sbc(operand, bus); },
-            Operator::SEC => { self.sec(operand, bus); },
-            Operator::SED => { self.sed(operand, bus); },
-            Operator::SEI => { self.sei(operand, bus); },
-            Operator::STA => { self.sta(operand, bus); },
-            Operator::STX => { self.stx(operand, bus); },
-            Operator::STY => { self.sty(operand, bus); },
-            Operator::TAX => { self.tax(operand, bus); },
-            Operator::TAY => { self.tay(operand, bus); },
-            Operator::TSX => { self.tsx(operand, bus); },
-            Operator::TXA => { self.txa(operand, bus); },
-            Operator::TXS => { self.txs(operand, bus); },
-            Operator::TYA => { self.tya(operand, bus); },
-            _ => unimplemented!(),
-        };
-
-        self.cycles += instruction.cycles;
-    }
-
-    fn branch (&mut self, address: u16) {
-        self.cycles += if !same_page(address, self.pc) { 2 } else { 1 };
-        self.pc = address;
+        if self.instruction.illegal {
+            log::warn!("Encountered illegal instruction: {:02X} ({:?} {:?})", self.instruction.opcode, self.instruction.operator, self.instruction.mode);
+        }
     }
 
-    fn nop (&mut self, _operand: Operand, _bus: &mut Bus) {}
-
-    /**
-     * Add Memory to Accumulator with Carry
-     */
-    fn adc (&mut self, operand: Operand, bus: &mut Bus) {
-        let value = match operand {
-            Operand::Byte (byte) => byte,
-            Operand::Address (address) => bus.read(address),
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        let res = self.a as u16 + value as u16 + self.get_flag(StatusFlag::Carry) as u16;
-        let overflow = (!(self.a ^ value) & (self.a ^ res as u8) & StatusFlag::Negative as u8) != 0;
-        
-        self.a = res as u8;
-        
-        self.set_flag(StatusFlag::Carry, res >> 8 != 0);
-        self.set_flag(StatusFlag::Zero, self.a == 0);
-        self.set_flag(StatusFlag::Overflow, overflow);
-        self.set_flag(StatusFlag::Negative, (self.a as i8) < 0);
-    }
-    
     /**
-     * AND Memory with Accumulator
-     */
-    fn and (&mut self, operand: Operand, bus: &mut Bus) {
-        let value = match operand {
-            Operand::Byte (byte) => byte,
-            Operand::Address (address) => bus.read(address),
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.a &= value;
-    
-        self.set_flag(StatusFlag::Zero, self.a == 0);
-        self.set_flag(StatusFlag::Negative, (self.a as i8) < 0);
-    }
-    
-    /**
-     * Shift Left One Bit
-     */
-    fn asl (&mut self, operand: Operand, bus: &mut Bus) {
-        fn asl_inner (cpu: &mut Cpu, value: u8) -> u8 {
-            let (new_value, carry) = (value << 1, value & StatusFlag::Negative as u8);
-            cpu.set_flag(StatusFlag::Carry, carry != 0);
-            cpu.set_flag(StatusFlag::Zero, new_value == 0);
-            cpu.set_flag(StatusFlag::Negative, (new_value as i8) < 0);
-    
-            new_value
-        }
-    
-        match operand {
-            Operand::Byte (byte) => {
-                let new_value = asl_inner(self, byte);
-                self.a = new_value;
+     * Run the next cycle of the current instruction, past the opcode fetch
+     * Returns whether the instruction is complete
+     */
+    pub (super) fn instruction_cycle (&mut self, bus: &mut Bus) -> bool {
+        match self.instruction.operator {
+            Operator::BRK => self.interrupt_cycle(bus, Interrupt::IRQ, true),
+            Operator::JMP => self.jmp_cycle(bus),
+            Operator::JSR => self.jsr_cycle(bus),
+            Operator::RTS => self.rts_cycle(bus),
+            Operator::RTI => self.rti_cycle(bus),
+            Operator::PHA | Operator::PHP => self.push_cycle(bus),
+            Operator::PLA | Operator::PLP => self.pull_cycle(bus),
+            Operator::BCC | Operator::BCS | Operator::BEQ | Operator::BMI |
+            Operator::BNE | Operator::BPL | Operator::BVC | Operator::BVS => self.branch_cycle(bus),
+            _ => match self.instruction.mode {
+                AddressingMode::Implied | AddressingMode::Accumulator => {
+                    bus.read(self.pc);
+                    self.implied_operation();
+                    true
+                },
+                AddressingMode::Immediate => {
+                    let value = self.fetch(bus);
+                    self.read_operation(value);
+                    true
+                },
+                _ => self.addressed_cycle(bus),
             },
-            Operand::Address (address) => {
-                let new_value = asl_inner(self, bus.read(address));
-                bus.write(address, new_value);
-            },
-            _ => panic!("Invalid addressing mode"),
-        };
-    }
-    
-    /**
-     * Branch on Carry Clear
-     */
-    fn bcc (&mut self, operand: Operand, _bus: &mut Bus) {
-        let address = match operand {
-            Operand::Address (address) => address,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        if !self.get_flag(StatusFlag::Carry) {
-            self.branch(address);
-        }
-    }
-    
-    /**
-     * Branch on Carry Set
-     */
-    fn bcs (&mut self, operand: Operand, _bus: &mut Bus) {
-        let address = match operand {
-            Operand::Address (address) => address,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        if self.get_flag(StatusFlag::Carry) {
-            self.branch(address);
         }
     }
-    
+
     /**
-     * Branch on Result Zero
+     * Interrupt sequence, shared with BRK
+     * An NMI occurring before the vector is fetched hijacks IRQ and BRK sequences
+     * https://wiki.nesdev.org/w/index.php/CPU_interrupts
      */
-    fn beq (&mut self, operand: Operand, _bus: &mut Bus) {
-        let address = match operand {
-            Operand::Address (address) => address,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        if self.get_flag(StatusFlag::Zero) {
-            self.branch(address);
+    pub (super) fn interrupt_cycle (&mut self, bus: &mut Bus, interrupt: Interrupt, brk: bool) -> bool {
+        match self.cycles {
+            2 => {
+                // BRK skips a padding byte, hardware interrupts don't increment PC
+                bus.read(self.pc);
+                if brk {
+                    self.pc = self.pc.wrapping_add(1);
+                }
+            },
+            3 ..= 5 if interrupt == Interrupt::RESET => {
+                // Writes are suppressed during reset
+                bus.read(MEMORY_RAM_STACK_START + self.sp as u16);
+                self.sp = self.sp.wrapping_sub(1);
+            },
+            3 => self.push_stack(bus, (self.pc >> 8) as u8),
+            4 => self.push_stack(bus, self.pc as u8),
+            5 => {
+                let status = if brk { self.php() } else { self.status | StatusFlag::Unused as u8 };
+                self.push_stack(bus, status);
+            },
+            6 => {
+                let vector = match interrupt {
+                    Interrupt::RESET => Interrupt::RESET,
+                    _ if self.nmi_pending => {
+                        self.nmi_pending = false;
+                        Interrupt::NMI
+                    },
+                    _ => Interrupt::IRQ,
+                };
+
+                self.address = vector as u16;
+                self.data = bus.read(self.address);
+                self.set_flag(StatusFlag::DisableInterrupt, true);
+            },
+            _ => {
+                self.pc = (bus.read(self.address + 1) as u16) << 8 | self.data as u16;
+                return true;
+            },
         }
+
+        false
     }
-    
-    /**
-     * Test Bits in Memory with Accumulator
-     */
-    fn bit (&mut self, operand: Operand, bus: &mut Bus) {
-        let value = match operand {
-            Operand::Address (address) => bus.read(address),
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.set_flag(StatusFlag::Zero, (self.a & value) == 0);
-        self.set_flag(StatusFlag::Overflow, (value & StatusFlag::Overflow as u8) != 0);
-        self.set_flag(StatusFlag::Negative, (value & StatusFlag::Negative as u8) != 0);
-    }
-    
-    /**
-     * Branch on Result Minus
-     */
-    fn bmi (&mut self, operand: Operand, _bus: &mut Bus) {
-        let address = match operand {
-            Operand::Address (address) => address,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        if self.get_flag(StatusFlag::Negative) {
-            self.branch(address);
+
+    fn jmp_cycle (&mut self, bus: &mut Bus) -> bool {
+        match (self.instruction.mode, self.cycles) {
+            (_, 2) => {
+                self.address = self.fetch(bus) as u16;
+            },
+            (AddressingMode::Absolute, _) => {
+                self.pc = (self.fetch(bus) as u16) << 8 | self.address;
+                return true;
+            },
+            (_, 3) => {
+                self.address |= (self.fetch(bus) as u16) << 8;
+            },
+            (_, 4) => {
+                self.data = bus.read(self.address);
+            },
+            (_, _) => {
+                // The pointer's high byte is fetched without carrying into the next page
+                let address = (self.address & 0xFF00) | (self.address as u8).wrapping_add(1) as u16;
+                self.pc = (bus.read(address) as u16) << 8 | self.data as u16;
+                return true;
+            },
         }
+
+        false
     }
-    
-    /**
-     * Branch on Result not Zero
-     */
-    fn bne (&mut self, operand: Operand, _bus: &mut Bus) {
-        let address = match operand {
-            Operand::Address (address) => address,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        if !self.get_flag(StatusFlag::Zero) {
-            self.branch(address);
+
+    fn jsr_cycle (&mut self, bus: &mut Bus) -> bool {
+        match self.cycles {
+            2 => {
+                self.data = self.fetch(bus);
+            },
+            3 => {
+                bus.read(MEMORY_RAM_STACK_START + self.sp as u16);
+            },
+            // PC points at the last byte of the instruction
+            4 => self.push_stack(bus, (self.pc >> 8) as u8),
+            5 => self.push_stack(bus, self.pc as u8),
+            _ => {
+                self.pc = (bus.read(self.pc) as u16) << 8 | self.data as u16;
+                return true;
+            },
         }
+
+        false
     }
-    
-    /**
-     * Branch on Result Plus
-     */
-    fn bpl (&mut self, operand: Operand, _bus: &mut Bus) {
-        let address = match operand {
-            Operand::Address (address) => address,
-            _ => panic!("Invalid addressing mode"),
-        };
 
-        if !self.get_flag(StatusFlag::Negative) {
-            self.branch(address);
+    fn rts_cycle (&mut self, bus: &mut Bus) -> bool {
+        match self.cycles {
+            2 => {
+                bus.read(self.pc);
+            },
+            3 => {
+                bus.read(MEMORY_RAM_STACK_START + self.sp as u16);
+            },
+            4 => {
+                self.data = self.pop_stack(bus);
+            },
+            5 => {
+                self.pc = (self.pop_stack(bus) as u16) << 8 | self.data as u16;
+            },
+            _ => {
+                bus.read(self.pc);
+                self.pc = self.pc.wrapping_add(1);
+                return true;
+            },
         }
+
+        false
     }
 
-    /**
-     * Force Break
-     */
-    fn brk (&mut self, operand: Operand, bus: &mut Bus) {
-        if operand != Operand::None {
-            panic!("Invalid addressing mode");
+    fn rti_cycle (&mut self, bus: &mut Bus) -> bool {
+        match self.cycles {
+            2 => {
+                bus.read(self.pc);
+            },
+            3 => {
+                bus.read(MEMORY_RAM_STACK_START + self.sp as u16);
+            },
+            4 => {
+                let status = self.pop_stack(bus);
+                self.plp(status);
+            },
+            5 => {
+                self.data = self.pop_stack(bus);
+            },
+            _ => {
+                self.pc = (self.pop_stack(bus) as u16) << 8 | self.data as u16;
+                return true;
+            },
         }
-    
-        let (hi, lo) = (((self.pc + 2) >> 8) as u8, (self.pc + 2) as u8);
-        self.push_stack(bus, hi);
-        self.push_stack(bus, lo);
-    
-        self.push_stack(bus, self.status | (StatusFlag::Break as u8) | (StatusFlag::Unused as u8));
-        self.set_flag(StatusFlag::DisableInterrupt, true);
-    
-        let address = (bus.read(Interrupt::IRQ as u16 + 1) as u16) << 8 | bus.read(Interrupt::IRQ as u16) as u16;
-        self.pc = address;
+
+        false
     }
-    
-    /**
-     * Branch on Overflow Clear
-     */
-    fn bvc (&mut self, operand: Operand, _bus: &mut Bus) {
-        let address = match operand {
-            Operand::Address (address) => address,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        if !self.get_flag(StatusFlag::Overflow) {
-            self.branch(address);
+
+    fn push_cycle (&mut self, bus: &mut Bus) -> bool {
+        match self.cycles {
+            2 => {
+                bus.read(self.pc);
+                false
+            },
+            _ => {
+                let data = match self.instruction.operator {
+                    Operator::PHP => self.php(),
+                    _ => self.a,
+                };
+
+                self.push_stack(bus, data);
+                true
+            },
         }
     }
-    
-    /**
-     * Branch on Overflow Set
-     */
-    fn bvs (&mut self, operand: Operand, _bus: &mut Bus) {
-        let address = match operand {
-            Operand::Address (address) => address,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        if self.get_flag(StatusFlag::Overflow) {
-            self.branch(address);
+
+    fn pull_cycle (&mut self, bus: &mut Bus) -> bool {
+        match self.cycles {
+            2 => {
+                bus.read(self.pc);
+                false
+            },
+            3 => {
+                bus.read(MEMORY_RAM_STACK_START + self.sp as u16);
+                false
+            },
+            _ => {
+                let data = self.pop_stack(bus);
+
+                match self.instruction.operator {
+                    Operator::PLP => self.plp(data),
+                    _ => self.pla(data),
+                }
+
+                true
+            },
         }
     }
-    
-    /**
-     * Clear Carry Flag
-     */
-    fn clc (&mut self, operand: Operand, _bus: &mut Bus) {
-        if operand != Operand::None {
-            panic!("Invalid addressing mode");
-        };
-    
-        self.set_flag(StatusFlag::Carry, false);
-    }
-    
-    /**
-     * Clear Interrupt Disable Bit
-     */
-    fn cli (&mut self, operand: Operand, _bus: &mut Bus) {
-        if operand != Operand::None {
-            panic!("Invalid addressing mode");
-        };
-    
-        self.set_flag(StatusFlag::DisableInterrupt, false);
-    }
-    
-    /**
-     * Clear Overflow Flag
-     */
-    fn clv (&mut self, operand: Operand, _bus: &mut Bus) {
-        if operand != Operand::None {
-            panic!("Invalid addressing mode");
-        };
-    
-        self.set_flag(StatusFlag::Overflow, false);
-    }
-    
-    /**
-     * Clear Decimal Mode
-     */
-    fn cld (&mut self, operand: Operand, _bus: &mut Bus) {
-        if operand != Operand::None {
-            panic!("Invalid addressing mode");
-        };
-    
-        self.set_flag(StatusFlag::Decimal, false);
-    }
-    
-    /**
-     * Compare Memory with Accumulator
-     */
-    fn cmp (&mut self, operand: Operand, bus: &mut Bus) {
-        let value = match operand {
-            Operand::Byte (byte) => byte,
-            Operand::Address (address) => bus.read(address),
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.set_flag(StatusFlag::Carry, self.a >= value);
-        self.set_flag(StatusFlag::Zero, self.a == value);
-        self.set_flag(StatusFlag::Negative, (self.a.wrapping_sub(value) as i8) < 0);
-    }
-    
-    /**
-     * Compare Memory and Index X
-     */
-    fn cpx (&mut self, operand: Operand, bus: &mut Bus) {
-        let value = match operand {
-            Operand::Byte (byte) => byte,
-            Operand::Address (address) => bus.read(address),
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.set_flag(StatusFlag::Carry, self.x >= value);
-        self.set_flag(StatusFlag::Zero, self.x == value);
-        self.set_flag(StatusFlag::Negative, (self.x.wrapping_sub(value) as i8) < 0);
-    }
-    
-    /**
-     * Compare Memory and Index Y
-     */
-    fn cpy (&mut self, operand: Operand, bus: &mut Bus) {
-        let value = match operand {
-            Operand::Byte (byte) => byte,
-            Operand::Address (address) => bus.read(address),
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.set_flag(StatusFlag::Carry, self.y >= value);
-        self.set_flag(StatusFlag::Zero, self.y == value);
-        self.set_flag(StatusFlag::Negative, (self.y.wrapping_sub(value) as i8) < 0);
-    }
-    
-    /**
-     * Decrement Memory by One
-     */
-    fn dec (&mut self, operand: Operand, bus: &mut Bus) {
-        let address = match operand {
-            Operand::Address (address) => address,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        let value = bus.read(address).wrapping_sub(1);
-        bus.write(address, value);
-    
-        self.set_flag(StatusFlag::Zero, value == 0);
-        self.set_flag(StatusFlag::Negative, (value as i8) < 0);
-    }
-    
-    /**
-     * Decrement Index X by One
-     */
-    fn dex (&mut self, operand: Operand, _bus: &mut Bus) {
-        let value = match operand {
-            Operand::None => self.x,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.x = value.wrapping_sub(1);
-    
-        self.set_flag(StatusFlag::Zero, self.x == 0);
-        self.set_flag(StatusFlag::Negative, (self.x as i8) < 0);
-    }
-    
-    /**
-     * Decrement Index Y by One
-     */
-    fn dey (&mut self, operand: Operand, _bus: &mut Bus) {
-        let value = match operand {
-            Operand::None => self.y,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.y = value.wrapping_sub(1);
-    
-        self.set_flag(StatusFlag::Zero, self.y == 0);
-        self.set_flag(StatusFlag::Negative, (self.y as i8) < 0);
-    }
-    
-    /**
-     * Exclusive-OR Memory with Accumulator
-     */
-    fn eor (&mut self, operand: Operand, bus: &mut Bus) {
-        let value = match operand {
-            Operand::Byte (byte) => byte,
-            Operand::Address (address) => bus.read(address),
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.a ^= value;
-    
-        self.set_flag(StatusFlag::Zero, self.a == 0);
-        self.set_flag(StatusFlag::Negative, (self.a as i8) < 0);
-    }
-    
-    /**
-     * Increment Memory by One
-     */
-    fn inc (&mut self, operand: Operand, bus: &mut Bus) {
-        let address = match operand {
-            Operand::Address (address) => address,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        let value = bus.read(address).wrapping_add(1);
-        bus.write(address, value);
-    
-        self.set_flag(StatusFlag::Zero, value == 0);
-        self.set_flag(StatusFlag::Negative, (value as i8) < 0);
-    }
-    
-    /**
-     * Increment Index X by One
-     */
-    fn inx (&mut self, operand: Operand, _bus: &mut Bus) {
-        let value = match operand {
-            Operand::None => self.x,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.x = value.wrapping_add(1);
-    
-        self.set_flag(StatusFlag::Zero, self.x == 0);
-        self.set_flag(StatusFlag::Negative, (self.x as i8) < 0);
-    }
-    
-    /**
-     * Increment Index Y by One
-     */
-    fn iny (&mut self, operand: Operand, _bus: &mut Bus) {
-        let value = match operand {
-            Operand::None => self.y,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.y = value.wrapping_add(1);
-    
-        self.set_flag(StatusFlag::Zero, self.y == 0);
-        self.set_flag(StatusFlag::Negative, (self.y as i8) < 0);
-    }
-    
-    /**
-     * Jump to New Location
-     */
-    fn jmp (&mut self, operand: Operand, _bus: &mut Bus) {
-        let address = match operand {
-            Operand::Address (address) => address,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.pc = address;
-    }
-    
-    /**
-     * Jump to New Location Saving Return Address
-     */
-    fn jsr (&mut self, operand: Operand, bus: &mut Bus) {
-        let address = match operand {
-            Operand::Address (address) => address,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        let (hi, lo) = (((self.pc - 1) >> 8) as u8, (self.pc - 1) as u8);
-        self.push_stack(bus, hi);
-        self.push_stack(bus, lo);
-        self.pc = address;
-    }
-    
-    /**
-     * Loads a byte of memory into the X register setting the zero and negative flags as appropriate.
-     */
-    fn ldx (&mut self, operand: Operand, bus: &mut Bus) {
-        let value = match operand {
-            Operand::Byte (byte) => byte,
-            Operand::Address (address) => bus.read(address),
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.x = value;
-    
-        self.set_flag(StatusFlag::Zero, value == 0);
-        self.set_flag(StatusFlag::Negative, (value as i8) < 0);
-    }
-    
-    /**
-     * Load Accumulator with Memory
-     */
-    fn lda (&mut self, operand: Operand, bus: &mut Bus) {
-        let value = match operand {
-            Operand::Byte (byte) => byte,
-            Operand::Address (address) => bus.read(address),
-            _ => panic!("Invalid addressing mode"),
-        };
 
-        self.a = value;
+    fn branch_cycle (&mut self, bus: &mut Bus) -> bool {
+        match self.cycles {
+            2 => {
+                self.data = self.fetch(bus);
 
-        self.set_flag(StatusFlag::Zero, value == 0);
-        self.set_flag(StatusFlag::Negative, (value as i8) < 0);
-    }
-    
-    /**
-     * Load Index Y with Memory
-     */
-    fn ldy (&mut self, operand: Operand, bus: &mut Bus) {
-        let value = match operand {
-            Operand::Byte (byte) => byte,
-            Operand::Address (address) => bus.read(address),
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.y = value;
-    
-        self.set_flag(StatusFlag::Zero, value == 0);
-        self.set_flag(StatusFlag::Negative, (value as i8) < 0);
-    }
-    
-    /**
-     * Shift One Bit Right
-     */
-    fn lsr (&mut self, operand: Operand, bus: &mut Bus) {
-        fn lsr_inner (cpu: &mut Cpu, value: u8) -> u8 {
-            let (new_value, carry) = (value >> 1, value & 1);
-            cpu.set_flag(StatusFlag::Carry, carry != 0);
-            cpu.set_flag(StatusFlag::Zero, new_value == 0);
-            cpu.set_flag(StatusFlag::Negative, false);
-    
-            new_value
-        }
-    
-        match operand {
-            Operand::Byte (byte) => {
-                let new_value = lsr_inner(self, byte);
-                self.a = new_value;
+                let taken = match self.instruction.operator {
+                    Operator::BCC => self.bcc(),
+                    Operator::BCS => self.bcs(),
+                    Operator::BEQ => self.beq(),
+                    Operator::BMI => self.bmi(),
+                    Operator::BNE => self.bne(),
+                    Operator::BPL => self.bpl(),
+                    Operator::BVC => self.bvc(),
+                    _ => self.bvs(),
+                };
+
+                !taken
             },
-            Operand::Address (address) => {
-                let new_value = lsr_inner(self, bus.read(address));
-                bus.write(address, new_value);
+            3 => {
+                bus.read(self.pc);
+
+                self.address = self.pc.wrapping_add(self.data as i8 as u16);
+                self.crossed = !same_page(self.address, self.pc);
+                self.pc = (self.pc & 0xFF00) | (self.address & 0x00FF);
+
+                if !self.crossed {
+                    // A taken branch without page crossing does not poll interrupts on its last cycle
+                    self.poll = self.last_poll;
+                }
+
+                !self.crossed
             },
-            _ => panic!("Invalid addressing mode"),
-        };
-    }
-    
-    /**
-     * OR Memory with Accumulator
-     */
-    fn ora (&mut self, operand: Operand, bus: &mut Bus) {
-        let value = match operand {
-            Operand::Byte (byte) => byte,
-            Operand::Address (address) => bus.read(address),
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.a |= value;
-    
-        self.set_flag(StatusFlag::Zero, self.a == 0);
-        self.set_flag(StatusFlag::Negative, (self.a as i8) < 0);
-    }
-    
-    /**
-     * Push Accumulator on Stack
-     */
-    fn pha (&mut self, operand: Operand, bus: &mut Bus) {
-        let value = match operand {
-            Operand::None => self.a,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.push_stack(bus, value);
-    }
-    
-    /**
-     * Push Processor Status on Stack
-     */
-    fn php (&mut self, operand: Operand, bus: &mut Bus) {
-        let status = match operand {
-            Operand::None => self.status,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.push_stack(bus, status | (StatusFlag::Break as u8) | (StatusFlag::Unused as u8));
-    }
-    
-    /**
-     * Pull Accumulator from Stack
-     */
-    fn pla (&mut self, operand: Operand, bus: &mut Bus) {
-        let value = match operand {
-            Operand::None => self.pop_stack(bus),
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.a = value;
-    
-        self.set_flag(StatusFlag::Zero, self.a == 0);
-        self.set_flag(StatusFlag::Negative, (self.a & StatusFlag::Negative as u8) > 0);
+            _ => {
+                bus.read(self.pc);
+                self.pc = self.address;
+                true
+            },
+        }
     }
-    
+
     /**
-     * Pull Processor Status from Stack
+     * Instructions working on memory: compute the effective address, then read, write or modify it
      */
-    fn plp (&mut self, operand: Operand, bus: &mut Bus) {
-        if operand != Operand::None {
-            panic!("Invalid addressing mode");
+    fn addressed_cycle (&mut self, bus: &mut Bus) -> bool {
+        let access = self.instruction.access();
+
+        // Indexed writes always spend a cycle fixing the address, reads only when crossing a page
+        let fixup = if self.crossed || access != Access::Read { 1 } else { 0 };
+        let addressing = match self.instruction.mode {
+            AddressingMode::ZeroPage                                => 2,
+            AddressingMode::ZeroPageX | AddressingMode::ZeroPageY   => 3,
+            AddressingMode::Absolute                                => 3,
+            AddressingMode::AbsoluteX | AddressingMode::AbsoluteY   => 3 + fixup,
+            AddressingMode::IndirectX                               => 5,
+            AddressingMode::IndirectY                               => 4 + fixup,
+            _ => unreachable!(),
         };
-    
-        // Ignored flags, stay as-is
-        let mask = (StatusFlag::Break as u8) | (StatusFlag::Unused as u8);
-        let status = self.pop_stack(bus);
-        self.status = (status & !mask) | (self.status & mask);
-    }
 
-    /**
-     * Rotate One Bit Left
-     */
-    fn rol (&mut self, operand: Operand, bus: &mut Bus) {
-        fn ror_inner (cpu: &mut Cpu, value: u8) -> u8 {
-            let (new_value, carry) = (value << 1 | cpu.status & StatusFlag::Carry as u8, value & StatusFlag::Negative as u8);
-            cpu.set_flag(StatusFlag::Carry, carry != 0);
-            cpu.set_flag(StatusFlag::Zero, new_value == 0);
-            cpu.set_flag(StatusFlag::Negative, (new_value as i8) < 0);
-    
-            new_value
+        if self.cycles <= addressing {
+            self.address_cycle(bus);
+            return false;
         }
 
-        match operand {
-            Operand::Byte (byte) => {
-                let new_value = ror_inner(self, byte);
-                self.a = new_value;
+        match (access, self.cycles - addressing) {
+            (Access::Read, _) => {
+                let value = bus.read(self.address);
+                self.read_operation(value);
+                true
             },
-            Operand::Address (address) => {
-                let new_value = ror_inner(self, bus.read(address));
-                bus.write(address, new_value);
+            (Access::Write, _) => {
+                let data = self.write_operation();
+
+                // Unstable stores corrupt the high byte of the address when crossing a page
+                let address = match self.instruction.operator {
+                    Operator::SHA | Operator::SHX | Operator::SHY | Operator::TAS if self.crossed => {
+                        (data as u16) << 8 | (self.address & 0x00FF)
+                    },
+                    _ => self.address,
+                };
+
+                bus.write(address, data);
+                true
             },
-            _ => panic!("Invalid addressing mode"),
-        };
+            (Access::ReadModifyWrite, 1) => {
+                self.data = bus.read(self.address);
+                false
+            },
+            (Access::ReadModifyWrite, 2) => {
+                // The unmodified value is written back while the operation runs
+                bus.write(self.address, self.data);
+                self.data = self.modify_operation(self.data);
+                false
+            },
+            (Access::ReadModifyWrite, _) => {
+                bus.write(self.address, self.data);
+                true
+            },
+        }
     }
 
-    /**
-     * Rotate One Bit Right
-     */
-    fn ror (&mut self, operand: Operand, bus: &mut Bus) {
-        fn ror_inner (cpu: &mut Cpu, value: u8) -> u8 {
-            let (new_value, carry) = (value >> 1 | (cpu.status & StatusFlag::Carry as u8) << 7, value & 1);
-            cpu.set_flag(StatusFlag::Carry, carry != 0);
-            cpu.set_flag(StatusFlag::Zero, new_value == 0);
-            cpu.set_flag(StatusFlag::Negative, (new_value as i8) < 0);
-    
-            new_value
-        }
-    
-        match operand {
-            Operand::Byte (byte) => {
-                let new_value = ror_inner(self, byte);
-                self.a = new_value;
+    fn address_cycle (&mut self, bus: &mut Bus) {
+        match (self.instruction.mode, self.cycles) {
+            (AddressingMode::IndirectX, 2) | (AddressingMode::IndirectY, 2) => {
+                self.pointer = self.fetch(bus);
             },
-            Operand::Address (address) => {
-                let new_value = ror_inner(self, bus.read(address));
-                bus.write(address, new_value);
+            (_, 2) => {
+                self.address = self.fetch(bus) as u16;
             },
-            _ => panic!("Invalid addressing mode"),
-        };
+            (AddressingMode::ZeroPageX, _) => {
+                bus.read(self.address);
+                self.address = (self.address as u8).wrapping_add(self.x) as u16;
+            },
+            (AddressingMode::ZeroPageY, _) => {
+                bus.read(self.address);
+                self.address = (self.address as u8).wrapping_add(self.y) as u16;
+            },
+            (AddressingMode::Absolute, _) => {
+                self.address |= (self.fetch(bus) as u16) << 8;
+            },
+            (AddressingMode::AbsoluteX, 3) => {
+                let high = self.fetch(bus);
+                self.index(high, self.x);
+            },
+            (AddressingMode::AbsoluteY, 3) => {
+                let high = self.fetch(bus);
+                self.index(high, self.y);
+            },
+            (AddressingMode::IndirectX, 3) => {
+                bus.read(self.pointer as u16);
+                self.pointer = self.pointer.wrapping_add(self.x);
+            },
+            (AddressingMode::IndirectX, 4) | (AddressingMode::IndirectY, 3) => {
+                self.address = bus.read(self.pointer as u16) as u16;
+            },
+            (AddressingMode::IndirectX, _) => {
+                self.address |= (bus.read(self.pointer.wrapping_add(1) as u16) as u16) << 8;
+            },
+            (AddressingMode::IndirectY, 4) => {
+                let high = bus.read(self.pointer.wrapping_add(1) as u16);
+                self.index(high, self.y);
+            },
+            (_, _) => {
+                // Dummy read before the page crossing is fixed
+                let address = if self.crossed { self.address.wrapping_sub(0x100) } else { self.address };
+                bus.read(address);
+            },
+        }
     }
 
-    /**
-     * Return from Interrupt
-     */
-    fn rti (&mut self, operand: Operand, bus: &mut Bus) {
-        self.plp(operand, bus);
-        let (lo, hi) = (self.pop_stack(bus), self.pop_stack(bus));
-        let address = (hi as u16) << 8 | lo as u16;
-        
-        self.pc = address;
+    fn index (&mut self, high: u8, index: u8) {
+        let base = (high as u16) << 8 | self.address;
+        self.address = base.wrapping_add(index as u16);
+        self.crossed = !same_page(base, self.address);
+    }
+
+    fn read_operation (&mut self, value: u8) {
+        match self.instruction.operator {
+            Operator::ADC => self.adc(value),
+            Operator::ALR => self.alr(value),
+            Operator::ANC => self.anc(value),
+            Operator::AND => self.and(value),
+            Operator::ARR => self.arr(value),
+            Operator::AXS => self.axs(value),
+            Operator::BIT => self.bit(value),
+            Operator::CMP => self.cmp(value),
+            Operator::CPX => self.cpx(value),
+            Operator::CPY => self.cpy(value),
+            Operator::EOR => self.eor(value),
+            Operator::LAS => self.las(value),
+            Operator::LAX => self.lax(value),
+            Operator::LDA => self.lda(value),
+            Operator::LDX => self.ldx(value),
+            Operator::LDY => self.ldy(value),
+            Operator::NOP => {},
+            Operator::ORA => self.ora(value),
+            Operator::SBC => self.sbc(value),
+            Operator::XAA => self.xaa(value),
+            operator => unreachable!("{:?} does not read memory", operator),
+        }
     }
 
-    /**
-     * Return from Subroutine
-     */
-    fn rts (&mut self, operand: Operand, bus: &mut Bus) {
-        if operand != Operand::None {
-            panic!("Invalid addressing mode");
-        };
-    
-        let (lo, hi) = (self.pop_stack(bus), self.pop_stack(bus));
-        let address = (hi as u16) << 8 | lo as u16;
-    
-        self.pc = address + 1;
-    }
-    
-    /**
-     * Subtract Memory from Accumulator with Borrow
-     */
-    fn sbc (&mut self, operand: Operand, bus: &mut Bus) {
-        let value = match operand {
-            Operand::Byte (byte) => byte,
-            Operand::Address (address) => bus.read(address),
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.adc(Operand::Byte(!value), bus);
-    }
-    
-    /**
-     * Set Carry Flag
-     */
-    fn sec (&mut self, operand: Operand, _bus: &mut Bus) {
-        if operand != Operand::None {
-            panic!("Invalid addressing mode");
-        };
-    
-        self.set_flag(StatusFlag::Carry, true);
-    }
-    
-    /**
-     * Set Decimal Flag
-     */
-    fn sed (&mut self, operand: Operand, _bus: &mut Bus) {
-        if operand != Operand::None {
-            panic!("Invalid addressing mode");
-        };
-    
-        self.set_flag(StatusFlag::Decimal, true);
-    }
-    
-    /**
-     * Set Interrupt Disable Status
-     */
-    fn sei (&mut self, operand: Operand, _bus: &mut Bus) {
-        if operand != Operand::None {
-            panic!("Invalid addressing mode");
-        };
-    
-        self.set_flag(StatusFlag::DisableInterrupt, true);
-    }
-    
-    /**
-     * Store Accumulator in Memory
-     */
-    fn sta (&mut self, operand: Operand, bus: &mut Bus) {
-        let address = match operand {
-            Operand::Address (address) => address,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        bus.write(address, self.a);
-    }
-    
-    /**
-     * Store Index X in Memory
-     */
-    fn stx (&mut self, operand: Operand, bus: &mut Bus) {
-        let address = match operand {
-            Operand::Address (address) => address,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        bus.write(address, self.x);
-    }
-    
-    /**
-     * Store Index Y in Memory
-     */
-    fn sty (&mut self, operand: Operand, bus: &mut Bus) {
-        let address = match operand {
-            Operand::Address (address) => address,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        bus.write(address, self.y);
-    }
-    
-    /**
-     * Transfer Accumulator to Index X
-     */
-    fn tax (&mut self, operand: Operand, _bus: &mut Bus) {
-        let value = match operand {
-            Operand::None => self.a,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.x = value;
-    
-        self.set_flag(StatusFlag::Zero, self.x == 0);
-        self.set_flag(StatusFlag::Negative, (self.x as i8) < 0);
-    }
-    
-    /**
-     * Transfer Accumulator to Index Y
-     */
-    fn tay (&mut self, operand: Operand, _bus: &mut Bus) {
-        let value = match operand {
-            Operand::None => self.a,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.y = value;
-    
-        self.set_flag(StatusFlag::Zero, self.y == 0);
-        self.set_flag(StatusFlag::Negative, (self.y as i8) < 0);
-    }
-    
-    /**
-     * Transfer Stack Pointer to Index X
-     */
-    fn tsx (&mut self, operand: Operand, _bus: &mut Bus) {
-        let value = match operand {
-            Operand::None => self.sp,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.x = value;
-    
-        self.set_flag(StatusFlag::Zero, self.x == 0);
-        self.set_flag(StatusFlag::Negative, (self.x as i8) < 0);
-    }
-    
-    /**
-     * Transfer Index X to Accumulator
-     */
-    fn txa (&mut self, operand: Operand, _bus: &mut Bus) {
-        let value = match operand {
-            Operand::None => self.x,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.a = value;
-    
-        self.set_flag(StatusFlag::Zero, self.a == 0);
-        self.set_flag(StatusFlag::Negative, (self.a as i8) < 0);
+    fn modify_operation (&mut self, value: u8) -> u8 {
+        match self.instruction.operator {
+            Operator::ASL => self.asl(value),
+            Operator::DCP => self.dcp(value),
+            Operator::DEC => self.dec(value),
+            Operator::INC => self.inc(value),
+            Operator::ISC => self.isc(value),
+            Operator::LSR => self.lsr(value),
+            Operator::RLA => self.rla(value),
+            Operator::ROL => self.rol(value),
+            Operator::ROR => self.ror(value),
+            Operator::RRA => self.rra(value),
+            Operator::SLO => self.slo(value),
+            Operator::SRE => self.sre(value),
+            operator => unreachable!("{:?} does not modify memory", operator),
+        }
     }
-    
-    /**
-     * Transfer Index X to Stack Pointer
-     */
-    fn txs (&mut self, operand: Operand, _bus: &mut Bus) {
-        let value = match operand {
-            Operand::None => self.x,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.sp = value;
+
+    fn write_operation (&mut self) -> u8 {
+        match self.instruction.operator {
+            Operator::SAX => self.sax(),
+            Operator::SHA => self.sha(),
+            Operator::SHX => self.shx(),
+            Operator::SHY => self.shy(),
+            Operator::STA => self.sta(),
+            Operator::STX => self.stx(),
+            Operator::STY => self.sty(),
+            Operator::TAS => self.tas(),
+            operator => unreachable!("{:?} does not write memory", operator),
+        }
     }
-    
-    /**
-     * Transfer Index Y to Accumulator
-     */
-    fn tya (&mut self, operand: Operand, _bus: &mut Bus) {
-        let value = match operand {
-            Operand::None => self.y,
-            _ => panic!("Invalid addressing mode"),
-        };
-    
-        self.a = value;
-    
-        self.set_flag(StatusFlag::Zero, self.a == 0);
-        self.set_flag(StatusFlag::Negative, (self.a as i8) < 0);
+
+    fn implied_operation (&mut self) {
+        match self.instruction.operator {
+            Operator::ASL => self.a = self.asl(self.a),
+            Operator::LSR => self.a = self.lsr(self.a),
+            Operator::ROL => self.a = self.rol(self.a),
+            Operator::ROR => self.a = self.ror(self.a),
+            Operator::CLC => self.clc(),
+            Operator::CLD => self.cld(),
+            Operator::CLI => self.cli(),
+            Operator::CLV => self.clv(),
+            Operator::DEX => self.dex(),
+            Operator::DEY => self.dey(),
+            Operator::INX => self.inx(),
+            Operator::INY => self.iny(),
+            Operator::KIL => self.kil(),
+            Operator::NOP => {},
+            Operator::SEC => self.sec(),
+            Operator::SED => self.sed(),
+            Operator::SEI => self.sei(),
+            Operator::TAX => self.tax(),
+            Operator::TAY => self.tay(),
+            Operator::TSX => self.tsx(),
+            Operator::TXA => self.txa(),
+            Operator::TXS => self.txs(),
+            Operator::TYA => self.tya(),
+            operator => unreachable!("{:?} is not an implied operation", operator),
+        }
     }
 }
@@ -2,9 +2,13 @@
  * https://wiki.nesdev.com/w/index.php/CPU_interrupts
  */
 
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
 pub const INTERRUPT_LATENCY: usize = 7;
 
-#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize)]
+#[cfg_attr(feature = "wasm", wasm_bindgen(js_name = CpuInterrupt))]
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
 pub enum Interrupt {
     NMI     = 0xFFFA, // Non-maskable interrupt
     IRQ     = 0xFFFE, // Maskable interrupt
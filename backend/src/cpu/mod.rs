@@ -1,6 +1,7 @@
 mod cpu;
 mod instruction;
 mod interrupt;
+mod operation;
 
 pub use cpu::*;
 pub use instruction::*;
@@ -0,0 +1,612 @@
+use crate::cpu::{Cpu, StatusFlag};
+
+/**
+ * Instruction operations, applied to values the instruction sequencer moved on and off the bus
+ * http://www.obelisk.me.uk/6502/reference.html
+ * https://wiki.nesdev.org/w/index.php/CPU_unofficial_opcodes
+ */
+impl Cpu {
+    fn set_zero_negative (&mut self, value: u8) {
+        self.set_flag(StatusFlag::Zero, value == 0);
+        self.set_flag(StatusFlag::Negative, (value as i8) < 0);
+    }
+
+    fn compare (&mut self, register: u8, value: u8) {
+        self.set_flag(StatusFlag::Carry, register >= value);
+        self.set_zero_negative(register.wrapping_sub(value));
+    }
+
+    /**
+     * Value stored by SHA, SHX, SHY and TAS: the register ANDed with the high byte of the base address plus one
+     */
+    fn and_high (&self, value: u8) -> u8 {
+        let high = (self.address >> 8) as u8;
+
+        // On page cross, the high byte was already incremented by the index
+        value & if self.crossed { high } else { high.wrapping_add(1) }
+    }
+
+    /* Read operations */
+
+    /**
+     * Add Memory to Accumulator with Carry
+     */
+    pub (super) fn adc (&mut self, value: u8) {
+        let res = self.a as u16 + value as u16 + self.get_flag(StatusFlag::Carry) as u16;
+        let overflow = (!(self.a ^ value) & (self.a ^ res as u8) & StatusFlag::Negative as u8) != 0;
+
+        self.a = res as u8;
+
+        self.set_flag(StatusFlag::Carry, res >> 8 != 0);
+        self.set_flag(StatusFlag::Overflow, overflow);
+        self.set_zero_negative(self.a);
+    }
+
+    /**
+     * AND Memory with Accumulator then Shift Right (unofficial)
+     */
+    pub (super) fn alr (&mut self, value: u8) {
+        self.and(value);
+        self.a = self.lsr(self.a);
+    }
+
+    /**
+     * AND Memory with Accumulator then Move Negative into Carry (unofficial)
+     */
+    pub (super) fn anc (&mut self, value: u8) {
+        self.and(value);
+
+        let negative = self.get_flag(StatusFlag::Negative);
+        self.set_flag(StatusFlag::Carry, negative);
+    }
+
+    /**
+     * AND Memory with Accumulator
+     */
+    pub (super) fn and (&mut self, value: u8) {
+        self.a &= value;
+        self.set_zero_negative(self.a);
+    }
+
+    /**
+     * AND Memory with Accumulator then Rotate Right (unofficial)
+     */
+    pub (super) fn arr (&mut self, value: u8) {
+        self.a = (self.a & value) >> 1 | (self.status & StatusFlag::Carry as u8) << 7;
+
+        // Carry and overflow come from bits 6 and 5 of the result, as if added
+        let (bit6, bit5) = ((self.a >> 6) & 1, (self.a >> 5) & 1);
+        self.set_flag(StatusFlag::Carry, bit6 != 0);
+        self.set_flag(StatusFlag::Overflow, (bit6 ^ bit5) != 0);
+        self.set_zero_negative(self.a);
+    }
+
+    /**
+     * AND Accumulator with Index X then Subtract Memory into Index X (unofficial)
+     */
+    pub (super) fn axs (&mut self, value: u8) {
+        let and = self.a & self.x;
+        self.x = and.wrapping_sub(value);
+
+        self.set_flag(StatusFlag::Carry, and >= value);
+        self.set_zero_negative(self.x);
+    }
+
+    /**
+     * Test Bits in Memory with Accumulator
+     */
+    pub (super) fn bit (&mut self, value: u8) {
+        self.set_flag(StatusFlag::Zero, (self.a & value) == 0);
+        self.set_flag(StatusFlag::Overflow, (value & StatusFlag::Overflow as u8) != 0);
+        self.set_flag(StatusFlag::Negative, (value & StatusFlag::Negative as u8) != 0);
+    }
+
+    /**
+     * Compare Memory with Accumulator
+     */
+    pub (super) fn cmp (&mut self, value: u8) {
+        self.compare(self.a, value);
+    }
+
+    /**
+     * Compare Memory and Index X
+     */
+    pub (super) fn cpx (&mut self, value: u8) {
+        self.compare(self.x, value);
+    }
+
+    /**
+     * Compare Memory and Index Y
+     */
+    pub (super) fn cpy (&mut self, value: u8) {
+        self.compare(self.y, value);
+    }
+
+    /**
+     * Exclusive-OR Memory with Accumulator
+     */
+    pub (super) fn eor (&mut self, value: u8) {
+        self.a ^= value;
+        self.set_zero_negative(self.a);
+    }
+
+    /**
+     * AND Memory with Stack Pointer into Accumulator, Index X and Stack Pointer (unofficial)
+     */
+    pub (super) fn las (&mut self, value: u8) {
+        let value = value & self.sp;
+        self.a = value;
+        self.x = value;
+        self.sp = value;
+
+        self.set_zero_negative(value);
+    }
+
+    /**
+     * Load Accumulator and Index X with Memory (unofficial)
+     */
+    pub (super) fn lax (&mut self, value: u8) {
+        self.a = value;
+        self.x = value;
+
+        self.set_zero_negative(value);
+    }
+
+    /**
+     * Load Accumulator with Memory
+     */
+    pub (super) fn lda (&mut self, value: u8) {
+        self.a = value;
+        self.set_zero_negative(self.a);
+    }
+
+    /**
+     * Load Index X with Memory
+     */
+    pub (super) fn ldx (&mut self, value: u8) {
+        self.x = value;
+        self.set_zero_negative(self.x);
+    }
+
+    /**
+     * Load Index Y with Memory
+     */
+    pub (super) fn ldy (&mut self, value: u8) {
+        self.y = value;
+        self.set_zero_negative(self.y);
+    }
+
+    /**
+     * OR Memory with Accumulator
+     */
+    pub (super) fn ora (&mut self, value: u8) {
+        self.a |= value;
+        self.set_zero_negative(self.a);
+    }
+
+    /**
+     * Subtract Memory from Accumulator with Borrow
+     */
+    pub (super) fn sbc (&mut self, value: u8) {
+        self.adc(!value);
+    }
+
+    /**
+     * Transfer Index X to Accumulator then AND with Memory (unofficial, unstable)
+     */
+    pub (super) fn xaa (&mut self, value: u8) {
+        // The "magic" constant varies between chips, $EE is the most common
+        self.a = (self.a | 0xEE) & self.x & value;
+        self.set_zero_negative(self.a);
+    }
+
+    /* Read-modify-write operations */
+
+    /**
+     * Shift Left One Bit
+     */
+    pub (super) fn asl (&mut self, value: u8) -> u8 {
+        let new_value = value << 1;
+
+        self.set_flag(StatusFlag::Carry, (value & StatusFlag::Negative as u8) != 0);
+        self.set_zero_negative(new_value);
+
+        new_value
+    }
+
+    /**
+     * Decrement Memory by One then Compare with Accumulator (unofficial)
+     */
+    pub (super) fn dcp (&mut self, value: u8) -> u8 {
+        let new_value = value.wrapping_sub(1);
+        self.cmp(new_value);
+
+        new_value
+    }
+
+    /**
+     * Decrement Memory by One
+     */
+    pub (super) fn dec (&mut self, value: u8) -> u8 {
+        let new_value = value.wrapping_sub(1);
+        self.set_zero_negative(new_value);
+
+        new_value
+    }
+
+    /**
+     * Increment Memory by One
+     */
+    pub (super) fn inc (&mut self, value: u8) -> u8 {
+        let new_value = value.wrapping_add(1);
+        self.set_zero_negative(new_value);
+
+        new_value
+    }
+
+    /**
+     * Increment Memory by One then Subtract from Accumulator with Borrow (unofficial)
+     */
+    pub (super) fn isc (&mut self, value: u8) -> u8 {
+        let new_value = value.wrapping_add(1);
+        self.sbc(new_value);
+
+        new_value
+    }
+
+    /**
+     * Shift One Bit Right
+     */
+    pub (super) fn lsr (&mut self, value: u8) -> u8 {
+        let new_value = value >> 1;
+
+        self.set_flag(StatusFlag::Carry, (value & 1) != 0);
+        self.set_zero_negative(new_value);
+
+        new_value
+    }
+
+    /**
+     * Rotate One Bit Left then AND with Accumulator (unofficial)
+     */
+    pub (super) fn rla (&mut self, value: u8) -> u8 {
+        let new_value = self.rol(value);
+        self.and(new_value);
+
+        new_value
+    }
+
+    /**
+     * Rotate One Bit Left
+     */
+    pub (super) fn rol (&mut self, value: u8) -> u8 {
+        let new_value = value << 1 | self.status & StatusFlag::Carry as u8;
+
+        self.set_flag(StatusFlag::Carry, (value & StatusFlag::Negative as u8) != 0);
+        self.set_zero_negative(new_value);
+
+        new_value
+    }
+
+    /**
+     * Rotate One Bit Right
+     */
+    pub (super) fn ror (&mut self, value: u8) -> u8 {
+        let new_value = value >> 1 | (self.status & StatusFlag::Carry as u8) << 7;
+
+        self.set_flag(StatusFlag::Carry, (value & 1) != 0);
+        self.set_zero_negative(new_value);
+
+        new_value
+    }
+
+    /**
+     * Rotate One Bit Right then Add to Accumulator with Carry (unofficial)
+     */
+    pub (super) fn rra (&mut self, value: u8) -> u8 {
+        let new_value = self.ror(value);
+        self.adc(new_value);
+
+        new_value
+    }
+
+    /**
+     * Shift Left One Bit then OR with Accumulator (unofficial)
+     */
+    pub (super) fn slo (&mut self, value: u8) -> u8 {
+        let new_value = self.asl(value);
+        self.ora(new_value);
+
+        new_value
+    }
+
+    /**
+     * Shift One Bit Right then EOR with Accumulator (unofficial)
+     */
+    pub (super) fn sre (&mut self, value: u8) -> u8 {
+        let new_value = self.lsr(value);
+        self.eor(new_value);
+
+        new_value
+    }
+
+    /* Write operations */
+
+    /**
+     * Store Accumulator AND Index X in Memory (unofficial)
+     */
+    pub (super) fn sax (&mut self) -> u8 {
+        self.a & self.x
+    }
+
+    /**
+     * Store Accumulator AND Index X AND High Byte of Address in Memory (unofficial)
+     */
+    pub (super) fn sha (&mut self) -> u8 {
+        self.and_high(self.a & self.x)
+    }
+
+    /**
+     * Store Index X AND High Byte of Address in Memory (unofficial)
+     */
+    pub (super) fn shx (&mut self) -> u8 {
+        self.and_high(self.x)
+    }
+
+    /**
+     * Store Index Y AND High Byte of Address in Memory (unofficial)
+     */
+    pub (super) fn shy (&mut self) -> u8 {
+        self.and_high(self.y)
+    }
+
+    /**
+     * Store Accumulator in Memory
+     */
+    pub (super) fn sta (&mut self) -> u8 {
+        self.a
+    }
+
+    /**
+     * Store Index X in Memory
+     */
+    pub (super) fn stx (&mut self) -> u8 {
+        self.x
+    }
+
+    /**
+     * Store Index Y in Memory
+     */
+    pub (super) fn sty (&mut self) -> u8 {
+        self.y
+    }
+
+    /**
+     * Transfer Accumulator AND Index X to Stack Pointer, then Store it AND High Byte of Address in Memory (unofficial)
+     */
+    pub (super) fn tas (&mut self) -> u8 {
+        self.sp = self.a & self.x;
+        self.and_high(self.sp)
+    }
+
+    /* Implied operations */
+
+    /**
+     * Clear Carry Flag
+     */
+    pub (super) fn clc (&mut self) {
+        self.set_flag(StatusFlag::Carry, false);
+    }
+
+    /**
+     * Clear Decimal Mode
+     */
+    pub (super) fn cld (&mut self) {
+        self.set_flag(StatusFlag::Decimal, false);
+    }
+
+    /**
+     * Clear Interrupt Disable Bit
+     */
+    pub (super) fn cli (&mut self) {
+        self.set_flag(StatusFlag::DisableInterrupt, false);
+    }
+
+    /**
+     * Clear Overflow Flag
+     */
+    pub (super) fn clv (&mut self) {
+        self.set_flag(StatusFlag::Overflow, false);
+    }
+
+    /**
+     * Decrement Index X by One
+     */
+    pub (super) fn dex (&mut self) {
+        self.x = self.x.wrapping_sub(1);
+        self.set_zero_negative(self.x);
+    }
+
+    /**
+     * Decrement Index Y by One
+     */
+    pub (super) fn dey (&mut self) {
+        self.y = self.y.wrapping_sub(1);
+        self.set_zero_negative(self.y);
+    }
+
+    /**
+     * Increment Index X by One
+     */
+    pub (super) fn inx (&mut self) {
+        self.x = self.x.wrapping_add(1);
+        self.set_zero_negative(self.x);
+    }
+
+    /**
+     * Increment Index Y by One
+     */
+    pub (super) fn iny (&mut self) {
+        self.y = self.y.wrapping_add(1);
+        self.set_zero_negative(self.y);
+    }
+
+    /**
+     * Halt the Processor until Reset (unofficial)
+     */
+    pub (super) fn kil (&mut self) {
+        self.halted = true;
+    }
+
+    /**
+     * Set Carry Flag
+     */
+    pub (super) fn sec (&mut self) {
+        self.set_flag(StatusFlag::Carry, true);
+    }
+
+    /**
+     * Set Decimal Flag
+     */
+    pub (super) fn sed (&mut self) {
+        self.set_flag(StatusFlag::Decimal, true);
+    }
+
+    /**
+     * Set Interrupt Disable Status
+     */
+    pub (super) fn sei (&mut self) {
+        self.set_flag(StatusFlag::DisableInterrupt, true);
+    }
+
+    /**
+     * Transfer Accumulator to Index X
+     */
+    pub (super) fn tax (&mut self) {
+        self.x = self.a;
+        self.set_zero_negative(self.x);
+    }
+
+    /**
+     * Transfer Accumulator to Index Y
+     */
+    pub (super) fn tay (&mut self) {
+        self.y = self.a;
+        self.set_zero_negative(self.y);
+    }
+
+    /**
+     * Transfer Stack Pointer to Index X
+     */
+    pub (super) fn tsx (&mut self) {
+        self.x = self.sp;
+        self.set_zero_negative(self.x);
+    }
+
+    /**
+     * Transfer Index X to Accumulator
+     */
+    pub (super) fn txa (&mut self) {
+        self.a = self.x;
+        self.set_zero_negative(self.a);
+    }
+
+    /**
+     * Transfer Index X to Stack Pointer
+     */
+    pub (super) fn txs (&mut self) {
+        self.sp = self.x;
+    }
+
+    /**
+     * Transfer Index Y to Accumulator
+     */
+    pub (super) fn tya (&mut self) {
+        self.a = self.y;
+        self.set_zero_negative(self.a);
+    }
+
+    /* Stack operations */
+
+    /**
+     * Push Processor Status on Stack, with the break flag set
+     */
+    pub (super) fn php (&mut self) -> u8 {
+        self.status | (StatusFlag::Break as u8) | (StatusFlag::Unused as u8)
+    }
+
+    /**
+     * Pull Accumulator from Stack
+     */
+    pub (super) fn pla (&mut self, value: u8) {
+        self.a = value;
+        self.set_zero_negative(self.a);
+    }
+
+    /**
+     * Pull Processor Status from Stack
+     */
+    pub (super) fn plp (&mut self, value: u8) {
+        // Ignored flags, stay as-is
+        let mask = (StatusFlag::Break as u8) | (StatusFlag::Unused as u8);
+        self.status = (value & !mask) | (self.status & mask);
+    }
+
+    /* Branch conditions */
+
+    /**
+     * Branch on Carry Clear
+     */
+    pub (super) fn bcc (&mut self) -> bool {
+        !self.get_flag(StatusFlag::Carry)
+    }
+
+    /**
+     * Branch on Carry Set
+     */
+    pub (super) fn bcs (&mut self) -> bool {
+        self.get_flag(StatusFlag::Carry)
+    }
+
+    /**
+     * Branch on Result Zero
+     */
+    pub (super) fn beq (&mut self) -> bool {
+        self.get_flag(StatusFlag::Zero)
+    }
+
+    /**
+     * Branch on Result Minus
+     */
+    pub (super) fn bmi (&mut self) -> bool {
+        self.get_flag(StatusFlag::Negative)
+    }
+
+    /**
+     * Branch on Result not Zero
+     */
+    pub (super) fn bne (&mut self) -> bool {
+        !self.get_flag(StatusFlag::Zero)
+    }
+
+    /**
+     * Branch on Result Plus
+     */
+    pub (super) fn bpl (&mut self) -> bool {
+        !self.get_flag(StatusFlag::Negative)
+    }
+
+    /**
+     * Branch on Overflow Clear
+     */
+    pub (super) fn bvc (&mut self) -> bool {
+        !self.get_flag(StatusFlag::Overflow)
+    }
+
+    /**
+     * Branch on Overflow Set
+     */
+    pub (super) fn bvs (&mut self) -> bool {
+        self.get_flag(StatusFlag::Overflow)
+    }
+}
@@ -1,8 +1,8 @@
 use wasm_bindgen::prelude::*;
-use crate::{cpu, Emulator};
+use crate::{cpu, WasmEmulator};
 
-#[wasm_bindgen]
-impl Emulator {
+#[wasm_bindgen(js_class = Emulator)]
+impl WasmEmulator {
     pub fn debug_bus_ram (&mut self) -> JsValue {
         JsValue::from_serde(&self.bus.wram).unwrap()
     }
@@ -1,8 +1,8 @@
 use wasm_bindgen::prelude::*;
-use crate::Emulator;
+use crate::WasmEmulator;
 
-#[wasm_bindgen]
-impl Emulator {
+#[wasm_bindgen(js_class = Emulator)]
+impl WasmEmulator {
     pub fn debug_clock (&mut self) -> JsValue {
         JsValue::from_serde(&self.clock).unwrap()
     }
@@ -0,0 +1,110 @@
+use wasm_bindgen::prelude::*;
+use crate::{cpu, BreakpointKind, Expression, ExpressionError, SymbolError, TraceFormat, WasmEmulator};
+
+#[wasm_bindgen(js_class = Emulator)]
+impl WasmEmulator {
+    /**
+     * Breakpoints return an id to remove them. The bank is only checked at $8000-$FFFF
+     */
+    pub fn add_breakpoint (&mut self, address: u16, bank: Option<u8>) -> u32 {
+        self.emulator.add_breakpoint(BreakpointKind::Execute { address, bank })
+    }
+
+    pub fn add_watchpoint (&mut self, start: u16, end: u16, read: bool, write: bool) -> u32 {
+        self.emulator.add_breakpoint(BreakpointKind::Access { start, end, read, write })
+    }
+
+    /**
+     * Throw a SymbolError when there is no such symbol
+     */
+    pub fn add_breakpoint_at_symbol (&mut self, name: &str) -> Result<u32, SymbolError> {
+        self.emulator.add_breakpoint_at(name)
+    }
+
+    pub fn add_watchpoint_at_symbol (&mut self, name: &str, read: bool, write: bool) -> Result<u32, SymbolError> {
+        self.emulator.add_watchpoint_at(name, read, write)
+    }
+
+    pub fn add_ppu_register_breakpoint (&mut self, register: u8, read: bool, write: bool) -> u32 {
+        self.emulator.add_breakpoint(BreakpointKind::PpuRegister { register, read, write })
+    }
+
+    pub fn add_interrupt_breakpoint (&mut self, interrupt: cpu::Interrupt) -> u32 {
+        self.emulator.add_breakpoint(BreakpointKind::Interrupt(interrupt))
+    }
+
+    pub fn remove_breakpoint (&mut self, id: u32) -> bool {
+        self.emulator.remove_breakpoint(id)
+    }
+
+    /**
+     * Throws an ExpressionError if the condition can't be parsed. Undefined removes it
+     */
+    pub fn set_breakpoint_condition (&mut self, id: u32, condition: Option<String>) -> Result<bool, ExpressionError> {
+        let condition = condition.map(|condition| Expression::parse(&condition)).transpose()?;
+
+        Ok(self.emulator.set_breakpoint_condition(id, condition))
+    }
+
+    pub fn clear_breakpoints (&mut self) {
+        self.emulator.clear_breakpoints();
+    }
+
+    pub fn breakpoints (&self) -> JsValue {
+        JsValue::from_serde(self.emulator.breakpoints()).unwrap()
+    }
+
+    /**
+     * Debugger commands return why they stopped, e.g. { Breakpoint: { id, address } } or "Frame"
+     */
+    pub fn step_instruction (&mut self) -> JsValue {
+        JsValue::from_serde(&self.emulator.step_instruction()).unwrap()
+    }
+
+    pub fn step_over (&mut self) -> JsValue {
+        JsValue::from_serde(&self.emulator.step_over()).unwrap()
+    }
+
+    pub fn step_out (&mut self) -> JsValue {
+        JsValue::from_serde(&self.emulator.step_out()).unwrap()
+    }
+
+    pub fn run_until_break (&mut self) -> JsValue {
+        JsValue::from_serde(&self.emulator.run_until_break()).unwrap()
+    }
+
+    pub fn trace_to_buffer (&mut self, format: TraceFormat, capacity: usize) {
+        self.emulator.trace_to_buffer(format, capacity);
+    }
+
+    /**
+     * Calls back with each line
+     */
+    pub fn trace_to_callback (&mut self, format: TraceFormat, callback: js_sys::Function) {
+        self.emulator.trace_to_callback(format, move |line| {
+            callback.call1(&JsValue::NULL, &JsValue::from_str(line)).ok();
+        });
+    }
+
+    pub fn stop_trace (&mut self) {
+        self.emulator.stop_trace();
+    }
+
+    /**
+     * Buffered lines, each ending with a new line
+     */
+    pub fn take_trace (&mut self) -> String {
+        self.emulator.take_trace().iter().map(|line| format!("{}\n", line)).collect()
+    }
+
+    /**
+     * ca65 .dbg, FCEUX .nl or Mesen .mlb, told apart by the file name. Throws a SymbolError if it can't be parsed
+     */
+    pub fn load_symbols (&mut self, file_name: &str, contents: &str) -> Result<usize, SymbolError> {
+        self.emulator.load_symbols(file_name, contents)
+    }
+
+    pub fn clear_symbols (&mut self) {
+        self.emulator.clear_symbols();
+    }
+}
@@ -3,6 +3,7 @@ mod bus;
 mod cartridge;
 mod clock;
 mod cpu;
+mod debugger;
 mod disassembly;
 mod input;
 mod ppu;
@@ -12,6 +13,7 @@ pub use bus::*;
 pub use cartridge::*;
 pub use clock::*;
 pub use cpu::*;
+pub use debugger::*;
 pub use disassembly::*;
 pub use input::*;
 pub use ppu::*;
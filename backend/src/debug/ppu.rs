@@ -1,5 +1,5 @@
 use wasm_bindgen::prelude::*;
-use crate::{ppu, Emulator};
+use crate::{ppu, WasmEmulator};
 
 #[derive(Clone, serde::Serialize)]
 struct Oam {
@@ -10,8 +10,8 @@ struct Oam {
     tile: Vec<u8>,
 }
 
-#[wasm_bindgen]
-impl Emulator {
+#[wasm_bindgen(js_class = Emulator)]
+impl WasmEmulator {
     pub fn debug_ppu_ctrl (&mut self) -> u8 {
         self.bus.ppu.ctrl
     }
@@ -0,0 +1,321 @@
+//! Debugger: execution, bus access and interrupt breakpoints, checked on instruction boundaries by the stepping commands.
+//! Bus accesses are matched by the bus itself as they happen, and reported once the instruction making them completes.
+//! Mirrored addresses are matched through their canonical address: RAM at $0000-$07FF, PPU registers at $2000-$2007.
+//! A breakpoint with a condition only stops when it holds, but counts every hit.
+
+use std::collections::HashMap;
+use crate::{cpu::Interrupt, Emulator, Expression, SymbolTable, Window};
+
+const JSR: u8 = 0x20;
+const RTS: u8 = 0x60;
+const RTI: u8 = 0x40;
+const STEP_FRAMES: u32 = 60; // Frames a step over or out runs at most, in case it never returns
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub enum BreakpointKind {
+    Execute { address: u16, bank: Option<u8> }, // The PRG bank mapped at the address, only checked at $8000-$FFFF
+    Access { start: u16, end: u16, read: bool, write: bool }, // Inclusive range
+    PpuRegister { register: u8, read: bool, write: bool }, // 0-7, i.e. $2000-$2007
+    Interrupt (Interrupt), // Stops at the first instruction of the handler
+}
+
+impl BreakpointKind {
+    fn matches_access (&self, access: &BusAccess) -> bool {
+        let address = match access.address {
+            0x0000 ..= 0x1FFF => access.address % 0x800,
+            0x2000 ..= 0x3FFF => 0x2000 | access.address & 0b111,
+            _ => access.address,
+        };
+
+        match *self {
+            BreakpointKind::Access { start, end, read, write } => (start ..= end).contains(&address) && if access.write { write } else { read },
+            BreakpointKind::PpuRegister { register, read, write } => address == 0x2000 | register as u16 && if access.write { write } else { read },
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Breakpoint {
+    pub id: u32,
+    pub kind: BreakpointKind,
+    pub hits: u32,
+    pub condition: Option<Expression>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct BusAccess {
+    pub address: u16,
+    pub data: u8,
+    pub write: bool,
+}
+
+/**
+ * Why a debugger command returned
+ */
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub enum StopReason {
+    Frame, // Reached the end of the frame without breaking
+    Step, // The step completed
+    Breakpoint { id: u32, address: u16 },
+    Watchpoint { id: u32, pc: u16, access: BusAccess }, // PC of the instruction that made the access
+    Interrupt { id: u32, interrupt: Interrupt },
+    Halted, // The CPU is jammed by KIL until reset
+}
+
+/**
+ * Bus access breakpoints, kept on the bus
+ */
+#[derive(Default)]
+pub struct Watch {
+    kinds: Vec<BreakpointKind>,
+    pub hit: Option<BusAccess>, // First matching access since the last instruction boundary
+}
+
+impl Watch {
+    pub fn is_empty (&self) -> bool {
+        self.kinds.is_empty()
+    }
+
+    pub fn check (&mut self, address: u16, data: u8, write: bool) {
+        let access = BusAccess { address, data, write };
+
+        if self.hit.is_none() && self.kinds.iter().any(|kind| kind.matches_access(&access)) {
+            self.hit = Some(access);
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct Debugger {
+    breakpoints: Vec<Breakpoint>,
+    next_id: u32,
+    pub (crate) boundaries: HashMap<Window, Vec<u16>>, // Instruction addresses in each disassembled bank
+    pub (crate) symbols: SymbolTable,
+}
+
+/**
+ * Where a step ends, besides breakpoints
+ */
+#[derive(Clone, Copy, PartialEq)]
+enum Target {
+    Frame,
+    Instruction,
+    Return { pc: u16, sp: u8 }, // Back from a subroutine, at the instruction following JSR
+    Out { sp: u8 }, // After RTS or RTI popped the stack above where it was
+}
+
+/**
+ * What runs after an instruction boundary
+ */
+#[derive(Clone, Copy)]
+struct Upcoming {
+    pc: u16,
+    opcode: Option<u8>, // None if an interrupt sequence runs instead
+    interrupt: Option<Interrupt>,
+}
+
+impl Emulator {
+    pub fn add_breakpoint (&mut self, kind: BreakpointKind) -> u32 {
+        let id = self.debugger.next_id;
+
+        self.debugger.next_id += 1;
+        self.debugger.breakpoints.push(Breakpoint { id, kind, hits: 0, condition: None });
+        self.update_watch();
+
+        id
+    }
+
+    /**
+     * Returns false if there is no such breakpoint
+     */
+    pub fn remove_breakpoint (&mut self, id: u32) -> bool {
+        let count = self.debugger.breakpoints.len();
+
+        self.debugger.breakpoints.retain(|breakpoint| breakpoint.id != id);
+        self.update_watch();
+
+        self.debugger.breakpoints.len() < count
+    }
+
+    /**
+     * Only stop when the condition holds, or always when None. Returns false if there is no such breakpoint
+     */
+    pub fn set_breakpoint_condition (&mut self, id: u32, condition: Option<Expression>) -> bool {
+        match self.debugger.breakpoints.iter_mut().find(|breakpoint| breakpoint.id == id) {
+            Some (breakpoint) => {
+                breakpoint.condition = condition;
+                true
+            },
+            None => false,
+        }
+    }
+
+    pub fn clear_breakpoints (&mut self) {
+        self.debugger.breakpoints.clear();
+        self.update_watch();
+    }
+
+    pub fn breakpoints (&self) -> &[Breakpoint] {
+        &self.debugger.breakpoints
+    }
+
+    fn update_watch (&mut self) {
+        self.bus.watch.kinds = self.debugger.breakpoints.iter()
+            .map(|breakpoint| breakpoint.kind)
+            .filter(|kind| matches!(kind, BreakpointKind::Access { .. } | BreakpointKind::PpuRegister { .. }))
+            .collect();
+    }
+
+    /**
+     * Run until the current instruction completes, or the interrupt sequence about to start
+     */
+    pub fn step_instruction (&mut self) -> StopReason {
+        self.debug_run(Target::Instruction, STEP_FRAMES)
+    }
+
+    /**
+     * Step, running subroutines called by JSR until they return
+     */
+    pub fn step_over (&mut self) -> StopReason {
+        let upcoming = self.upcoming();
+
+        match upcoming.opcode {
+            Some (JSR) if self.cpu.cycles == 0 => self.debug_run(Target::Return { pc: upcoming.pc.wrapping_add(3), sp: self.cpu.sp }, STEP_FRAMES),
+            _ => self.step_instruction(),
+        }
+    }
+
+    /**
+     * Run until the current subroutine or interrupt handler returns
+     */
+    pub fn step_out (&mut self) -> StopReason {
+        self.debug_run(Target::Out { sp: self.cpu.sp }, STEP_FRAMES)
+    }
+
+    /**
+     * Same as cycle_until_frame, unless a breakpoint stops execution before the end of the frame.
+     * Resuming from a breakpoint runs the instruction it stopped at.
+     */
+    pub fn run_until_break (&mut self) -> StopReason {
+        self.debug_run(Target::Frame, 1)
+    }
+
+    fn upcoming (&self) -> Upcoming {
+        let interrupt = self.cpu.pending_interrupt();
+
+        Upcoming {
+            pc: self.cpu.pc,
+            opcode: if interrupt.is_some() { None } else { self.bus.peek(self.cpu.pc) },
+            interrupt,
+        }
+    }
+
+    /**
+     * Run up to the end of the given number of frames, unless the target or a breakpoint is reached first
+     */
+    fn debug_run (&mut self, target: Target, frames: u32) -> StopReason {
+        if self.cpu.halted && target != Target::Frame {
+            return StopReason::Halted;
+        }
+
+        // Reads made by the host since (e.g. disassembling) don't count
+        self.bus.watch.hit = None;
+        let mut upcoming = self.upcoming();
+
+        for _ in 0..frames {
+            self.bus.catch_up();
+            self.play_movie_input();
+
+            let dots = self.bus.ppu.dots_until_frame();
+            let end = self.clock.cycles + self.bus.ppu.clock.master_cycles_until(dots);
+
+            while self.clock.cycles < end {
+                if !self.tick() {
+                    continue;
+                }
+
+                let previous = std::mem::replace(&mut upcoming, self.upcoming());
+                if let Some(reason) = self.check_break(target, previous, upcoming) {
+                    self.bus.catch_up();
+                    return reason;
+                }
+            }
+
+            self.end_frame();
+        }
+
+        StopReason::Frame
+    }
+
+    /**
+     * On an instruction boundary, between what just ran and what comes next
+     */
+    fn check_break (&mut self, target: Target, previous: Upcoming, upcoming: Upcoming) -> Option<StopReason> {
+        if let Some(access) = self.bus.watch.hit.take() {
+            if let Some(id) = self.trigger(|kind| kind.matches_access(&access)) {
+                return Some(StopReason::Watchpoint { id, pc: previous.pc, access });
+            }
+        }
+
+        if let Some(interrupt) = previous.interrupt {
+            if let Some(id) = self.trigger(|kind| *kind == BreakpointKind::Interrupt(interrupt)) {
+                return Some(StopReason::Interrupt { id, interrupt });
+            }
+        }
+
+        let address = upcoming.pc;
+        let breaks_here = |kind: &BreakpointKind| matches!(*kind, BreakpointKind::Execute { address: at, .. } if at == address);
+
+        if upcoming.opcode.is_some() && self.debugger.breakpoints.iter().any(|breakpoint| breaks_here(&breakpoint.kind)) {
+            let bank = self.prg_bank_at(address);
+            let id = self.trigger(|kind| breaks_here(kind) && match (*kind, bank) {
+                (BreakpointKind::Execute { bank: Some (expected), .. }, Some (bank)) => expected == bank,
+                _ => true,
+            });
+
+            if let Some(id) = id {
+                return Some(StopReason::Breakpoint { id, address });
+            }
+        }
+
+        if self.cpu.halted {
+            return Some(StopReason::Halted);
+        }
+
+        let reached = match target {
+            Target::Frame => false,
+            Target::Instruction => true,
+            Target::Return { pc, sp } => upcoming.pc == pc && self.cpu.sp >= sp,
+            Target::Out { sp } => matches!(previous.opcode, Some (RTS) | Some (RTI)) && self.cpu.sp > sp,
+        };
+
+        if reached { Some(StopReason::Step) } else { None }
+    }
+
+    /**
+     * Count a hit on every matching breakpoint, returning the first one whose condition holds
+     */
+    fn trigger (&mut self, matches: impl Fn(&BreakpointKind) -> bool) -> Option<u32> {
+        let mut stop = None;
+
+        for breakpoint in self.debugger.breakpoints.iter_mut().filter(|breakpoint| matches(&breakpoint.kind)) {
+            breakpoint.hits += 1;
+
+            let holds = match &breakpoint.condition {
+                Some (condition) => {
+                    // Conditions can look at the PPU position
+                    self.bus.catch_up();
+                    condition.evaluate(&self.cpu, &self.bus, breakpoint.hits) != 0
+                },
+                None => true,
+            };
+
+            if holds && stop.is_none() {
+                stop = Some(breakpoint.id);
+            }
+        }
+
+        stop
+    }
+}
@@ -0,0 +1,289 @@
+//! Disassembler reading memory without side effects, from the CPU bus as currently mapped or from any PRG-ROM bank.
+//! Instruction boundaries come from a linear sweep of each bank, cached since PRG-ROM never changes.
+//! Branch targets are resolved to absolute addresses, and known addresses are named: loaded symbols, registers, vectors,
+//! and the handlers the vectors point to.
+
+use std::fmt;
+use crate::{
+    bus::Bus,
+    cpu::{AddressingMode, Instruction, Operator},
+    Emulator,
+};
+
+const VECTORS: [(u16, &str, &str); 3] = [
+    (0xFFFA, "NMI_VECTOR", "NMI"),
+    (0xFFFC, "RESET_VECTOR", "RESET"),
+    (0xFFFE, "IRQ_VECTOR", "IRQ"),
+];
+
+/**
+ * https://wiki.nesdev.org/w/index.php/PPU_registers
+ * https://wiki.nesdev.org/w/index.php/APU_registers
+ */
+const REGISTERS: [(u16, &str); 30] = [
+    (0x2000, "PPUCTRL"),    (0x2001, "PPUMASK"),    (0x2002, "PPUSTATUS"),  (0x2003, "OAMADDR"),
+    (0x2004, "OAMDATA"),    (0x2005, "PPUSCROLL"),  (0x2006, "PPUADDR"),    (0x2007, "PPUDATA"),
+    (0x4000, "SQ1_VOL"),    (0x4001, "SQ1_SWEEP"),  (0x4002, "SQ1_LO"),     (0x4003, "SQ1_HI"),
+    (0x4004, "SQ2_VOL"),    (0x4005, "SQ2_SWEEP"),  (0x4006, "SQ2_LO"),     (0x4007, "SQ2_HI"),
+    (0x4008, "TRI_LINEAR"), (0x400A, "TRI_LO"),     (0x400B, "TRI_HI"),
+    (0x400C, "NOISE_VOL"),  (0x400E, "NOISE_LO"),   (0x400F, "NOISE_HI"),
+    (0x4010, "DMC_FREQ"),   (0x4011, "DMC_RAW"),    (0x4012, "DMC_START"),  (0x4013, "DMC_LEN"),
+    (0x4014, "OAMDMA"),     (0x4015, "SND_CHN"),    (0x4016, "JOY1"),       (0x4017, "JOY2"),
+];
+
+/**
+ * Memory to disassemble
+ */
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum AddressSpace {
+    Cpu, // As the CPU currently sees it. Only $8000-$FFFF is indexed
+    Prg { bank: u8, size: usize, base: u16 }, // PRG-ROM bank of `size` bytes at offset bank * size, as if mapped at base
+}
+
+/**
+ * A range of PRG-ROM mapped at a CPU address, whose instruction boundaries are cached
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub (crate) struct Window {
+    pub (crate) bank: u8,
+    pub (crate) size: usize,
+    pub (crate) base: u16,
+}
+
+/**
+ * An instruction as found in memory
+ */
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct DisassembledInstruction {
+    pub address: u16,
+    pub bank: Option<u8>, // PRG bank, for $8000-$FFFF
+    pub bytes: Vec<u8>, // Opcode then operand
+    pub operator: Operator,
+    pub mode: AddressingMode,
+    pub illegal: bool,
+    pub target: Option<u16>, // Address the operand refers to, before indexing. Branches resolve to their destination
+    pub label: Option<String>, // Name of the instruction's address
+    pub target_label: Option<String>,
+}
+
+impl DisassembledInstruction {
+    /**
+     * Operand as written, with the target named when it has a label
+     */
+    pub fn operand (&self) -> Option<String> {
+        let target = |digits: usize| match &self.target_label {
+            Some (label) => label.clone(),
+            None => format!("${:0digits$X}", self.target.unwrap_or(0), digits = digits),
+        };
+
+        match self.mode {
+            AddressingMode::Implied     => None,
+            AddressingMode::Accumulator => Some("A".to_string()),
+            AddressingMode::Immediate   => Some(format!("#${:02X}", self.bytes[1])),
+            AddressingMode::Relative    => Some(target(4)),
+            AddressingMode::Absolute    => Some(target(4)),
+            AddressingMode::AbsoluteX   => Some(format!("{},X", target(4))),
+            AddressingMode::AbsoluteY   => Some(format!("{},Y", target(4))),
+            AddressingMode::Indirect    => Some(format!("({})", target(4))),
+            AddressingMode::ZeroPage    => Some(target(2)),
+            AddressingMode::ZeroPageX   => Some(format!("{},X", target(2))),
+            AddressingMode::ZeroPageY   => Some(format!("{},Y", target(2))),
+            AddressingMode::IndirectX   => Some(format!("({},X)", target(2))),
+            AddressingMode::IndirectY   => Some(format!("({}),Y", target(2))),
+        }
+    }
+}
+
+impl fmt::Display for DisassembledInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.operand() {
+            Some (operand) => write!(f, "{:?} {}", self.operator, operand),
+            None => write!(f, "{:?}", self.operator),
+        }
+    }
+}
+
+fn peek (bus: &Bus, space: AddressSpace, address: u16) -> Option<u8> {
+    match space {
+        AddressSpace::Cpu => bus.peek(address),
+        AddressSpace::Prg { bank, size, base } => {
+            let offset = (address as usize).checked_sub(base as usize).filter(|&offset| offset < size)?;
+            bus.cartridge.prg_rom.get(bank as usize * size + offset).copied()
+        },
+    }
+}
+
+/**
+ * Addresses instructions start at, stepping over bytes that can't be peeked
+ */
+fn sweep (bus: &Bus, space: AddressSpace, window: Window) -> Vec<u16> {
+    let mut addresses = vec![];
+    let mut address = window.base as usize;
+
+    while address < window.base as usize + window.size && address <= 0xFFFF {
+        addresses.push(address as u16);
+        address += peek(bus, space, address as u16).map_or(1, |opcode| Instruction::from_opcode(opcode).length() as usize);
+    }
+
+    addresses
+}
+
+impl Emulator {
+    /**
+     * PRG bank mapped at an address, for $8000-$FFFF
+     */
+    pub fn prg_bank_at (&self, address: u16) -> Option<u8> {
+        match address {
+            0x8000 ..= 0xFFFF => Some(self.bus.cartridge.mapper.get_bank_at(&self.bus.cartridge.prg_rom, address)),
+            _ => None,
+        }
+    }
+
+    /**
+     * Instruction at an address, None if its bytes can't be read without side effects
+     */
+    pub fn disassemble (&self, space: AddressSpace, address: u16) -> Option<DisassembledInstruction> {
+        let instruction = Instruction::from_opcode(peek(&self.bus, space, address)?);
+        let bytes = (0 .. instruction.length())
+            .map(|offset| peek(&self.bus, space, address.wrapping_add(offset)))
+            .collect::<Option<Vec<u8>>>()?;
+        let target = match instruction.mode {
+            AddressingMode::Implied | AddressingMode::Accumulator | AddressingMode::Immediate => None,
+            AddressingMode::Relative => Some(address.wrapping_add(2).wrapping_add(bytes[1] as i8 as u16)),
+            _ => Some(bytes[1..].iter().rev().fold(0, |word, &byte| word << 8 | byte as u16)),
+        };
+        let bank = self.bank_in(space, address);
+
+        Some(DisassembledInstruction {
+            address,
+            bank,
+            bytes,
+            operator: instruction.operator,
+            mode: instruction.mode,
+            illegal: instruction.illegal,
+            target,
+            label: self.label(space, address),
+            target_label: target.and_then(|target| self.label(space, target)),
+        })
+    }
+
+    /**
+     * Number of instructions in the space, as found by a linear sweep of each bank
+     */
+    pub fn disassembly_len (&mut self, space: AddressSpace) -> usize {
+        self.windows(space).into_iter().map(|window| self.boundaries(space, window).len()).sum()
+    }
+
+    /**
+     * Address of the nth instruction in the space
+     */
+    pub fn disassembly_address (&mut self, space: AddressSpace, index: usize) -> Option<u16> {
+        let mut index = index;
+
+        for window in self.windows(space) {
+            let boundaries = self.boundaries(space, window);
+
+            match boundaries.get(index) {
+                Some (&address) => return Some(address),
+                None => index -= boundaries.len(),
+            }
+        }
+
+        None
+    }
+
+    /**
+     * Index of the first instruction starting at or after an address, i.e. the number of instructions before it
+     */
+    pub fn disassembly_index (&mut self, space: AddressSpace, address: u16) -> usize {
+        let mut index = 0;
+
+        for window in self.windows(space) {
+            let boundaries = self.boundaries(space, window);
+            let before = boundaries.partition_point(|&start| start < address);
+
+            index += before;
+            if before < boundaries.len() {
+                break;
+            }
+        }
+
+        index
+    }
+
+    /**
+     * Banks making up the space. The CPU's repeat when they don't fill $8000-$FFFF, like mirrored PRG-ROM does
+     */
+    pub (crate) fn windows (&self, space: AddressSpace) -> Vec<Window> {
+        match space {
+            AddressSpace::Cpu => {
+                let banks = self.bus.cartridge.mapper.get_current_prg(&self.bus.cartridge.prg_rom);
+                let mut windows = vec![];
+                let mut base = 0x8000;
+
+                for bank in banks.iter().cycle().take_while(|bank| bank.size > 0) {
+                    if base > 0xFFFF {
+                        break;
+                    }
+                    windows.push(Window { bank: bank.number, size: bank.size, base: base as u16 });
+                    base += bank.size;
+                }
+
+                windows
+            },
+            AddressSpace::Prg { bank, size, base } => vec![Window { bank, size, base }],
+        }
+    }
+
+    fn boundaries (&mut self, space: AddressSpace, window: Window) -> &[u16] {
+        let bus = &self.bus;
+        self.debugger.boundaries.entry(window).or_insert_with(|| sweep(bus, space, window))
+    }
+
+    /**
+     * Bank of an address: the one disassembled when it's in there, otherwise the one the CPU has mapped
+     */
+    fn bank_in (&self, space: AddressSpace, address: u16) -> Option<u8> {
+        match space {
+            AddressSpace::Prg { bank, size, base } if (base as usize .. base as usize + size).contains(&(address as usize)) => Some(bank),
+            _ => self.prg_bank_at(address),
+        }
+    }
+
+    /**
+     * PRG-ROM offset of an address: in the bank disassembled when it's in there, otherwise in the one the CPU has mapped
+     */
+    fn prg_offset (&self, space: AddressSpace, address: u16) -> Option<usize> {
+        let windows = match space {
+            AddressSpace::Prg { .. } => self.windows(space).into_iter().chain(self.windows(AddressSpace::Cpu)).collect(),
+            AddressSpace::Cpu => self.windows(space),
+        };
+
+        windows.into_iter()
+            .find(|window| (window.base as usize .. window.base as usize + window.size).contains(&(address as usize)))
+            .map(|window| window.bank as usize * window.size + (address - window.base) as usize)
+    }
+
+    /**
+     * Name of an address: a loaded symbol, a register, a vector, or a handler a vector points to in the bank mapped now
+     */
+    fn label (&self, space: AddressSpace, address: u16) -> Option<String> {
+        let symbol = match self.prg_offset(space, address) {
+            Some (offset) => self.debugger.symbols.at_prg(offset),
+            None => self.debugger.symbols.at_cpu(address),
+        };
+        if let Some(symbol) = symbol {
+            return Some(symbol.name.clone());
+        }
+
+        let bank = self.bank_in(space, address);
+        let register = REGISTERS.iter().find(|(register, _)| *register == address).map(|(_, name)| *name);
+        let vector = VECTORS.iter().find(|(vector, ..)| *vector == address).map(|(_, name, _)| *name);
+        let handler = VECTORS.iter().find(|(vector, ..)| {
+            let handler = self.bus.peek(*vector).zip(self.bus.peek(vector + 1)).map(|(low, high)| u16::from_le_bytes([low, high]));
+            handler == Some(address) && self.prg_bank_at(address) == bank
+        }).map(|(.., name)| *name);
+
+        register.or(vector).or(handler).map(|name| name.to_string())
+    }
+}
@@ -0,0 +1,319 @@
+//! Breakpoint conditions, e.g. `A == $40 && [$0300] > 3 && scanline < 20`
+//!
+//! Values: numbers ($40, 0x40 or 64), registers A, X, Y, P, SP, PC, PPU scanline, dot and frame, the breakpoint hit count
+//! (hits, this hit included), and memory [address] read without side effects, 0 where reading would have some.
+//! Operators, by increasing precedence: || && | ^ & == != < <= > >= << >> + - * / % and unary ! - ~, with parentheses.
+//! Comparisons are 1 when true and 0 otherwise, and a condition holds when it isn't 0.
+
+use std::fmt;
+use crate::{bus::Bus, cpu::Cpu};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpressionError {
+    pub column: usize, // From 1
+    pub reason: String,
+}
+
+impl fmt::Display for ExpressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid condition, column {}: {}", self.column, self.reason)
+    }
+}
+
+impl std::error::Error for ExpressionError {}
+
+#[cfg(feature = "wasm")]
+impl From<ExpressionError> for JsValue {
+    fn from (error: ExpressionError) -> Self {
+        let js_error = js_sys::Error::new(&error.to_string());
+        js_error.set_name("ExpressionError");
+        js_error.into()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Variable {
+    A, X, Y, P, SP, PC, Scanline, Dot, Frame, Hits,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operator {
+    Or, And, BitOr, BitXor, BitAnd, Equal, NotEqual, Less, LessEqual, Greater, GreaterEqual,
+    ShiftLeft, ShiftRight, Add, Subtract, Multiply, Divide, Remainder,
+    Not, Negate, Complement, // Unary
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Number (i64),
+    Variable (Variable),
+    Memory (Box<Node>),
+    Unary (Operator, Box<Node>),
+    Binary (Operator, Box<Node>, Box<Node>),
+}
+
+/**
+ * Parsed condition, keeping its source to show it back
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    pub source: String,
+    pub root: Node,
+}
+
+impl serde::Serialize for Expression {
+    fn serialize<S: serde::Serializer> (&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number (i64),
+    Variable (Variable),
+    Operator (&'static str),
+}
+
+/**
+ * Binary operators by increasing precedence
+ */
+const BINARY: [&[(&str, Operator)]; 10] = [
+    &[("||", Operator::Or)],
+    &[("&&", Operator::And)],
+    &[("|", Operator::BitOr)],
+    &[("^", Operator::BitXor)],
+    &[("&", Operator::BitAnd)],
+    &[("==", Operator::Equal), ("!=", Operator::NotEqual)],
+    &[("<", Operator::Less), ("<=", Operator::LessEqual), (">", Operator::Greater), (">=", Operator::GreaterEqual)],
+    &[("<<", Operator::ShiftLeft), (">>", Operator::ShiftRight)],
+    &[("+", Operator::Add), ("-", Operator::Subtract)],
+    &[("*", Operator::Multiply), ("/", Operator::Divide), ("%", Operator::Remainder)],
+];
+
+// Longest first, so that "<=" isn't read as "<"
+const SYMBOLS: [&str; 24] = [
+    "||", "&&", "==", "!=", "<=", ">=", "<<", ">>",
+    "|", "^", "&", "<", ">", "+", "-", "*", "/", "%", "!", "~", "(", ")", "[", "]",
+];
+
+fn tokenize (source: &str) -> Result<Vec<(usize, Token)>, ExpressionError> {
+    let mut tokens = vec![];
+    let mut position = 0;
+    let error = |position: usize, reason: String| ExpressionError { column: position + 1, reason };
+
+    while position < source.len() {
+        let rest = &source[position..];
+        let character = rest.chars().next().unwrap();
+
+        if character.is_whitespace() {
+            position += character.len_utf8();
+            continue;
+        }
+
+        if let Some(symbol) = SYMBOLS.iter().find(|symbol| rest.starts_with(*symbol)) {
+            tokens.push((position, Token::Operator(symbol)));
+            position += symbol.len();
+            continue;
+        }
+
+        let length = rest.find(|character: char| !character.is_ascii_alphanumeric() && character != '$' && character != '_').unwrap_or(rest.len());
+        let word = &rest[..length.max(character.len_utf8())];
+
+        let token = if character == '$' || character.is_ascii_digit() {
+            let (digits, radix) = match word.strip_prefix('$').or_else(|| word.strip_prefix("0x")) {
+                Some (digits) => (digits, 16),
+                None => (word, 10),
+            };
+
+            Token::Number(i64::from_str_radix(digits, radix).map_err(|_| error(position, format!("invalid number \"{}\"", word)))?)
+        } else {
+            Token::Variable(match word.to_lowercase().as_str() {
+                "a" => Variable::A,
+                "x" => Variable::X,
+                "y" => Variable::Y,
+                "p" => Variable::P,
+                "sp" => Variable::SP,
+                "pc" => Variable::PC,
+                "scanline" => Variable::Scanline,
+                "dot" => Variable::Dot,
+                "frame" => Variable::Frame,
+                "hits" => Variable::Hits,
+                _ => return Err(error(position, format!("unknown name \"{}\"", word))),
+            })
+        };
+
+        tokens.push((position, token));
+        position += word.len();
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+    end: usize, // Position reported for errors at the end
+}
+
+impl Parser {
+    fn peek (&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(_, token)| token)
+    }
+
+    fn error (&self, reason: &str) -> ExpressionError {
+        let position = self.tokens.get(self.next).map_or(self.end, |(position, _)| *position);
+        ExpressionError { column: position + 1, reason: reason.to_string() }
+    }
+
+    fn expect (&mut self, symbol: &str) -> Result<(), ExpressionError> {
+        match self.peek() {
+            Some (Token::Operator (operator)) if *operator == symbol => {
+                self.next += 1;
+                Ok(())
+            },
+            _ => Err(self.error(&format!("expected \"{}\"", symbol))),
+        }
+    }
+
+    /**
+     * Operands bound by operators of at least the given precedence
+     */
+    fn binary (&mut self, precedence: usize) -> Result<Node, ExpressionError> {
+        if precedence == BINARY.len() {
+            return self.unary();
+        }
+
+        let mut node = self.binary(precedence + 1)?;
+
+        while let Some(&(_, operator)) = BINARY[precedence].iter().find(|(symbol, _)| self.peek() == Some(&Token::Operator(symbol))) {
+            self.next += 1;
+            node = Node::Binary(operator, Box::new(node), Box::new(self.binary(precedence + 1)?));
+        }
+
+        Ok(node)
+    }
+
+    fn unary (&mut self) -> Result<Node, ExpressionError> {
+        let token = self.peek().cloned().ok_or_else(|| self.error("expected a value"))?;
+        self.next += 1;
+
+        match token {
+            Token::Number (number) => Ok(Node::Number(number)),
+            Token::Variable (variable) => Ok(Node::Variable(variable)),
+            Token::Operator ("!") => Ok(Node::Unary(Operator::Not, Box::new(self.unary()?))),
+            Token::Operator ("-") => Ok(Node::Unary(Operator::Negate, Box::new(self.unary()?))),
+            Token::Operator ("~") => Ok(Node::Unary(Operator::Complement, Box::new(self.unary()?))),
+            Token::Operator ("(") => {
+                let node = self.binary(0)?;
+                self.expect(")")?;
+                Ok(node)
+            },
+            Token::Operator ("[") => {
+                let node = self.binary(0)?;
+                self.expect("]")?;
+                Ok(Node::Memory(Box::new(node)))
+            },
+            _ => {
+                self.next -= 1;
+                Err(self.error("expected a value"))
+            },
+        }
+    }
+}
+
+impl Expression {
+    pub fn parse (source: &str) -> Result<Self, ExpressionError> {
+        let mut parser = Parser { tokens: tokenize(source)?, next: 0, end: source.len() };
+        let root = parser.binary(0)?;
+
+        if parser.peek().is_some() {
+            return Err(parser.error("expected an operator"));
+        }
+
+        Ok(Self { source: source.to_string(), root })
+    }
+
+    pub fn evaluate (&self, cpu: &Cpu, bus: &Bus, hits: u32) -> i64 {
+        evaluate(&self.root, cpu, bus, hits)
+    }
+}
+
+fn evaluate (node: &Node, cpu: &Cpu, bus: &Bus, hits: u32) -> i64 {
+    let value = |node: &Node| evaluate(node, cpu, bus, hits);
+
+    match node {
+        Node::Number (number) => *number,
+        Node::Variable (variable) => match variable {
+            Variable::A => cpu.a as i64,
+            Variable::X => cpu.x as i64,
+            Variable::Y => cpu.y as i64,
+            Variable::P => cpu.status as i64,
+            Variable::SP => cpu.sp as i64,
+            Variable::PC => cpu.pc as i64,
+            Variable::Scanline => bus.ppu.scanline as i64,
+            Variable::Dot => bus.ppu.dot as i64,
+            Variable::Frame => bus.ppu.frame as i64,
+            Variable::Hits => hits as i64,
+        },
+        Node::Memory (address) => bus.peek(value(address) as u16).unwrap_or(0) as i64,
+        Node::Unary (operator, operand) => {
+            let operand = value(operand);
+
+            match operator {
+                Operator::Not => (operand == 0) as i64,
+                Operator::Negate => operand.wrapping_neg(),
+                _ => !operand,
+            }
+        },
+        Node::Binary (Operator::Or, left, right) => (value(left) != 0 || value(right) != 0) as i64,
+        Node::Binary (Operator::And, left, right) => (value(left) != 0 && value(right) != 0) as i64,
+        Node::Binary (operator, left, right) => {
+            let (left, right) = (value(left), value(right));
+
+            match operator {
+                Operator::BitOr => left | right,
+                Operator::BitXor => left ^ right,
+                Operator::BitAnd => left & right,
+                Operator::Equal => (left == right) as i64,
+                Operator::NotEqual => (left != right) as i64,
+                Operator::Less => (left < right) as i64,
+                Operator::LessEqual => (left <= right) as i64,
+                Operator::Greater => (left > right) as i64,
+                Operator::GreaterEqual => (left >= right) as i64,
+                Operator::ShiftLeft => left.wrapping_shl(right as u32),
+                Operator::ShiftRight => left.wrapping_shr(right as u32),
+                Operator::Add => left.wrapping_add(right),
+                Operator::Subtract => left.wrapping_sub(right),
+                Operator::Multiply => left.wrapping_mul(right),
+                Operator::Divide => left.checked_div(right).unwrap_or(0),
+                _ => left.checked_rem(right).unwrap_or(0),
+            }
+        },
+    }
+}
+
+#[test]
+fn parse () {
+    let number = |number| Box::new(Node::Number(number));
+
+    assert_eq!(Expression::parse("1 + 2 * 3 == 7 || !0").unwrap().root, Node::Binary(Operator::Or,
+        Box::new(Node::Binary(Operator::Equal,
+            Box::new(Node::Binary(Operator::Add, number(1), Box::new(Node::Binary(Operator::Multiply, number(2), number(3))))),
+            number(7))),
+        Box::new(Node::Unary(Operator::Not, number(0))),
+    ));
+    assert_eq!(Expression::parse("[$0300 + x] <= 0x10").unwrap().root, Node::Binary(Operator::LessEqual,
+        Box::new(Node::Memory(Box::new(Node::Binary(Operator::Add, number(0x300), Box::new(Node::Variable(Variable::X)))))),
+        number(16),
+    ));
+
+    let error = |source: &str| Expression::parse(source).unwrap_err();
+    assert_eq!(error("A == $4G"), ExpressionError { column: 6, reason: "invalid number \"$4G\"".to_string() });
+    assert_eq!(error("scanline < "), ExpressionError { column: 12, reason: "expected a value".to_string() });
+    assert_eq!(error("(A == 1"), ExpressionError { column: 8, reason: "expected \")\"".to_string() });
+    assert_eq!(error("A X"), ExpressionError { column: 3, reason: "expected an operator".to_string() });
+    assert_eq!(error("hits == @"), ExpressionError { column: 9, reason: "unknown name \"@\"".to_string() });
+    assert_eq!(error("cycles > 3"), ExpressionError { column: 1, reason: "unknown name \"cycles\"".to_string() });
+}
@@ -0,0 +1,11 @@
+mod debugger;
+mod disassembler;
+mod expression;
+mod symbols;
+mod trace;
+
+pub use debugger::*;
+pub use disassembler::*;
+pub use expression::*;
+pub use symbols::*;
+pub use trace::*;
@@ -0,0 +1,338 @@
+//! Symbols from the assembler or other emulators, naming addresses in the disassembly and resolving breakpoints by name.
+//! Code and data in PRG-ROM are keyed by their offset in it, i.e. by bank and address, anything else by CPU address.
+//!
+//! ca65 debug info (ld65 --dbgfile): https://cc65.github.io/doc/debugging.html
+//! FCEUX (.nl), one file per 16 KiB PRG bank named `game.nes.<bank in hex>.nl`, and `game.nes.ram.nl` for $0000-$7FFF:
+//! http://fceux.com/web/help/NLFilesFormat.html
+//! Mesen (.mlb), lines of `<memory type>:<address or offset>[-<end>]:<label>[:<comment>]`
+
+use std::{collections::HashMap, fmt};
+use crate::{AddressSpace, BreakpointKind, Emulator};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+const INES_HEADER_SIZE: usize = 16;
+const FCEUX_BANK_SIZE: usize = 0x4000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SymbolError {
+    UnknownFormat(String), // File name
+    Syntax { line: usize, reason: String },
+    UnknownSymbol(String),
+}
+
+impl fmt::Display for SymbolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SymbolError::UnknownFormat(name)        => write!(f, "Unknown symbol file {}, expected .dbg, .nl or .mlb", name),
+            SymbolError::Syntax { line, reason }    => write!(f, "Invalid symbol file, line {}: {}", line, reason),
+            SymbolError::UnknownSymbol(name)        => write!(f, "Unknown symbol {}", name),
+        }
+    }
+}
+
+impl std::error::Error for SymbolError {}
+
+#[cfg(feature = "wasm")]
+impl From<SymbolError> for JsValue {
+    fn from (error: SymbolError) -> Self {
+        let js_error = js_sys::Error::new(&error.to_string());
+        js_error.set_name("SymbolError");
+        js_error.into()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub enum Location {
+    Cpu (u16), // RAM, registers, or anything else outside PRG-ROM
+    Prg { offset: usize, address: Option<u16> }, // The CPU address it was assembled for, when the file tells
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Symbol {
+    pub name: String,
+    pub location: Location,
+}
+
+/**
+ * The first symbol loaded for a location or name wins
+ */
+#[derive(Default)]
+pub struct SymbolTable {
+    symbols: Vec<Symbol>,
+    cpu: HashMap<u16, usize>,
+    prg: HashMap<usize, usize>,
+    names: HashMap<String, usize>,
+}
+
+impl SymbolTable {
+    pub fn add (&mut self, symbol: Symbol) {
+        let index = self.symbols.len();
+
+        match symbol.location {
+            Location::Cpu (address) => self.cpu.entry(address).or_insert(index),
+            Location::Prg { offset, .. } => self.prg.entry(offset).or_insert(index),
+        };
+        self.names.entry(symbol.name.clone()).or_insert(index);
+        self.symbols.push(symbol);
+    }
+
+    pub fn at_cpu (&self, address: u16) -> Option<&Symbol> {
+        self.cpu.get(&address).map(|&index| &self.symbols[index])
+    }
+
+    pub fn at_prg (&self, offset: usize) -> Option<&Symbol> {
+        self.prg.get(&offset).map(|&index| &self.symbols[index])
+    }
+
+    pub fn find (&self, name: &str) -> Option<&Symbol> {
+        self.names.get(name).map(|&index| &self.symbols[index])
+    }
+
+    pub fn symbols (&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /**
+     * Load a file, told apart by its name. Nothing is loaded if it can't be parsed. Returns the number of symbols loaded
+     */
+    pub fn load (&mut self, file_name: &str, contents: &str) -> Result<usize, SymbolError> {
+        let symbols = match file_name.rsplit('.').next().unwrap_or("").to_ascii_lowercase().as_str() {
+            "dbg" => parse_ca65(contents)?,
+            "mlb" => parse_mesen(contents)?,
+            "nl" => {
+                // game.nes.ram.nl or game.nes.1.nl
+                let bank = file_name.rsplit('.').nth(1).unwrap_or("");
+                match bank.to_ascii_lowercase().as_str() {
+                    "ram" => parse_fceux(contents, None)?,
+                    bank => parse_fceux(contents, Some(u8::from_str_radix(bank, 16).map_err(|_| SymbolError::UnknownFormat(file_name.to_string()))?))?,
+                }
+            },
+            _ => return Err(SymbolError::UnknownFormat(file_name.to_string())),
+        };
+        let count = symbols.len();
+
+        for symbol in symbols {
+            self.add(symbol);
+        }
+
+        Ok(count)
+    }
+}
+
+fn syntax (line: usize, reason: &str) -> SymbolError {
+    SymbolError::Syntax { line: line + 1, reason: reason.to_string() }
+}
+
+fn parse_number (text: &str) -> Option<usize> {
+    match text.strip_prefix("0x").or_else(|| text.strip_prefix('$')) {
+        Some (hex) => usize::from_str_radix(hex, 16).ok(),
+        None => text.parse().ok(),
+    }
+}
+
+/**
+ * `keyword<tab>key=value,...` where values may be quoted
+ */
+fn parse_ca65_fields (fields: &str) -> HashMap<&str, &str> {
+    let mut map = HashMap::new();
+    let mut rest = fields.trim();
+
+    while !rest.is_empty() {
+        let (key, value) = rest.split_at(rest.find('=').unwrap_or(rest.len()));
+        let value = value.strip_prefix('=').unwrap_or("");
+        let end = if let Some(quoted) = value.strip_prefix('"') {
+            quoted.find('"').map_or(value.len(), |end| end + 2)
+        } else {
+            value.find(',').unwrap_or(value.len())
+        };
+
+        map.insert(key, value[..end].trim_matches('"'));
+        rest = value[end..].strip_prefix(',').unwrap_or(&value[end..]);
+    }
+
+    map
+}
+
+/**
+ * Labels only, equates being constants as often as addresses. Segments written to the ROM give PRG-ROM offsets
+ */
+fn parse_ca65 (dbg: &str) -> Result<Vec<Symbol>, SymbolError> {
+    let mut segments = HashMap::new(); // Id to start address and offset in the ROM file
+    let mut labels = vec![];
+
+    for (number, line) in dbg.lines().enumerate() {
+        let (keyword, fields) = line.split_at(line.find(char::is_whitespace).unwrap_or(line.len()));
+        let fields = parse_ca65_fields(fields);
+        let number_field = |key: &str| fields.get(key).map(|value| parse_number(value).ok_or_else(|| syntax(number, &format!("invalid {}", key))));
+
+        match keyword {
+            "seg" => {
+                let id = number_field("id").ok_or_else(|| syntax(number, "segment without id"))??;
+                let start = number_field("start").ok_or_else(|| syntax(number, "segment without start"))??;
+                segments.insert(id, (start, number_field("ooffs").transpose()?));
+            },
+            "sym" if fields.get("type") == Some(&"lab") => {
+                let name = fields.get("name").ok_or_else(|| syntax(number, "symbol without name"))?;
+                let value = number_field("val").ok_or_else(|| syntax(number, "label without value"))??;
+                labels.push((number, name.to_string(), value, number_field("seg").transpose()?));
+            },
+            _ => {},
+        }
+    }
+
+    labels.into_iter().filter_map(|(number, name, value, segment)| {
+        let location = match segment.map(|segment| segments.get(&segment)) {
+            Some (None) => return Some(Err(syntax(number, "unknown segment"))),
+            Some (Some (&(start, Some (file_offset)))) => {
+                // The iNES header is written to the ROM too, ahead of PRG-ROM
+                let offset = (file_offset + value).checked_sub(start + INES_HEADER_SIZE)?;
+                Location::Prg { offset, address: Some(value as u16) }
+            },
+            _ => Location::Cpu(value as u16),
+        };
+
+        Some(Ok(Symbol { name, location }))
+    }).collect()
+}
+
+/**
+ * `$C000#Name#Comment`, or `$0300/10#Name#` for an array
+ */
+fn parse_fceux (nl: &str, bank: Option<u8>) -> Result<Vec<Symbol>, SymbolError> {
+    let mut symbols = vec![];
+
+    for (number, line) in nl.lines().enumerate() {
+        if !line.starts_with('$') {
+            continue; // Multi-line comments
+        }
+
+        let mut fields = line.splitn(3, '#');
+        let address = fields.next().unwrap_or("").split('/').next().unwrap_or("");
+        let address = parse_number(address).filter(|&address| address <= 0xFFFF).ok_or_else(|| syntax(number, "invalid address"))? as u16;
+        let name = fields.next().ok_or_else(|| syntax(number, "expected a name"))?.trim();
+
+        if name.is_empty() {
+            continue;
+        }
+
+        let location = match bank {
+            Some (bank) => Location::Prg { offset: bank as usize * FCEUX_BANK_SIZE + address as usize % FCEUX_BANK_SIZE, address: Some(address) },
+            None => Location::Cpu(address),
+        };
+        symbols.push(Symbol { name: name.to_string(), location });
+    }
+
+    Ok(symbols)
+}
+
+/**
+ * Memory types are those of Mesen and Mesen 2. Other memories, such as CHR, are skipped
+ */
+fn parse_mesen (mlb: &str) -> Result<Vec<Symbol>, SymbolError> {
+    let mut symbols = vec![];
+
+    for (number, line) in mlb.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+        let fields: Vec<&str> = line.splitn(4, ':').collect();
+        if fields.len() < 3 {
+            return Err(syntax(number, "expected memory type, address and label"));
+        }
+
+        let start = fields[1].split('-').next().unwrap_or("");
+        let address = usize::from_str_radix(start, 16).map_err(|_| syntax(number, "invalid address"))?;
+        let name = fields[2].trim();
+        // Offset in a memory of limit + 1 bytes mapped at base
+        let cpu = |base: u16, limit: usize| if address <= limit {
+            Ok(Location::Cpu(base + address as u16))
+        } else {
+            Err(syntax(number, "address out of range"))
+        };
+
+        let location = match fields[0] {
+            "P" | "NesPrgRom" => Location::Prg { offset: address, address: None },
+            "R" | "NesInternalRam" => cpu(0x0000, 0x07FF)?,
+            "G" | "NesMemory" => cpu(0x0000, 0xFFFF)?,
+            "S" | "NesSaveRam" | "W" | "NesWorkRam" => cpu(0x6000, 0x1FFF)?,
+            _ => continue,
+        };
+
+        if !name.is_empty() {
+            symbols.push(Symbol { name: name.to_string(), location });
+        }
+    }
+
+    Ok(symbols)
+}
+
+impl Emulator {
+    /**
+     * Load a ca65 debug file (.dbg), or FCEUX (.nl) or Mesen (.mlb) labels, adding to those already loaded
+     */
+    pub fn load_symbols (&mut self, file_name: &str, contents: &str) -> Result<usize, SymbolError> {
+        self.debugger.symbols.load(file_name, contents)
+    }
+
+    pub fn clear_symbols (&mut self) {
+        self.debugger.symbols = SymbolTable::default();
+    }
+
+    pub fn symbols (&self) -> &SymbolTable {
+        &self.debugger.symbols
+    }
+
+    /**
+     * CPU address of a symbol, and the PRG bank that must be mapped there when it's in PRG-ROM.
+     * A bank that isn't mapped and whose address the file doesn't tell is assumed to be switched in at $8000
+     */
+    pub fn resolve_symbol (&self, name: &str) -> Result<(u16, Option<u8>), SymbolError> {
+        let symbol = self.debugger.symbols.find(name).ok_or_else(|| SymbolError::UnknownSymbol(name.to_string()))?;
+
+        match symbol.location {
+            Location::Cpu (address) => Ok((address, None)),
+            Location::Prg { offset, address } => {
+                let windows = self.windows(AddressSpace::Cpu);
+                let window = windows.iter()
+                    .find(|window| address.map_or(window.bank as usize == offset / window.size, |address| (window.base as usize .. window.base as usize + window.size).contains(&(address as usize))))
+                    .or_else(|| windows.first())
+                    .ok_or_else(|| SymbolError::UnknownSymbol(name.to_string()))?;
+                let address = address.unwrap_or(window.base + (offset % window.size) as u16);
+
+                Ok((address, Some((offset / window.size) as u8)))
+            },
+        }
+    }
+
+    /**
+     * Break when the code at a symbol runs, in its bank
+     */
+    pub fn add_breakpoint_at (&mut self, name: &str) -> Result<u32, SymbolError> {
+        let (address, bank) = self.resolve_symbol(name)?;
+
+        Ok(self.add_breakpoint(BreakpointKind::Execute { address, bank }))
+    }
+
+    /**
+     * Watch accesses to a variable
+     */
+    pub fn add_watchpoint_at (&mut self, name: &str, read: bool, write: bool) -> Result<u32, SymbolError> {
+        let (address, _) = self.resolve_symbol(name)?;
+
+        Ok(self.add_breakpoint(BreakpointKind::Access { start: address, end: address, read, write }))
+    }
+}
+
+#[test]
+fn ca65 () {
+    let dbg = "version\tmajor=2,minor=0\n\
+        seg\tid=0,name=\"CODE\",start=0x00C000,size=0x0040,addrsize=absolute,type=ro,oname=\"a,b.nes\",ooffs=16\n\
+        seg\tid=1,name=\"BSS\",start=0x000300,size=0x0002,addrsize=absolute,type=rw\n\
+        sym\tid=0,name=\"ReadJoypad\",addrsize=absolute,scope=0,def=1,val=0xC020,seg=0,type=lab\n\
+        sym\tid=1,name=\"Calls\",addrsize=absolute,scope=0,def=2,val=0x300,seg=1,type=lab\n\
+        sym\tid=2,name=\"BUTTON_A\",addrsize=zeropage,scope=0,def=3,val=0x80,type=equ\n";
+
+    assert_eq!(parse_ca65(dbg), Ok(vec![
+        Symbol { name: "ReadJoypad".to_string(), location: Location::Prg { offset: 0x20, address: Some(0xC020) } },
+        Symbol { name: "Calls".to_string(), location: Location::Cpu(0x0300) },
+    ]));
+    assert_eq!(parse_ca65("sym\tid=0,name=\"A\",val=0x10,seg=3,type=lab"), Err(SymbolError::Syntax { line: 1, reason: "unknown segment".to_string() }));
+}
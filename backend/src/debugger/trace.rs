@@ -0,0 +1,176 @@
+//! Execution trace: a line per instruction, logged before it runs, to diff against other emulators.
+//!
+//! Nestest (Nintendulator): `C000  4C F5 C5  JMP $C5F5    A:00 X:00 Y:00 P:24 SP:FD PPU:  0, 21 CYC:7`
+//! Mesen: same up to the registers, then `CYC:<dot> SL:<scanline> CPU Cycle:<cycles>`, the pre-render scanline being -1.
+//! Operands are annotated with the addresses and values they resolve to, read without side effects.
+
+use std::collections::VecDeque;
+use crate::{
+    bus::Bus,
+    cpu::{AddressingMode, Cpu, Instruction, Operator},
+    Emulator,
+};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TraceFormat {
+    Nestest,
+    Mesen,
+}
+
+pub enum TraceSink {
+    Buffer { lines: VecDeque<String>, capacity: usize }, // Keeps the last lines
+    Callback (Box<dyn FnMut(&str)>),
+}
+
+pub struct Tracer {
+    pub format: TraceFormat,
+    pub sink: TraceSink,
+}
+
+impl Tracer {
+    pub fn log (&mut self, line: String) {
+        match &mut self.sink {
+            TraceSink::Buffer { lines, capacity } => {
+                lines.push_back(line);
+                if lines.len() > *capacity {
+                    lines.pop_front();
+                }
+            },
+            TraceSink::Callback (callback) => callback(&line),
+        }
+    }
+}
+
+fn peek (bus: &Bus, address: u16) -> u8 {
+    bus.peek(address).unwrap_or(0)
+}
+
+/**
+ * Value shown for an operand. I/O registers show as FF like in Nintendulator, whether they can be peeked or not
+ */
+fn value (bus: &Bus, address: u16) -> u8 {
+    match address {
+        0x2000 ..= 0x401F => 0xFF,
+        _ => peek(bus, address),
+    }
+}
+
+fn peek_word (bus: &Bus, pointer: u16, wrap_page: bool) -> u16 {
+    let high = if wrap_page { (pointer & 0xFF00) | (pointer as u8).wrapping_add(1) as u16 } else { pointer.wrapping_add(1) };
+    (peek(bus, high) as u16) << 8 | peek(bus, pointer) as u16
+}
+
+/**
+ * Operand as written, followed by what it resolves to
+ */
+fn operand (cpu: &Cpu, bus: &Bus, instruction: &Instruction, pc: u16) -> String {
+    let byte = peek(bus, pc.wrapping_add(1));
+    let word = peek_word(bus, pc.wrapping_add(1), false);
+    let jump = matches!(instruction.operator, Operator::JMP | Operator::JSR);
+
+    match instruction.mode {
+        AddressingMode::Implied => String::new(),
+        AddressingMode::Accumulator => "A".to_string(),
+        AddressingMode::Immediate => format!("#${:02X}", byte),
+        AddressingMode::Relative => format!("${:04X}", pc.wrapping_add(2).wrapping_add(byte as i8 as u16)),
+        AddressingMode::Absolute if jump => format!("${:04X}", word),
+        AddressingMode::Absolute => format!("${:04X} = {:02X}", word, value(bus, word)),
+        AddressingMode::AbsoluteX | AddressingMode::AbsoluteY => {
+            let (index, name) = if instruction.mode == AddressingMode::AbsoluteX { (cpu.x, 'X') } else { (cpu.y, 'Y') };
+            let address = word.wrapping_add(index as u16);
+            format!("${:04X},{} @ {:04X} = {:02X}", word, name, address, value(bus, address))
+        },
+        AddressingMode::ZeroPage => format!("${:02X} = {:02X}", byte, peek(bus, byte as u16)),
+        AddressingMode::ZeroPageX | AddressingMode::ZeroPageY => {
+            let (index, name) = if instruction.mode == AddressingMode::ZeroPageX { (cpu.x, 'X') } else { (cpu.y, 'Y') };
+            let address = byte.wrapping_add(index);
+            format!("${:02X},{} @ {:02X} = {:02X}", byte, name, address, peek(bus, address as u16))
+        },
+        // The pointer's high byte is fetched without carrying into the next page
+        AddressingMode::Indirect => format!("(${:04X}) = {:04X}", word, peek_word(bus, word, true)),
+        AddressingMode::IndirectX => {
+            let pointer = byte.wrapping_add(cpu.x);
+            let address = peek_word(bus, pointer as u16, true);
+            format!("(${:02X},X) @ {:02X} = {:04X} = {:02X}", byte, pointer, address, value(bus, address))
+        },
+        AddressingMode::IndirectY => {
+            let base = peek_word(bus, byte as u16, true);
+            let address = base.wrapping_add(cpu.y as u16);
+            format!("(${:02X}),Y = {:04X} @ {:04X} = {:02X}", byte, base, address, value(bus, address))
+        },
+    }
+}
+
+/**
+ * Trace line for the instruction at PC, on an instruction boundary
+ */
+pub fn trace_line (cpu: &Cpu, bus: &Bus, format: TraceFormat) -> String {
+    let pc = cpu.pc;
+    let instruction = Instruction::from_opcode(peek(bus, pc));
+    let bytes: Vec<String> = (0..instruction.length()).map(|offset| format!("{:02X}", peek(bus, pc.wrapping_add(offset)))).collect();
+    let operator = match instruction.operator {
+        Operator::ISC => "ISB".to_string(), // Nintendulator's name
+        operator => format!("{:?}", operator),
+    };
+    let disassembly = format!("{}{} {}", if instruction.illegal { '*' } else { ' ' }, operator, operand(cpu, bus, &instruction, pc));
+    let registers = format!("A:{:02X} X:{:02X} Y:{:02X} P:{:02X} SP:{:02X}", cpu.a, cpu.x, cpu.y, cpu.status, cpu.sp);
+
+    let position = match format {
+        TraceFormat::Nestest => format!("PPU:{:>3},{:>3} CYC:{}", bus.ppu.scanline, bus.ppu.dot, cpu.clock.cycles),
+        TraceFormat::Mesen => {
+            let scanline = if bus.ppu.scanline == bus.ppu.region.scanlines() - 1 { -1 } else { bus.ppu.scanline as i32 };
+            format!("CYC:{:>3} SL:{:<3} CPU Cycle:{}", bus.ppu.dot, scanline, cpu.clock.cycles)
+        },
+    };
+
+    format!("{:04X}  {:<8} {:<33}{} {}", pc, bytes.join(" "), disassembly.trim_end(), registers, position)
+}
+
+impl Emulator {
+    /**
+     * Trace into a buffer keeping the last `capacity` lines, emptied by take_trace
+     */
+    pub fn trace_to_buffer (&mut self, format: TraceFormat, capacity: usize) {
+        let sink = TraceSink::Buffer { lines: VecDeque::with_capacity(capacity), capacity };
+        self.tracer = Some(Tracer { format, sink });
+    }
+
+    /**
+     * Trace by calling back with each line
+     */
+    pub fn trace_to_callback (&mut self, format: TraceFormat, callback: impl FnMut(&str) + 'static) {
+        self.tracer = Some(Tracer { format, sink: TraceSink::Callback(Box::new(callback)) });
+    }
+
+    pub fn stop_trace (&mut self) {
+        self.tracer = None;
+    }
+
+    /**
+     * Lines buffered since the last call, oldest first
+     */
+    pub fn take_trace (&mut self) -> Vec<String> {
+        match &mut self.tracer {
+            Some (Tracer { sink: TraceSink::Buffer { lines, .. }, .. }) => lines.drain(..).collect(),
+            _ => vec![],
+        }
+    }
+
+    /**
+     * On instruction boundaries. Interrupt sequences aren't logged, the first instruction of their handler is
+     */
+    pub (crate) fn trace (&mut self) {
+        if self.cpu.pending_interrupt().is_some() || self.cpu.halted {
+            return;
+        }
+
+        if let Some(tracer) = &mut self.tracer {
+            // The line shows the PPU position
+            self.bus.catch_up();
+            tracer.log(trace_line(&self.cpu, &self.bus, tracer.format));
+        }
+    }
+}
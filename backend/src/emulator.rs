@@ -1,46 +1,130 @@
-use wasm_bindgen::prelude::*;
-use crate::{bus, cpu, clock, input};
+use crate::{bus, cartridge, cpu, clock, input, Debugger, Movie, Tracer, MovieCommand, MovieError, MovieSession, Rewind, StateError};
 
-#[wasm_bindgen]
+/**
+ * The whole machine, driven by a host: JS through the wasm bindings, or any Rust program
+ */
 pub struct Emulator {
     pub (crate) cpu: cpu::Cpu,
     pub (crate) bus: bus::Bus,
     pub (crate) clock: clock::Clock,
+    pub (crate) rewind: Rewind,
+    pub (crate) movie: Option<MovieSession>,
+    pub (crate) debugger: Debugger,
+    pub (crate) tracer: Option<Tracer>,
+    power_on: Vec<u8>, // Snapshot to power cycle, which movies can start from
 }
 
-#[wasm_bindgen]
 impl Emulator {
-    pub fn new (rom: Vec<u8>, sample_rate: f64) -> Self {
+    /**
+     * Fails with a CartridgeError when the ROM can't be loaded
+     */
+    pub fn new (rom: Vec<u8>, sample_rate: f64) -> Result<Emulator, cartridge::CartridgeError> {
+        Emulator::build(rom, sample_rate, None)
+    }
+
+    /**
+     * Same as new, but running on the given region whatever the ROM header says
+     */
+    pub fn with_region (rom: Vec<u8>, sample_rate: f64, region: clock::Region) -> Result<Emulator, cartridge::CartridgeError> {
+        Emulator::build(rom, sample_rate, Some(region))
+    }
+
+    fn build (rom: Vec<u8>, sample_rate: f64, region: Option<clock::Region>) -> Result<Emulator, cartridge::CartridgeError> {
+        let bus = bus::Bus::new(&rom, sample_rate, region)?;
+        let region = bus.ppu.region;
         let mut emulator = Self {
-            cpu: cpu::Cpu::new(),
-            bus: bus::Bus::new(&rom, sample_rate),
-            clock: clock::Clock::new(crate::clock::CLOCK_MASTER_NTSC),
+            cpu: cpu::Cpu::new(region),
+            bus,
+            clock: clock::Clock::new(region.master_clock()),
+            rewind: Rewind::new(0, 0),
+            movie: None,
+            debugger: Debugger::default(),
+            tracer: None,
+            power_on: vec![],
         };
 
         emulator.cpu.reset();
+        emulator.power_on = emulator.save_state();
+
+        Ok(emulator)
+    }
 
-        emulator
+    pub fn region (&self) -> clock::Region {
+        self.bus.ppu.region
     }
 
     /**
-     * Run one master clock cycle
+     * Run one master clock cycle, leaving the PPU and APU behind until the bus needs them to catch up
+     * Returns whether the CPU completed an instruction
      */
-    pub fn cycle (&mut self) {
-        self.cpu.tick(self.clock.time, &mut self.bus);
-        self.bus.apu.tick(self.clock.time, &mut self.cpu);
-        self.bus.ppu.tick(self.clock.time, &self.bus.cartridge, &mut self.cpu);
+    pub (crate) fn tick (&mut self) -> bool {
+        let done = self.cpu.tick(&mut self.bus);
+        self.bus.ppu_lag += 1;
+        self.bus.apu_lag += 1;
 
         self.clock.tick();
+
+        if done && self.tracer.is_some() {
+            self.trace();
+        }
+
+        done
+    }
+
+    /**
+     * Run master clock cycles, skipping straight to each CPU cycle since nothing else happens in between
+     */
+    fn run (&mut self, master_cycles: u64) {
+        let mut remaining = master_cycles;
+
+        while remaining > 0 {
+            let idle = (self.cpu.clock.master_cycles_until(1) - 1).min(remaining - 1) as u32;
+
+            self.cpu.clock.advance(idle);
+            self.bus.ppu_lag += idle as i64;
+            self.bus.apu_lag += idle;
+            self.clock.cycles += idle as u64;
+
+            self.tick();
+            remaining -= idle as u64 + 1;
+        }
+    }
+
+    /**
+     * Run one master clock cycle
+     */
+    pub fn cycle (&mut self) {
+        self.tick();
+        self.bus.catch_up();
     }
 
     /**
-     * Cycle until frame is rendered
+     * Cycle until frame is rendered, capturing a rewind snapshot when one is due
      */
     pub fn cycle_until_frame (&mut self) {
-        let frame = self.bus.ppu.frame;
+        self.bus.catch_up();
+        self.play_movie_input();
 
-        while frame == self.bus.ppu.frame {
-            self.cycle();
+        // The frame length is known in advance, so the PPU can lag behind until then
+        let dots = self.bus.ppu.dots_until_frame();
+        self.run(self.bus.ppu.clock.master_cycles_until(dots));
+
+        self.end_frame();
+    }
+
+    /**
+     * Once a frame is rendered: movie desync check and rewind snapshot
+     */
+    pub (crate) fn end_frame (&mut self) {
+        self.bus.catch_up();
+
+        if let Some(movie) = &mut self.movie {
+            movie.end_frame(crate::ram_hash(&self.bus.wram));
+        }
+
+        if self.rewind.tick() {
+            let snapshot = self.save_state();
+            self.rewind.push(snapshot);
         }
     }
 
@@ -64,30 +148,224 @@ impl Emulator {
         let cycle = self.cpu.clock.cycles;
 
         while cycle == self.cpu.clock.cycles {
-            self.cycle();
+            self.tick();
         }
-    }
 
-    // pub fn set_rate (&mut self) {
-    //     self.clock.rate = crate::util::CLOCK_MASTER_PAL;
-    // }
+        self.bus.catch_up();
+    }
 
+    /**
+     * Ignored while a movie plays, since it drives the controllers
+     */
     pub fn update_controller (&mut self, player: usize, button: input::Button, pressed: bool) {
+        if matches!(self.movie, Some (MovieSession { playing: true, .. })) {
+            return;
+        }
+
         let state = self.bus.controllers[player].peek().unwrap();
         let state = if pressed { state | button as u8 } else { state & !(button as u8)};
 
         self.bus.controllers[player].update(state);
+        self.record_movie_input(0);
     }
 
     /**
      * https://wiki.nesdev.org/w/index.php/Init_code
+     * Ignored while a movie plays, since it records resets too
      */
     pub fn reset (&mut self) {
+        if matches!(self.movie, Some (MovieSession { playing: true, .. })) {
+            return;
+        }
+
+        self.soft_reset();
+        self.record_movie_input(MovieCommand::Reset as u8);
+    }
+
+    fn soft_reset (&mut self) {
+        self.bus.catch_up();
         self.cpu.reset();
         self.bus.apu.reset();
         self.clock.reset();
     }
 
+    /**
+     * Back to the state the emulator was created in, except for battery-backed RAM which keeps game saves like on hardware
+     */
+    fn power_cycle (&mut self) {
+        let sram = self.get_sram();
+        let power_on = std::mem::take(&mut self.power_on);
+
+        self.load_state(&power_on).expect("Power-on snapshot matches the running ROM");
+        self.power_on = power_on;
+        if let Some(sram) = sram {
+            self.set_sram(&sram).expect("Battery-backed RAM matches the running ROM");
+        }
+    }
+
+    /**
+     * Start recording input from now on, or from power-on after power cycling
+     */
+    pub fn movie_record (&mut self, from_power_on: bool) {
+        let (start, sram) = if from_power_on {
+            self.power_cycle();
+            (None, self.get_sram())
+        } else {
+            (Some(self.save_state()), None)
+        };
+
+        self.movie = Some(MovieSession::record(Movie::new(self.region(), self.bus.cartridge.checksum(), start, sram)));
+        self.record_movie_input(0);
+    }
+
+    /**
+     * Replay a movie from the state it starts from, battery-backed RAM included. Fails if it doesn't match the ROM or region, leaving the emulator untouched
+     */
+    pub fn movie_play (&mut self, movie: Movie) -> Result<(), MovieError> {
+        if matches!(movie.checksum, Some (checksum) if checksum != self.bus.cartridge.checksum()) {
+            return Err(MovieError::RomMismatch);
+        }
+        if movie.region != self.region() {
+            return Err(MovieError::RegionMismatch { movie: movie.region, emulator: self.region() });
+        }
+
+        if matches!(&movie.sram, Some (sram) if self.bus.cartridge.sram().map(<[u8]>::len) != Some(sram.len())) {
+            return Err(MovieError::InvalidFormat("battery-backed RAM doesn't fit the cartridge".to_string()));
+        }
+
+        match &movie.start {
+            Some (start) => self.load_state(start).map_err(|error| MovieError::InvalidFormat(error.to_string()))?,
+            None => self.power_cycle(),
+        }
+        if let Some(sram) = &movie.sram {
+            self.set_sram(sram).expect("Battery-backed RAM size was checked");
+        }
+
+        self.movie = Some(MovieSession::play(movie));
+
+        Ok(())
+    }
+
+    /**
+     * Stop recording or playing, returning the movie
+     */
+    pub fn movie_stop (&mut self) -> Option<Movie> {
+        self.movie.take().map(|session| session.movie)
+    }
+
+    /**
+     * Frames since the movie started, None when there is none
+     */
+    pub fn movie_frame (&self) -> Option<u32> {
+        self.movie.as_ref().map(|session| session.frame)
+    }
+
+    /**
+     * First frame whose RAM differed from the recording during playback
+     */
+    pub fn movie_desync (&self) -> Option<u32> {
+        self.movie.as_ref().and_then(|session| session.desync)
+    }
+
+    fn record_movie_input (&mut self, commands: u8) {
+        let players = [self.bus.controllers[0].peek().unwrap(), self.bus.controllers[1].peek().unwrap()];
+
+        if let Some(movie) = self.movie.as_mut().filter(|movie| !movie.playing) {
+            movie.record_input(players, commands);
+        }
+    }
+
+    pub (crate) fn play_movie_input (&mut self) {
+        while let Some(input) = self.movie.as_mut().and_then(|movie| movie.next_input()) {
+            if input.commands & MovieCommand::Power as u8 > 0 {
+                self.power_cycle();
+            }
+            if input.commands & MovieCommand::Reset as u8 > 0 {
+                self.soft_reset();
+            }
+
+            self.bus.controllers[0].update(input.players[0]);
+            self.bus.controllers[1].update(input.players[1]);
+        }
+    }
+
+    /**
+     * Snapshot of the whole machine, which can be restored as long as the same ROM is loaded
+     */
+    pub fn save_state (&self) -> Vec<u8> {
+        let cartridge = self.bus.cartridge.save_state();
+
+        crate::encode_state(self.bus.cartridge.checksum(), &(&self.cpu, &self.bus, &self.clock, &cartridge))
+    }
+
+    /**
+     * Fails with a StateError, in which case the emulator is left untouched
+     */
+    pub fn load_state (&mut self, state: &[u8]) -> Result<(), StateError> {
+        let (cpu, mut bus, clock, cartridge): (cpu::Cpu, bus::Bus, clock::Clock, cartridge::CartridgeState) = crate::decode_state(self.bus.cartridge.checksum(), state)?;
+
+        self.bus.cartridge.load_state(cartridge)?;
+        std::mem::swap(&mut bus.cartridge, &mut self.bus.cartridge);
+        // The audio sample rate belongs to the host, not to the machine
+        bus.apu.clock_sample.step = self.bus.apu.clock_sample.step;
+        // So do debugger watchpoints
+        bus.watch = std::mem::take(&mut self.bus.watch);
+
+        self.cpu = cpu;
+        self.bus = bus;
+        self.clock = clock;
+
+        Ok(())
+    }
+
+    /**
+     * Battery-backed PRG-RAM to persist game saves, None if the cartridge has none
+     */
+    pub fn get_sram (&self) -> Option<Vec<u8>> {
+        self.bus.cartridge.sram().map(|sram| sram.to_vec())
+    }
+
+    /**
+     * Restore game saves, before the game reads them (i.e. right after creating the emulator)
+     */
+    pub fn set_sram (&mut self, sram: &[u8]) -> Result<(), StateError> {
+        self.bus.cartridge.set_sram(sram)
+    }
+
+    /**
+     * Counter of writes changing battery-backed PRG-RAM, so that saves only need persisting when it changed
+     */
+    pub fn sram_writes (&self) -> u32 {
+        self.bus.cartridge.sram_writes
+    }
+
+    /**
+     * Capture a snapshot every `interval` frames, keeping the last `capacity` ones. Either being 0 disables rewinding
+     */
+    pub fn set_rewind (&mut self, interval: usize, capacity: usize) {
+        self.rewind = Rewind::new(interval, capacity);
+    }
+
+    /**
+     * Go back to the most recent snapshot, removing it from the buffer. Returns false when there is none left
+     */
+    pub fn rewind_step (&mut self) -> bool {
+        match self.rewind.pop() {
+            Some (snapshot) => {
+                self.load_state(&snapshot).expect("Rewind snapshots match the running ROM");
+                true
+            },
+            None => false,
+        }
+    }
+
+    /**
+     * Number of snapshots available to rewind
+     */
+    pub fn rewind_len (&self) -> usize {
+        self.rewind.len()
+    }
+
     pub fn read (&mut self, address: u16) -> u8 {
         self.bus.read(address)
     }
@@ -96,7 +374,24 @@ impl Emulator {
         self.bus.apu.flush()
     }
 
-    pub fn get_framebuffer (&self) -> js_sys::Uint8ClampedArray {
-        unsafe { js_sys::Uint8ClampedArray::view(&self.bus.ppu.framebuffer) }
+    /**
+     * 256x240 RGBA pixels
+     */
+    pub fn framebuffer (&self) -> &[u8] {
+        &self.bus.ppu.framebuffer
+    }
+
+    /**
+     * CPU registers, e.g. to inspect them once a debugger command stopped
+     */
+    pub fn cpu (&self) -> &cpu::Cpu {
+        &self.cpu
+    }
+
+    /**
+     * Read memory without side effects, None where reading would have some
+     */
+    pub fn peek (&self, address: u16) -> Option<u8> {
+        self.bus.peek(address)
     }
 }
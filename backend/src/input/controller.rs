@@ -1,6 +1,7 @@
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub enum Button {
     None    = 0b00000000,
     A       = 0b00000001,
@@ -13,7 +14,7 @@ pub enum Button {
     Right   = 0b10000000,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
 pub struct Controller {
     shift: u8,
     state: u8,
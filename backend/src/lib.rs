@@ -1,7 +1,6 @@
-use wee_alloc::WeeAlloc;
-
+#[cfg(feature = "wasm")]
 #[global_allocator]
-static GLOBAL: WeeAlloc = WeeAlloc::INIT;
+static GLOBAL: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
 pub mod apu;
 pub mod bus;
@@ -11,7 +10,20 @@ pub mod cpu;
 pub mod input;
 pub mod ppu;
 pub mod util;
+#[cfg(feature = "wasm")]
 pub mod debug;
 
+mod debugger;
 mod emulator;
+mod movie;
+mod rewind;
+mod state;
+#[cfg(feature = "wasm")]
+mod wasm;
+pub use debugger::*;
 pub use emulator::*;
+pub use movie::*;
+pub use rewind::*;
+pub use state::*;
+#[cfg(feature = "wasm")]
+pub use wasm::*;
@@ -0,0 +1,326 @@
+//! Input movies: controller changes and resets keyed by frame, replayed deterministically from the state the recording
+//! started from (power-on, or an embedded save state). A hash of RAM taken after every recorded frame detects desyncs.
+//!
+//! Native format: magic "NESM", version (u16 LE), then the bincode encoded Movie.
+//! FM2 format (FCEUX) http://fceux.com/web/FM2.html, limited to movies starting at power-on with standard controllers.
+
+use std::fmt;
+use crate::clock::Region;
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+pub const MOVIE_MAGIC: &[u8; 4] = b"NESM";
+pub const MOVIE_VERSION: u16 = 1; // Bump whenever Movie changes
+const MOVIE_HEADER_SIZE: usize = 6; // Magic, version (u16 LE)
+
+/**
+ * Same bits as FM2 commands
+ */
+pub enum MovieCommand {
+    Reset   = 0b0000_0001,
+    Power   = 0b0000_0010,
+}
+
+/**
+ * Reasons a movie can't be loaded, played or converted
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum MovieError {
+    InvalidFormat(String),
+    UnsupportedVersion(u16),
+    RomMismatch,
+    RegionMismatch { movie: Region, emulator: Region },
+    Fm2 { line: usize, reason: String },
+    NotFromPowerOn,
+}
+
+impl fmt::Display for MovieError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MovieError::InvalidFormat(reason)               => write!(f, "Not a movie: {}", reason),
+            MovieError::UnsupportedVersion(version)         => write!(f, "Unsupported movie version {} (expected {})", version, MOVIE_VERSION),
+            MovieError::RomMismatch                         => write!(f, "Movie was recorded with another ROM"),
+            MovieError::RegionMismatch { movie, emulator }  => write!(f, "Movie was recorded on {:?}, the emulator runs on {:?}", movie, emulator),
+            MovieError::Fm2 { line, reason }                => write!(f, "Invalid FM2 movie, line {}: {}", line, reason),
+            MovieError::NotFromPowerOn                      => write!(f, "FM2 movies can only start at power-on"),
+        }
+    }
+}
+
+impl std::error::Error for MovieError {}
+
+#[cfg(feature = "wasm")]
+impl From<MovieError> for JsValue {
+    fn from (error: MovieError) -> Self {
+        let js_error = js_sys::Error::new(&error.to_string());
+        js_error.set_name("MovieError");
+        js_error.into()
+    }
+}
+
+/**
+ * Controller states from a frame on, and commands run at its start
+ */
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MovieInput {
+    pub frame: u32,
+    pub players: [u8; 2],
+    pub commands: u8, // MovieCommand bits
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Movie {
+    pub region: Region,
+    pub checksum: Option<u32>, // ROM checksum, unknown for imported FM2 movies
+    pub start: Option<Vec<u8>>, // Save state the movie starts from, None for power-on
+    pub sram: Option<Vec<u8>>, // Battery-backed RAM at power-on, None when starting from a state or without a battery
+    pub length: u32, // Frames
+    pub inputs: Vec<MovieInput>,
+    pub hashes: Vec<u32>, // RAM hash after each frame, empty for imported FM2 movies
+}
+
+impl Movie {
+    pub fn new (region: Region, checksum: u32, start: Option<Vec<u8>>, sram: Option<Vec<u8>>) -> Self {
+        Self {
+            region,
+            checksum: Some(checksum),
+            start,
+            sram,
+            length: 0,
+            inputs: vec![],
+            hashes: vec![],
+        }
+    }
+
+    pub fn encode (&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(MOVIE_HEADER_SIZE);
+        data.extend_from_slice(MOVIE_MAGIC);
+        data.extend_from_slice(&MOVIE_VERSION.to_le_bytes());
+        bincode::serialize_into(&mut data, self).expect("Movie is serializable");
+        data
+    }
+
+    /**
+     * Decode a movie in the native or FM2 format
+     */
+    pub fn decode (data: &[u8]) -> Result<Self, MovieError> {
+        if data.starts_with(b"version") {
+            let text = std::str::from_utf8(data).map_err(|error| MovieError::InvalidFormat(error.to_string()))?;
+            return Movie::from_fm2(text);
+        }
+
+        if data.len() < MOVIE_HEADER_SIZE || &data[0..4] != MOVIE_MAGIC {
+            return Err(MovieError::InvalidFormat("invalid header constant".to_string()));
+        }
+
+        let version = u16::from_le_bytes([data[4], data[5]]);
+        if version != MOVIE_VERSION {
+            return Err(MovieError::UnsupportedVersion(version));
+        }
+
+        bincode::deserialize(&data[MOVIE_HEADER_SIZE..]).map_err(|error| MovieError::InvalidFormat(error.to_string()))
+    }
+
+    /**
+     * Export as FM2. The ROM checksum is left blank, since FM2 uses an MD5 hash which FCEUX only warns about.
+     * Battery-backed RAM isn't exported either, FCEUX clears it when starting a movie
+     */
+    pub fn to_fm2 (&self) -> Result<String, MovieError> {
+        if self.start.is_some() {
+            return Err(MovieError::NotFromPowerOn);
+        }
+
+        let mut fm2 = String::new();
+        fm2.push_str("version 3\n");
+        fm2.push_str("emuVersion 0\n");
+        fm2.push_str("rerecordCount 0\n");
+        fm2.push_str(&format!("palFlag {}\n", if self.region == Region::PAL { 1 } else { 0 }));
+        fm2.push_str("romFilename \n");
+        fm2.push_str("romChecksum base64:AAAAAAAAAAAAAAAAAAAAAA==\n");
+        fm2.push_str("guid 00000000-0000-0000-0000-000000000000\n");
+        fm2.push_str("fourscore 0\nmicrophone 0\nport0 1\nport1 1\nport2 0\nFDS 0\nNewPPU 0\n");
+
+        let mut inputs = self.inputs.iter().peekable();
+        let mut players = [0; 2];
+
+        for frame in 0 .. self.length {
+            let mut commands = 0;
+
+            while let Some(input) = inputs.next_if(|input| input.frame <= frame) {
+                players = input.players;
+                commands |= input.commands;
+            }
+
+            fm2.push_str(&format!("|{}|{}|{}||\n", commands, fm2_buttons(players[0]), fm2_buttons(players[1])));
+        }
+
+        Ok(fm2)
+    }
+
+    pub fn from_fm2 (fm2: &str) -> Result<Self, MovieError> {
+        let mut movie = Self {
+            region: Region::NTSC,
+            checksum: None,
+            start: None,
+            sram: None,
+            length: 0,
+            inputs: vec![],
+            hashes: vec![],
+        };
+        let mut players = [0; 2];
+
+        for (number, line) in fm2.lines().enumerate() {
+            let error = |reason: &str| MovieError::Fm2 { line: number + 1, reason: reason.to_string() };
+
+            if let Some(line) = line.strip_prefix('|') {
+                let fields: Vec<&str> = line.split('|').collect();
+                if fields.len() < 3 {
+                    return Err(error("expected commands and 2 controllers"));
+                }
+
+                let commands = fields[0].trim().parse::<u8>().map_err(|_| error("invalid commands"))?;
+                let frame_players = [
+                    parse_fm2_buttons(fields[1]).ok_or_else(|| error("invalid controller 1"))?,
+                    parse_fm2_buttons(fields[2]).ok_or_else(|| error("invalid controller 2"))?,
+                ];
+
+                if commands & !(MovieCommand::Reset as u8 | MovieCommand::Power as u8) != 0 {
+                    return Err(error("only reset and power commands are supported"));
+                }
+
+                if commands != 0 || frame_players != players {
+                    movie.inputs.push(MovieInput { frame: movie.length, players: frame_players, commands });
+                    players = frame_players;
+                }
+
+                movie.length += 1;
+                continue;
+            }
+
+            let mut parts = line.splitn(2, ' ');
+            let (key, value) = (parts.next().unwrap_or(""), parts.next().unwrap_or("").trim());
+
+            match key {
+                "version" if value != "3" => return Err(error("only version 3 is supported")),
+                "palFlag" => movie.region = if value == "1" { Region::PAL } else { Region::NTSC },
+                "savestate" => return Err(MovieError::NotFromPowerOn),
+                "fourscore" if value != "0" => return Err(error("Four Score is not supported")),
+                "port0" | "port1" if value != "0" && value != "1" => return Err(error("only standard controllers are supported")),
+                _ => {}, // Metadata such as comments or the ROM name
+            }
+        }
+
+        Ok(movie)
+    }
+}
+
+/**
+ * FM2 gamepad field "RLDUTSBA", which happens to be the controller state from its most significant bit
+ */
+fn fm2_buttons (state: u8) -> String {
+    "RLDUTSBA".chars().enumerate()
+        .map(|(index, button)| if state & (0b1000_0000 >> index) > 0 { button } else { '.' })
+        .collect()
+}
+
+fn parse_fm2_buttons (field: &str) -> Option<u8> {
+    if field.is_empty() {
+        return Some(0);
+    }
+    if field.len() != 8 {
+        return None;
+    }
+
+    Some(field.chars().enumerate().fold(0, |state, (index, button)| {
+        if button == '.' || button == ' ' { state } else { state | 0b1000_0000 >> index }
+    }))
+}
+
+/**
+ * FNV-1a hash of RAM, compared after each frame during playback
+ */
+pub fn ram_hash (ram: &[u8]) -> u32 {
+    ram.iter().fold(0x811C_9DC5, |hash, &byte| (hash ^ byte as u32).wrapping_mul(0x0100_0193))
+}
+
+/**
+ * Movie being recorded or played
+ */
+pub struct MovieSession {
+    pub movie: Movie,
+    pub playing: bool, // Recording otherwise
+    pub frame: u32, // Frames since the movie start
+    pub desync: Option<u32>, // First frame whose RAM hash differed from the recording
+    next: usize, // Next input to play
+}
+
+impl MovieSession {
+    pub fn record (movie: Movie) -> Self {
+        Self { movie, playing: false, frame: 0, desync: None, next: 0 }
+    }
+
+    pub fn play (movie: Movie) -> Self {
+        Self { movie, playing: true, frame: 0, desync: None, next: 0 }
+    }
+
+    /**
+     * Record controller states or commands, taking effect at the start of the next frame
+     */
+    pub fn record_input (&mut self, players: [u8; 2], commands: u8) {
+        match self.movie.inputs.last_mut() {
+            Some (input) if input.frame == self.frame => {
+                input.players = players;
+                input.commands |= commands;
+            },
+            _ => {
+                self.movie.inputs.push(MovieInput { frame: self.frame, players, commands });
+            },
+        }
+    }
+
+    /**
+     * Next input to play at the start of the current frame
+     */
+    pub fn next_input (&mut self) -> Option<MovieInput> {
+        let input = self.movie.inputs.get(self.next).filter(|input| input.frame <= self.frame)?.clone();
+        self.next += 1;
+        Some(input)
+    }
+
+    /**
+     * Record the RAM hash at the end of a frame, or check it against the recording
+     */
+    pub fn end_frame (&mut self, hash: u32) {
+        if self.playing {
+            let expected = self.movie.hashes.get(self.frame as usize);
+
+            if self.desync.is_none() && matches!(expected, Some (&expected) if expected != hash) {
+                self.desync = Some(self.frame);
+            }
+        } else {
+            self.movie.hashes.push(hash);
+            self.movie.length += 1;
+        }
+
+        self.frame += 1;
+    }
+}
+
+#[test]
+fn fm2 () {
+    let mut movie = Movie::new(Region::NTSC, 0, None, None);
+    movie.length = 4;
+    movie.inputs = vec![
+        MovieInput { frame: 0, players: [0, 0], commands: MovieCommand::Power as u8 },
+        MovieInput { frame: 2, players: [0b1000_1001, 0b0000_0010], commands: MovieCommand::Reset as u8 },
+    ];
+
+    let fm2 = movie.to_fm2().unwrap();
+    assert!(fm2.ends_with("|2|........|........||\n|0|........|........||\n|1|R...T..A|......B.||\n|0|R...T..A|......B.||\n"));
+
+    let imported = Movie::decode(fm2.as_bytes()).unwrap();
+    assert_eq!((imported.length, imported.inputs, imported.checksum), (4, movie.inputs, None));
+
+    assert_eq!(Movie::from_fm2("version 3\n|0|RLDU|........||"), Err(MovieError::Fm2 { line: 2, reason: "invalid controller 1".to_string() }));
+    assert_eq!(Movie::from_fm2("version 3\nsavestate base64:AAAA"), Err(MovieError::NotFromPowerOn));
+}
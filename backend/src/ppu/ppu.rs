@@ -1,5 +1,6 @@
 /**
  * 1 frame = 262 scanlines (1 pre-render, 240 visible, 1 post-render, 20 vblank).
+ * PAL frames have 312 scanlines (70 vblank), Dendy ones too (51 post-render, 20 vblank).
  * 1 scanline = 341 PPU clock cycles (dots)
  * 1 PPU cycle = 1/3 CPU cycle = 1 pixel
  * 1 VBlank = 20 scanlines
@@ -11,15 +12,16 @@
  * http://wiki.nesdev.com/w/index.php/PPU_nametables
  */
 
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 use crate::{
-    cpu::{Cpu, Interrupt},
+    bus::InterruptLines,
     cartridge::{Cartridge, Mirroring},
     ppu::PALETTE,
-    clock::ClockDivider,
+    clock::{ClockDivider, Region},
 };
 
-#[wasm_bindgen(js_name = PpuCtrlFlag)]
+#[cfg_attr(feature = "wasm", wasm_bindgen(js_name = PpuCtrlFlag))]
 pub enum CtrlFlag {
     Nametable       = 0b0000_0011,  // Nametable select
     Increment       = 0b0000_0100,  // VRAM address increment per read or write: -32 or +1
@@ -30,7 +32,7 @@ pub enum CtrlFlag {
     Nmi             = 0b1000_0000,  // Enable NMI on V-Blank
 }
 
-#[wasm_bindgen(js_name = PpuMaskFlag)]
+#[cfg_attr(feature = "wasm", wasm_bindgen(js_name = PpuMaskFlag))]
 pub enum MaskFlag {
     _Greyscale      = 0b0000_0001,  // Greyscale
     BackgroundLeft  = 0b0000_0010,  // Enable background on leftmost 8 pixels of screen
@@ -42,14 +44,14 @@ pub enum MaskFlag {
     _Blue           = 0b1000_0000,  // Emphasize blue
 }
 
-#[wasm_bindgen(js_name = PpuStatusFlag)]
+#[cfg_attr(feature = "wasm", wasm_bindgen(js_name = PpuStatusFlag))]
 pub enum StatusFlag {
     SpriteOverflow  = 0b0010_0000,  // Sprite overflow
     Hit             = 0b0100_0000,  // Sprite 0 hit
     VBlank          = 0b1000_0000,  // Vertical blank
 }
 
-#[wasm_bindgen]
+#[cfg_attr(feature = "wasm", wasm_bindgen)]
 pub enum SpriteAttribute {
     Palette         = 0b0000_0011,
     Zero            = 0b0001_1100, // Normally unused, serves to keep track of sprite zero
@@ -69,7 +71,7 @@ enum LoopyRegister {
 const NAMETABLE_X_MASK: u16 = 0b00000100_00000000;
 const NAMETABLE_Y_MASK: u16 = 0b00001000_00000000;
 
-#[derive(Clone)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 pub struct Ppu {
     pub ctrl: u8,
     pub mask: u8,
@@ -80,7 +82,11 @@ pub struct Ppu {
     pub scanline: u16,
     pub framebuffer: Vec<u8>, // 512x480 -> 256x240 (32x30 = 960 tiles)
     pub frame: usize,
+    pub cycles: usize, // PPU clocks since power on
+    pub region: Region,
     read_buffer: u8,
+    io_latch: u8, // Last value on the CPU data bus, read back from write-only registers (open bus, without decay)
+    skip_dot: bool, // Rendering was enabled as the pre-render scanline's dot 339 started, so an odd frame skips its first dot
     pub clock: ClockDivider,
 
     // Background
@@ -88,6 +94,7 @@ pub struct Ppu {
     tmp_address: u16, // loopy_t, top-left corner
     scroll_x_fine: u8, // Fine X offset (0-7)
     write_latch: bool,
+    vblank_suppressed: bool, // PPUSTATUS read just before vblank starts
     pattern_tile_id: u8,
     pattern_latch_hi: u8,
     pattern_latch_lo: u8,
@@ -111,7 +118,7 @@ pub struct Ppu {
 }
 
 impl Ppu {
-    pub fn new () -> Ppu {
+    pub fn new (region: Region) -> Ppu {
         Ppu {
             ctrl: 0,
             mask: 0,
@@ -122,12 +129,17 @@ impl Ppu {
             scanline: 0,
             framebuffer: vec![0; 256 * 240 * 4],
             frame: 0,
+            cycles: 0,
+            region,
             read_buffer: 0,
-            clock: ClockDivider::new(crate::clock::CLOCK_PPU_NTSC),
+            io_latch: 0,
+            skip_dot: false,
+            clock: ClockDivider::new(region.ppu_divider()),
             cur_address: 0,
             tmp_address: 0,
             scroll_x_fine: 0,
             write_latch: false,
+            vblank_suppressed: false,
             pattern_tile_id: 0,
             pattern_latch_hi: 0,
             pattern_latch_lo: 0,
@@ -149,10 +161,51 @@ impl Ppu {
         }
     }
 
-    pub fn tick (&mut self, time: f64, cartridge: &Cartridge, cpu: &mut Cpu) {
-        if self.clock.tick(time) {
-            self.cycle(cartridge, cpu);
+    /**
+     * Catch up with the CPU, running the dots owed for the given master clock cycles
+     */
+    pub fn run (&mut self, master_cycles: u32, cartridge: &mut Cartridge, interrupts: &mut InterruptLines) {
+        for _ in 0 .. self.clock.advance(master_cycles) {
+            self.cycle(cartridge);
+            interrupts.set_nmi(self.nmi());
         }
+
+        interrupts.set_nmi(self.nmi());
+    }
+
+    /**
+     * Dots that can run before one the CPU could observe without accessing PPU registers: NMI line changes at
+     * VBlank start and end, and any rendering fetch while the mapper watches the PPU bus (e.g. MMC3 scanline IRQ)
+     */
+    pub fn quiet_dots (&self, mapper_watches: bool) -> usize {
+        if mapper_watches && self.rendering() {
+            return 0;
+        }
+
+        let frame = self.region.scanlines() as usize * 341;
+        let position = self.scanline as usize * 341 + self.dot as usize;
+        let vblank_start = self.region.vblank_scanline() as usize * 341 + 1;
+        let vblank_end = frame - 341 + 1;
+
+        [vblank_start, vblank_end].iter()
+            // Events of the next frame come one dot earlier when the odd frame dot is skipped
+            .map(|&event| if event >= position { event - position } else { event + frame - position - 1 })
+            .min()
+            .unwrap()
+    }
+
+    /**
+     * Dots left to run before the frame count increments
+     */
+    pub fn dots_until_frame (&self) -> usize {
+        self.region.scanlines() as usize * 341 - (self.scanline as usize * 341 + self.dot as usize)
+    }
+
+    /**
+     * NMI is asserted while in vertical blank with NMI enabled, so toggling either can trigger it
+     */
+    pub fn nmi (&self) -> bool {
+        self.status & StatusFlag::VBlank as u8 > 0 && self.ctrl & CtrlFlag::Nmi as u8 > 0
     }
 
     /**
@@ -161,9 +214,12 @@ impl Ppu {
      * https://wiki.nesdev.com/w/index.php/PPU_OAM
      * https://wiki.nesdev.com/w/images/d/d1/Ntsc_timing.png
      */
-    pub fn cycle (&mut self, cartridge: &Cartridge, cpu: &mut Cpu) {
+    pub fn cycle (&mut self, cartridge: &mut Cartridge) {
+        let pre_render = self.region.scanlines() - 1;
+        let vblank = self.region.vblank_scanline();
+
         match self.scanline {
-            0 ..= 239 | 261 => {
+            scanline if scanline <= 239 || scanline == pre_render => {
                 // PPU busy fetching data, so PPU memory should not be accessed during this time (unless rendering is turned off - MaskFlags)
                 match self.dot {
                     0 => {}, // Idle
@@ -172,7 +228,7 @@ impl Ppu {
                         self.background_fetch(cartridge);
 
                         // Visible scanlines
-                        if self.scanline != 261 {
+                        if self.scanline != pre_render {
                             self.sprite_evaluation(cartridge);
 
                             // Draw pixel on visible dots
@@ -196,7 +252,7 @@ impl Ppu {
                                 (_, 257) => {
                                     self.x_reload();
                                 },
-                                (261, 280 ..= 304) => {
+                                (scanline, 280 ..= 304) if scanline == pre_render => {
                                     self.y_reload();
                                 },
                                 _ => {},
@@ -208,14 +264,12 @@ impl Ppu {
                     _ => {},
                 }
             },
-            240 => {}, // Post-render
-            241 => {
+            scanline if scanline == vblank => {
                 if self.dot == 1 {
-                    self.vblank_start(cpu);
+                    self.vblank_start();
                 }
             },
-            // The PPU makes no memory accesses during these scanlines, so PPU memory can be freely accessed by the program.
-            242 ..= 260 => {},
+            // Post-render and vblank: the PPU makes no memory accesses during these scanlines, so PPU memory can be freely accessed by the program.
             _ => {}
         }
 
@@ -223,18 +277,24 @@ impl Ppu {
     }
     
     fn cycle_increment (&mut self) {
+        self.cycles += 1;
         self.dot += 1;
     
+        // Rendering is checked for the odd frame skip before the last two (idle) dots of the pre-render scanline
+        if self.dot == 339 && self.scanline == self.region.scanlines() - 1 {
+            self.skip_dot = (self.mask & MaskFlag::Background as u8) > 0;
+        }
+
         if self.dot > 340 {
             self.dot = 0;
             self.scanline += 1;
     
-            if self.scanline > 261 {
+            if self.scanline >= self.region.scanlines() {
                 self.scanline = 0;
                 self.frame += 1;
     
-                // Skip first dot on odd frames to make them 1 PPU clock shorter than normal
-                if (self.mask & MaskFlag::Background as u8) > 0 && self.frame % 2 == 1 {
+                // Skip first dot on odd frames to make them 1 PPU clock shorter than normal (NTSC only)
+                if self.region == Region::NTSC && self.skip_dot && self.frame % 2 == 1 {
                     self.dot += 1;
                 }
             }            
@@ -256,7 +316,7 @@ impl Ppu {
     /**
      * Load data for next background tile. Each memory access takes 2 PPU cycles to complete, and 4 must be performed per tile
      */
-    fn background_fetch (&mut self, cartridge: &Cartridge) {
+    fn background_fetch (&mut self, cartridge: &mut Cartridge) {
         match self.dot % 8 {
             0 => {
                 self.x_increment();
@@ -270,7 +330,7 @@ impl Ppu {
                 self.palette_shift_hi = (self.palette_shift_hi & 0b11111111_00000000) | if (self.palette_latch & 0b10 as u8) != 0 { 0b11111111 } else { 0b00000000 };
                 self.palette_shift_lo = (self.palette_shift_lo & 0b11111111_00000000) | if (self.palette_latch & 0b01 as u8) != 0 { 0b11111111 } else { 0b00000000 };
 
-                self.pattern_tile_id = self.read_vram(
+                self.pattern_tile_id = self.fetch_vram(
                     cartridge,
                     0x2000
                     | (self.cur_address & (LoopyRegister::Nametable as u16 | LoopyRegister::CoarseX as u16 | LoopyRegister::CoarseY as u16))
@@ -281,7 +341,7 @@ impl Ppu {
             // See https://github.com/OneLoneCoder/olcNES/blob/master/Part%20%234%20-%20PPU%20Backgrounds/olc2C02.cpp#L802
             // and https://wiki.nesdev.com/w/index.php/PPU_scrolling#Tile_and_attribute_fetching for the computed address
             3 => {
-                let byte = self.read_vram(
+                let byte = self.fetch_vram(
                     cartridge,
                     0x23C0
                     | (self.cur_address & LoopyRegister::Nametable as u16)
@@ -298,27 +358,25 @@ impl Ppu {
             },
             // Pattern table tile low byte
             5 => {
-                self.pattern_latch_lo = self.read_vram(
-                    cartridge,
-                    if (self.ctrl & CtrlFlag::Background as u8) > 0 { 0x1000 } else { 0 }
-                    | (self.pattern_tile_id as u16 * 16)
-                    | ((self.cur_address & LoopyRegister::FineY as u16) >> 12)
-                );
+                self.pattern_latch_lo = self.fetch_vram(cartridge, self.background_pattern_address());
             },
             // Pattern table tile high byte
             7 => {
-                self.pattern_latch_hi = self.read_vram(
-                    cartridge,
-                    if (self.ctrl & CtrlFlag::Background as u8) > 0 { 0x1000 } else { 0 }
-                    | (self.pattern_tile_id as u16 * 16)
-                    | ((self.cur_address & LoopyRegister::FineY as u16) >> 12)
-                    + 8
-                );
+                self.pattern_latch_hi = self.fetch_vram(cartridge, self.background_pattern_address() + 8);
             },
             _ => {},
         }
     }
 
+    /**
+     * Address of the low byte of the next tile's row
+     */
+    fn background_pattern_address (&self) -> u16 {
+        let table = if (self.ctrl & CtrlFlag::Background as u8) > 0 { 0x1000 } else { 0 };
+
+        table | (self.pattern_tile_id as u16 * 16) | ((self.cur_address & LoopyRegister::FineY as u16) >> 12)
+    }
+
     /**
      * Prepare secondary OAM for next scanline
      * https://wiki.nesdev.com/w/index.php/PPU_sprite_evaluation
@@ -380,39 +438,17 @@ impl Ppu {
     /**
      * Sprite fetches. Garbage bytes are ignored
      */
-    fn sprite_fetch (&mut self, cartridge: &Cartridge) {
+    fn sprite_fetch (&mut self, cartridge: &mut Cartridge) {
+        let index = ((self.dot - 257) / 8) as usize;
+
         match (self.dot - 257) % 8 {
             cycle @ (4 | 6) => {
-                let index = ((self.dot - 257) / 8) as usize;
-                let (sprite_y, sprite_id, sprite_attributes, sprite_x) = (
-                    self.oam_secondary[index * 4 + 0] as u16,
-                    self.oam_secondary[index * 4 + 1] as u16,
-                    self.oam_secondary[index * 4 + 2],
-                    self.oam_secondary[index * 4 + 3],
-                );
-
-                if sprite_y != 0xFF {
-                    let row = (self.scanline - sprite_y) % 8; // Take into account 16px high tiles
-                    let address = if (self.ctrl & CtrlFlag::SpriteHeight as u8) > 0 {
-                        let half = (self.scanline - sprite_y) / 8; // Either top (0) or bottom (1) half
-
-                        (sprite_id & 0b0000_0001) * 0x1000
-                        | ((sprite_id & 0b1111_1110) + half) * 16
-                        | if (sprite_attributes & SpriteAttribute::FlipVertical as u8) > 0 { 7 - row } else { row }
-                    } else {
-                        (
-                            if (self.ctrl & CtrlFlag::Sprite as u8) > 0 { 0x1000 } else { 0 }
-                            | sprite_id * 16
-                            | if (sprite_attributes & SpriteAttribute::FlipVertical as u8) > 0 { 7 - row } else { row }
-                        )
-                    };
-
-                    let mut data = match cycle {
-                        4 => self.read_vram(cartridge, address),
-                        6 => self.read_vram(cartridge, address + 8),
-                        _ => unreachable!(),
-                    };
+                let address = self.sprite_pattern_address(index) + if cycle == 6 { 8 } else { 0 };
+                let mut data = self.fetch_vram(cartridge, address);
+                let (sprite_attributes, sprite_x) = (self.oam_secondary[index * 4 + 2], self.oam_secondary[index * 4 + 3]);
 
+                // Empty slots are fetched, but don't draw
+                if self.oam_secondary[index * 4] != 0xFF {
                     if (sprite_attributes & SpriteAttribute::FlipHorizontal as u8) > 0 {
                         data = data.reverse_bits();
                     }
@@ -431,6 +467,44 @@ impl Ppu {
         }
     }
 
+    /**
+     * Address of the low byte of a sprite's row on the current scanline
+     */
+    fn sprite_pattern_address (&self, index: usize) -> u16 {
+        let (sprite_y, sprite_id, sprite_attributes) = (
+            self.oam_secondary[index * 4 + 0] as u16,
+            self.oam_secondary[index * 4 + 1] as u16,
+            self.oam_secondary[index * 4 + 2],
+        );
+
+        if sprite_y == 0xFF {
+            // Empty slots still fetch tile $FF
+            return if (self.ctrl & CtrlFlag::SpriteHeight as u8) > 0 {
+                0x1FE0
+            } else if (self.ctrl & CtrlFlag::Sprite as u8) > 0 {
+                0x1FF0
+            } else {
+                0x0FF0
+            };
+        }
+
+        let row = (self.scanline - sprite_y) % 8; // Take into account 16px high tiles
+
+        if (self.ctrl & CtrlFlag::SpriteHeight as u8) > 0 {
+            let half = (self.scanline - sprite_y) / 8; // Either top (0) or bottom (1) half
+
+            (sprite_id & 0b0000_0001) * 0x1000
+            | ((sprite_id & 0b1111_1110) + half) * 16
+            | if (sprite_attributes & SpriteAttribute::FlipVertical as u8) > 0 { 7 - row } else { row }
+        } else {
+            (
+                if (self.ctrl & CtrlFlag::Sprite as u8) > 0 { 0x1000 } else { 0 }
+                | sprite_id * 16
+                | if (sprite_attributes & SpriteAttribute::FlipVertical as u8) > 0 { 7 - row } else { row }
+            )
+        }
+    }
+
     /**
      * Draw pixel at current location
      */
@@ -556,11 +630,11 @@ impl Ppu {
         self.cur_address = (self.cur_address & !mask) | (self.tmp_address & mask);
     }
 
-    fn vblank_start (&mut self, cpu: &mut Cpu) {
-        self.status |= StatusFlag::VBlank as u8;
-        if self.ctrl & (CtrlFlag::Nmi as u8) > 0 {
-            cpu.interrupt_request(Interrupt::NMI);
+    fn vblank_start (&mut self) {
+        if !self.vblank_suppressed {
+            self.status |= StatusFlag::VBlank as u8;
         }
+        self.vblank_suppressed = false;
     }
 
     fn vblank_end (&mut self) {
@@ -574,14 +648,22 @@ impl Ppu {
      * https://wiki.nesdev.com/w/index.php/PPU_scrolling
      * https://wiki.nesdev.com/w/index.php/PPU_registers
      */
-    pub fn read (&mut self, cartridge: &Cartridge, address: u16) -> u8 {
-        match (address % 8) + 0x2000 {
+    pub fn read (&mut self, cartridge: &mut Cartridge, interrupts: &mut InterruptLines, address: u16) -> u8 {
+        let data = match (address % 8) + 0x2000 {
             // PPUSTATUS
             0x2002 => {
-                // Residual data on bottom 5 bits
-                let status = (self.status & 0b1110_0000) | (self.read_buffer & 0b0001_1111);
+                // Open bus on bottom 5 bits
+                let status = (self.status & 0b1110_0000) | (self.io_latch & 0b0001_1111);
                 // Clear vblank bit on read
                 self.status &= !(StatusFlag::VBlank as u8);
+                // Reading one PPU clock before vblank starts keeps the flag (and NMI) from being set for this frame
+                if self.scanline == self.region.vblank_scanline() && self.dot == 1 {
+                    self.vblank_suppressed = true;
+                }
+                // Reading on the same or the next PPU clock returns the flag but still suppresses NMI
+                if self.scanline == self.region.vblank_scanline() && (2 ..= 3).contains(&self.dot) {
+                    interrupts.cancel_nmi();
+                }
                 self.write_latch = false;
                 status
             },
@@ -601,11 +683,16 @@ impl Ppu {
                 }
 
                 self.cur_address += if (self.ctrl & CtrlFlag::Increment as u8) > 0 { 32 } else { 1 };
+                self.drive_address(cartridge);
 
                 dummy
             },
-            _ => self.read_buffer
-        }
+            _ => self.io_latch
+        };
+
+        self.io_latch = data;
+
+        data
     }
 
     pub fn peek (&self, address: u16) -> Option<u8> {
@@ -622,11 +709,17 @@ impl Ppu {
      * https://wiki.nesdev.com/w/index.php/PPU_scrolling
      * https://wiki.nesdev.com/w/index.php/PPU_registers
      */
-    pub fn write (&mut self, cartridge: &mut Cartridge, address: u16, data: u8) {
+    pub fn write (&mut self, cartridge: &mut Cartridge, interrupts: &mut InterruptLines, address: u16, data: u8) {
+        self.io_latch = data;
+
         match (address % 8) + 0x2000 {
             // PPUCTRL
             0x2000 => {
                 self.ctrl = data;
+                // Disabling NMI on the same or the next PPU clock as vblank starts suppresses it
+                if self.ctrl & CtrlFlag::Nmi as u8 == 0 && self.scanline == self.region.vblank_scanline() && (2 ..= 3).contains(&self.dot) {
+                    interrupts.cancel_nmi();
+                }
                 self.tmp_address = (self.tmp_address & !(LoopyRegister::Nametable as u16)) | ((self.ctrl as u16 & CtrlFlag::Nametable as u16) << 10);
             },
             // PPUMASK
@@ -661,6 +754,7 @@ impl Ppu {
                     // Low byte
                     self.tmp_address = (self.tmp_address & 0b11111111_00000000) | (data as u16);
                     self.cur_address = self.tmp_address;
+                    self.drive_address(cartridge);
                 } else {
                     // High byte
                     self.tmp_address = (self.tmp_address & 0b00000000_11111111) | ((data as u16) << 8);
@@ -672,17 +766,44 @@ impl Ppu {
             0x2007 => {
                 self.write_vram(cartridge, self.cur_address, data);
                 self.cur_address += if (self.ctrl & CtrlFlag::Increment as u8) > 0 { 32 } else { 1 };
+                self.drive_address(cartridge);
             },
             _ => {}, // panic!("Invalid I/O write @ {:#x}", address),
         }
     }
 
+    fn rendering (&self) -> bool {
+        self.mask & (MaskFlag::Background as u8 | MaskFlag::Foreground as u8) > 0
+    }
+
+    /**
+     * Rendering memory access, visible to the mapper on the PPU bus
+     */
+    fn fetch_vram (&mut self, cartridge: &mut Cartridge, address: u16) -> u8 {
+        if self.rendering() {
+            cartridge.notify_ppu_address(address, self.cycles);
+        }
+
+        self.read_vram(cartridge, address)
+    }
+
+    /**
+     * Outside of rendering, the VRAM address register drives the PPU bus
+     */
+    fn drive_address (&mut self, cartridge: &mut Cartridge) {
+        if !self.rendering() {
+            cartridge.notify_ppu_address(self.cur_address % 0x4000, self.cycles);
+        }
+    }
+
     /**
      * Read memory
      * https://wiki.nesdev.com/w/index.php/PPU_memory_map
      */
     pub fn read_vram (&self, cartridge: &Cartridge, address: u16) -> u8 {
-        match address % 0x4000 {
+        let address = address % 0x4000; // 14-bit address space
+
+        match address {
             // Pattern tables in cartridge CHR ROM/RAM
             0x0000 ..= 0x1FFF => {
                 cartridge.read_chr(address)
@@ -705,7 +826,9 @@ impl Ppu {
      * https://wiki.nesdev.com/w/index.php/PPU_memory_map
      */
     fn write_vram (&mut self, cartridge: &mut Cartridge, address: u16, data: u8) {
-        match address % 0x4000 {
+        let address = address % 0x4000; // 14-bit address space
+
+        match address {
             // Pattern tables
             0x0000 ..= 0x1FFF => {
                 cartridge.write_chr(address, data);
@@ -720,7 +843,7 @@ impl Ppu {
             0x3F00 ..= 0x3FFF => {
                 // info!("Write Palette {:#x} <- {:#x}", address, data);
                 let mirrored = self.mirror_palette(address) as usize - 0x3F00;
-                self.palettes[mirrored] = data;
+                self.palettes[mirrored] = data & 0b0011_1111; // Colors are 6 bits
             },
             _ => panic!("Invalid write @ {:#x}", address),
         }
@@ -730,6 +853,7 @@ impl Ppu {
      * Copy bytes to OAM
      */
     pub fn write_oam (&mut self, data: u8) {
+        self.io_latch = data;
         self.oam[self.oam_address as usize] = data;
         self.oam_address = self.oam_address.wrapping_add(1);
     }
@@ -740,7 +864,8 @@ impl Ppu {
      */
     pub fn mirror (&self, cartridge: &Cartridge, address: u16) -> u16 {
         match cartridge.get_mirroring() {
-            Mirroring::OneScreenLower | Mirroring::OneScreenUpper => address % 0x400 + 0x2000,
+            Mirroring::OneScreenLower => address % 0x400 + 0x2000,
+            Mirroring::OneScreenUpper => address % 0x400 + 0x2400,
             Mirroring::Horizontal => match address {
                 0x2000 ..= 0x23FF => address,
                 0x2400 ..= 0x27FF => address - 0x400,
@@ -772,7 +897,7 @@ impl Ppu {
 
 #[test]
 fn palette () {
-    let ppu = Ppu::new();
+    let ppu = Ppu::new(Region::NTSC);
 
     assert_eq!(ppu.mirror_palette(0x3F00), 0x3F00);
     assert_eq!(ppu.mirror_palette(0x3F01), 0x3F01);
@@ -0,0 +1,166 @@
+//! Rewind buffer: save states captured every few frames in a bounded ring buffer.
+//! Only the most recent snapshot is kept whole, older ones are stored as deltas against the snapshot that follows them,
+//! which is small since most of the machine state doesn't change between captures.
+
+use std::collections::VecDeque;
+
+const DELTA_MIN_GAP: usize = 8; // Equal bytes needed to end a changed run, so that short gaps don't cost a run header
+
+pub struct Rewind {
+    pub interval: usize, // Frames between snapshots, 0 disables capture
+    pub capacity: usize, // Maximum number of snapshots
+    pub frames: usize, // Frames since the last snapshot
+    latest: Option<Vec<u8>>,
+    deltas: VecDeque<Vec<u8>>, // Oldest first, each one restoring the snapshot preceding the next
+}
+
+impl Rewind {
+    pub fn new (interval: usize, capacity: usize) -> Self {
+        Self {
+            interval,
+            capacity,
+            frames: 0,
+            latest: None,
+            deltas: VecDeque::new(),
+        }
+    }
+
+    /**
+     * Count a frame, returning whether a snapshot is due
+     */
+    pub fn tick (&mut self) -> bool {
+        if self.interval == 0 || self.capacity == 0 {
+            return false;
+        }
+
+        self.frames += 1;
+
+        if self.frames >= self.interval {
+            self.frames = 0;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn push (&mut self, snapshot: Vec<u8>) {
+        if let Some(previous) = self.latest.take() {
+            self.deltas.push_back(delta_encode(&snapshot, &previous));
+
+            while self.deltas.len() + 1 > self.capacity.max(1) {
+                self.deltas.pop_front();
+            }
+        }
+
+        self.latest = Some(snapshot);
+    }
+
+    /**
+     * Take the most recent snapshot out of the buffer
+     */
+    pub fn pop (&mut self) -> Option<Vec<u8>> {
+        let latest = self.latest.take()?;
+
+        self.latest = self.deltas.pop_back().map(|delta| delta_decode(&latest, &delta));
+        self.frames = 0;
+
+        Some(latest)
+    }
+
+    pub fn len (&self) -> usize {
+        if self.latest.is_some() { self.deltas.len() + 1 } else { 0 }
+    }
+
+    pub fn is_empty (&self) -> bool {
+        self.latest.is_none()
+    }
+}
+
+/**
+ * Encode target as the runs of bytes differing from base: target length (u32 LE), then for each run
+ * the number of unchanged bytes to skip and the run length (both u32 LE), followed by the run bytes
+ */
+fn delta_encode (base: &[u8], target: &[u8]) -> Vec<u8> {
+    let mut delta = (target.len() as u32).to_le_bytes().to_vec();
+    let differs = |index: usize| base.get(index) != Some(&target[index]);
+    let mut position = 0;
+    let mut index = 0;
+
+    while index < target.len() {
+        if !differs(index) {
+            index += 1;
+            continue;
+        }
+
+        // Extend the run until enough consecutive bytes are equal again
+        let start = index;
+        let mut end = index + 1;
+        let mut gap = 0;
+        index += 1;
+
+        while index < target.len() && gap < DELTA_MIN_GAP {
+            if differs(index) {
+                gap = 0;
+                end = index + 1;
+            } else {
+                gap += 1;
+            }
+            index += 1;
+        }
+
+        delta.extend_from_slice(&((start - position) as u32).to_le_bytes());
+        delta.extend_from_slice(&((end - start) as u32).to_le_bytes());
+        delta.extend_from_slice(&target[start..end]);
+        position = end;
+        index = end;
+    }
+
+    delta
+}
+
+fn delta_decode (base: &[u8], delta: &[u8]) -> Vec<u8> {
+    let read_u32 = |offset: usize| u32::from_le_bytes([delta[offset], delta[offset + 1], delta[offset + 2], delta[offset + 3]]) as usize;
+    let mut target = base.to_vec();
+    target.resize(read_u32(0), 0);
+
+    let mut offset = 4;
+    let mut position = 0;
+
+    while offset < delta.len() {
+        let start = position + read_u32(offset);
+        let length = read_u32(offset + 4);
+        offset += 8;
+
+        target[start..start + length].copy_from_slice(&delta[offset..offset + length]);
+        offset += length;
+        position = start + length;
+    }
+
+    target
+}
+
+#[test]
+fn delta () {
+    let base: Vec<u8> = (0..100).collect();
+    let mut target = base.clone();
+    target[3] = 0xFF;
+    target[5] = 0xFF;
+    target[60] = 0xFF;
+    target.truncate(90);
+    target.extend_from_slice(&[1, 2, 3]);
+
+    let delta = delta_encode(&base, &target);
+    assert!(delta.len() < target.len());
+    assert_eq!(delta_decode(&base, &delta), target);
+    assert_eq!(delta_decode(&target, &delta_encode(&target, &base)), base);
+
+    let mut rewind = Rewind::new(1, 3);
+    for snapshot in 0..5u8 {
+        rewind.push(vec![snapshot; 16]);
+    }
+    assert_eq!(rewind.len(), 3);
+    assert_eq!(rewind.pop(), Some(vec![4; 16]));
+    assert_eq!(rewind.pop(), Some(vec![3; 16]));
+    assert_eq!(rewind.pop(), Some(vec![2; 16]));
+    assert_eq!(rewind.pop(), None);
+}
@@ -0,0 +1,80 @@
+//! Save state format: a header identifying the format version and the ROM, followed by the bincode encoded machine state.
+//! PRG-ROM is never saved, so a state can only be loaded in an emulator running the same game.
+
+use std::fmt;
+use serde::{Serialize, de::DeserializeOwned};
+#[cfg(feature = "wasm")]
+use wasm_bindgen::prelude::*;
+
+pub const STATE_MAGIC: &[u8; 4] = b"NESS";
+pub const STATE_VERSION: u16 = 1; // Bump whenever a serialized component changes
+const STATE_HEADER_SIZE: usize = 10; // Magic, version (u16 LE), ROM checksum (u32 LE)
+
+/**
+ * Reasons a save state or battery-backed RAM can't be loaded
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum StateError {
+    InvalidMagic,
+    UnsupportedVersion(u16),
+    RomMismatch,
+    Corrupted(String),
+    NoBattery,
+    SramSize { expected: usize, actual: usize },
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateError::InvalidMagic                  => write!(f, "Not a save state (invalid header constant)"),
+            StateError::UnsupportedVersion(version)   => write!(f, "Unsupported save state version {} (expected {})", version, STATE_VERSION),
+            StateError::RomMismatch                   => write!(f, "Save state was made with another ROM"),
+            StateError::Corrupted(reason)             => write!(f, "Corrupted save state: {}", reason),
+            StateError::NoBattery                     => write!(f, "Cartridge has no battery-backed RAM"),
+            StateError::SramSize { expected, actual } => write!(f, "Invalid battery-backed RAM size: expected {} bytes, found {}", expected, actual),
+        }
+    }
+}
+
+impl std::error::Error for StateError {}
+
+impl From<bincode::Error> for StateError {
+    fn from (error: bincode::Error) -> Self {
+        StateError::Corrupted(error.to_string())
+    }
+}
+
+#[cfg(feature = "wasm")]
+impl From<StateError> for JsValue {
+    fn from (error: StateError) -> Self {
+        let js_error = js_sys::Error::new(&error.to_string());
+        js_error.set_name("StateError");
+        js_error.into()
+    }
+}
+
+pub fn encode_state<T: Serialize> (checksum: u32, state: &T) -> Vec<u8> {
+    let mut data = Vec::with_capacity(STATE_HEADER_SIZE);
+    data.extend_from_slice(STATE_MAGIC);
+    data.extend_from_slice(&STATE_VERSION.to_le_bytes());
+    data.extend_from_slice(&checksum.to_le_bytes());
+    bincode::serialize_into(&mut data, state).expect("Machine state is serializable");
+    data
+}
+
+pub fn decode_state<T: DeserializeOwned> (checksum: u32, data: &[u8]) -> Result<T, StateError> {
+    if data.len() < STATE_HEADER_SIZE || &data[0..4] != STATE_MAGIC {
+        return Err(StateError::InvalidMagic);
+    }
+
+    let version = u16::from_le_bytes([data[4], data[5]]);
+    if version != STATE_VERSION {
+        return Err(StateError::UnsupportedVersion(version));
+    }
+
+    if u32::from_le_bytes([data[6], data[7], data[8], data[9]]) != checksum {
+        return Err(StateError::RomMismatch);
+    }
+
+    Ok(bincode::deserialize(&data[STATE_HEADER_SIZE..])?)
+}
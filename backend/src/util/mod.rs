@@ -1,7 +1,11 @@
+#[cfg(feature = "wasm")]
 mod log;
+#[cfg(feature = "wasm")]
 mod panic;
 mod tilemap;
 
+#[cfg(feature = "wasm")]
 pub use self::log::*;
+#[cfg(feature = "wasm")]
 pub use self::panic::*;
 pub use self::tilemap::*;
@@ -0,0 +1,158 @@
+//! JS bindings: the emulator exposed as the `Emulator` class, forwarding to the Rust API.
+//! Debug getters are bound in the debug module.
+
+use std::ops::{Deref, DerefMut};
+use wasm_bindgen::prelude::*;
+use crate::{cartridge, clock, input, Emulator, Movie, MovieError, StateError};
+
+#[wasm_bindgen(js_name = Emulator)]
+pub struct WasmEmulator {
+    pub (crate) emulator: Emulator,
+}
+
+impl Deref for WasmEmulator {
+    type Target = Emulator;
+
+    fn deref (&self) -> &Emulator {
+        &self.emulator
+    }
+}
+
+impl DerefMut for WasmEmulator {
+    fn deref_mut (&mut self) -> &mut Emulator {
+        &mut self.emulator
+    }
+}
+
+#[wasm_bindgen(js_class = Emulator)]
+impl WasmEmulator {
+    /**
+     * Throws a CartridgeError when the ROM can't be loaded
+     */
+    pub fn new (rom: Vec<u8>, sample_rate: f64) -> Result<WasmEmulator, cartridge::CartridgeError> {
+        Ok(Self { emulator: Emulator::new(rom, sample_rate)? })
+    }
+
+    pub fn with_region (rom: Vec<u8>, sample_rate: f64, region: clock::Region) -> Result<WasmEmulator, cartridge::CartridgeError> {
+        Ok(Self { emulator: Emulator::with_region(rom, sample_rate, region)? })
+    }
+
+    pub fn region (&self) -> clock::Region {
+        self.emulator.region()
+    }
+
+    pub fn cycle (&mut self) {
+        self.emulator.cycle();
+    }
+
+    pub fn cycle_until_frame (&mut self) {
+        self.emulator.cycle_until_frame();
+    }
+
+    pub fn cycle_until_scanline (&mut self) {
+        self.emulator.cycle_until_scanline();
+    }
+
+    pub fn cycle_until_ppu (&mut self) {
+        self.emulator.cycle_until_ppu();
+    }
+
+    pub fn cycle_until_cpu (&mut self) {
+        self.emulator.cycle_until_cpu();
+    }
+
+    pub fn update_controller (&mut self, player: usize, button: input::Button, pressed: bool) {
+        self.emulator.update_controller(player, button, pressed);
+    }
+
+    pub fn reset (&mut self) {
+        self.emulator.reset();
+    }
+
+    pub fn save_state (&self) -> Vec<u8> {
+        self.emulator.save_state()
+    }
+
+    /**
+     * Throws a StateError, in which case the emulator is left untouched
+     */
+    pub fn load_state (&mut self, state: &[u8]) -> Result<(), StateError> {
+        self.emulator.load_state(state)
+    }
+
+    /**
+     * Undefined if the cartridge has no battery-backed RAM
+     */
+    pub fn get_sram (&self) -> Option<Vec<u8>> {
+        self.emulator.get_sram()
+    }
+
+    pub fn set_sram (&mut self, sram: &[u8]) -> Result<(), StateError> {
+        self.emulator.set_sram(sram)
+    }
+
+    pub fn sram_writes (&self) -> u32 {
+        self.emulator.sram_writes()
+    }
+
+    pub fn set_rewind (&mut self, interval: usize, capacity: usize) {
+        self.emulator.set_rewind(interval, capacity);
+    }
+
+    pub fn rewind_step (&mut self) -> bool {
+        self.emulator.rewind_step()
+    }
+
+    pub fn rewind_len (&self) -> usize {
+        self.emulator.rewind_len()
+    }
+
+    pub fn movie_record (&mut self, from_power_on: bool) {
+        self.emulator.movie_record(from_power_on);
+    }
+
+    /**
+     * Throws a MovieError when the movie, native or FM2, can't be decoded or doesn't match the ROM
+     */
+    pub fn movie_play (&mut self, movie: &[u8]) -> Result<(), MovieError> {
+        self.emulator.movie_play(Movie::decode(movie)?)
+    }
+
+    /**
+     * The movie in the native format, undefined if there was none
+     */
+    pub fn movie_stop (&mut self) -> Option<Vec<u8>> {
+        self.emulator.movie_stop().map(|movie| movie.encode())
+    }
+
+    pub fn movie_frame (&self) -> Option<u32> {
+        self.emulator.movie_frame()
+    }
+
+    pub fn movie_desync (&self) -> Option<u32> {
+        self.emulator.movie_desync()
+    }
+
+    pub fn read (&mut self, address: u16) -> u8 {
+        self.emulator.read(address)
+    }
+
+    pub fn get_audio (&mut self) -> Vec<f32> {
+        self.emulator.get_audio()
+    }
+
+    /**
+     * View into wasm memory, only valid until the next call into the emulator
+     */
+    pub fn get_framebuffer (&self) -> js_sys::Uint8ClampedArray {
+        unsafe { js_sys::Uint8ClampedArray::view(self.emulator.framebuffer()) }
+    }
+}
+
+/**
+ * Convert a movie in the native format to FM2, throwing a MovieError if it doesn't start at power-on
+ */
+#[wasm_bindgen]
+pub fn movie_to_fm2 (movie: &[u8]) -> Result<String, MovieError> {
+    Movie::decode(movie)?.to_fm2()
+}
@@ -1,10 +1,10 @@
-use wasm_nes::Emulator;
+use wasm_nes::{BreakpointKind, Emulator, StopReason};
 
 macro_rules! run {
-    ($path:expr) => {
+    (bytes $rom:expr) => {
         use colored::Colorize;
 
-        match crate::run(include_bytes!($path)) {
+        match crate::run($rom) {
             Ok (message) => {
                 println!("{}", message.green());
             },
@@ -14,6 +14,22 @@ macro_rules! run {
             },
         }
     };
+    (console $path:expr) => {
+        use colored::Colorize;
+
+        match crate::run_console(include_bytes!($path)) {
+            Ok (message) => {
+                println!("{}", message.green());
+            },
+            Err (message) => {
+                println!("{}", message.red());
+                panic!("Failed");
+            },
+        }
+    };
+    ($path:expr) => {
+        run!(bytes include_bytes!($path));
+    };
 }
 
 fn read_string (nes: &mut Emulator) -> String {
@@ -33,7 +49,7 @@ fn read_string (nes: &mut Emulator) -> String {
 }
 
 fn run (rom: &[u8]) -> Result<String, (u8, String)> {
-    let mut nes = Emulator::new(rom.to_vec(), 48_000.0);
+    let mut nes = Emulator::new(rom.to_vec(), 48_000.0).unwrap();
 
     loop {
         nes.cycle_until_frame();
@@ -56,6 +72,140 @@ fn run (rom: &[u8]) -> Result<String, (u8, String)> {
     }
 }
 
+/**
+ * Older tests only report on screen: read the text they write to the nametables through PPUDATA
+ */
+fn run_console (rom: &[u8]) -> Result<String, String> {
+    let mut nes = Emulator::new(rom.to_vec(), 48_000.0).unwrap();
+    let mut screen: Vec<u8> = vec![];
+
+    nes.add_breakpoint(BreakpointKind::PpuRegister { register: 7, read: false, write: true });
+
+    loop {
+        match nes.run_until_break() {
+            StopReason::Watchpoint { access, .. } => {
+                screen.push(access.data);
+            },
+            StopReason::Frame => {
+                let text = String::from_utf8_lossy(&screen)
+                    .split(|character: char| !character.is_ascii_graphic())
+                    .filter(|word| !word.is_empty())
+                    .collect::<Vec<&str>>()
+                    .join(" ");
+
+                if text.contains("Passed") {
+                    return Ok(text)
+                } else if text.contains("Failed") || text.contains("Error") {
+                    return Err(text)
+                }
+            },
+            reason => panic!("Unexpected stop: {:?}", reason),
+        }
+    }
+}
+
+/**
+ * What Holy Mapperel displays, from the variables it keeps in zero page since it draws its text in CHR
+ */
+#[derive(Debug, PartialEq)]
+struct Mapperel {
+    mapper: u8,
+    prg_kib: usize,
+    chr_rom: bool,
+    chr_kib: usize,
+    chr_ok: bool,
+    detail: u16, // WRAM, PRG-ROM, IRQ and CHR digits, 0 when normal
+}
+
+fn run_mapperel (rom: &[u8]) -> Mapperel {
+    let mut nes = Emulator::new(rom.to_vec(), 48_000.0).unwrap();
+
+    // Results are shown once the CHR and WRAM tests stop buzzing
+    for _ in 0..300 {
+        nes.cycle_until_frame();
+    }
+
+    Mapperel {
+        mapper: nes.read(0x15),
+        prg_kib: (nes.read(0x17) as usize + 1) * 4,
+        chr_rom: nes.read(0x18) > 0,
+        chr_kib: (nes.read(0x19) as usize + 1) * 8,
+        chr_ok: nes.read(0x1A) == 0,
+        detail: u16::from_be_bytes([nes.read(0x1F), nes.read(0x20)]),
+    }
+}
+
+mod cpu {
+    #[test]
+    fn cpu_dummy_reads () {
+       run!(console "roms/cpu/cpu_dummy_reads/cpu_dummy_reads.nes");
+    }
+
+    mod cpu_dummy_writes {
+        #[test]
+        fn oam () {
+           run!("roms/cpu/cpu_dummy_writes/cpu_dummy_writes_oam.nes");
+        }
+
+        #[test]
+        fn ppumem () {
+           run!("roms/cpu/cpu_dummy_writes/cpu_dummy_writes_ppumem.nes");
+        }
+    }
+
+    #[test]
+    fn instr_misc () {
+       run!("roms/cpu/instr_misc/instr_misc.nes");
+    }
+
+    mod instr_test_v5 {
+        #[test]
+        fn immediate () {
+           run!("roms/cpu/instr_test_v5/03-immediate.nes");
+        }
+
+        #[test]
+        fn zero_page () {
+           run!("roms/cpu/instr_test_v5/04-zero_page.nes");
+        }
+
+        #[test]
+        fn zp_xy () {
+           run!("roms/cpu/instr_test_v5/05-zp_xy.nes");
+        }
+
+        #[test]
+        fn absolute () {
+           run!("roms/cpu/instr_test_v5/06-absolute.nes");
+        }
+
+        #[test]
+        fn abs_xy () {
+           run!("roms/cpu/instr_test_v5/07-abs_xy.nes");
+        }
+
+        #[test]
+        fn ind_x () {
+           run!("roms/cpu/instr_test_v5/08-ind_x.nes");
+        }
+
+        #[test]
+        fn ind_y () {
+           run!("roms/cpu/instr_test_v5/09-ind_y.nes");
+        }
+
+        #[test]
+        fn brk () {
+           run!("roms/cpu/instr_test_v5/15-brk.nes");
+        }
+
+        #[test]
+        fn special () {
+           run!("roms/cpu/instr_test_v5/16-special.nes");
+        }
+    }
+}
+
 mod ppu {
     mod ppu_vbl_nmi {
         #[test]
@@ -136,3 +286,134 @@ mod ppu {
         }
     }
 }
+
+mod apu {
+    mod apu_mixer {
+        #[test]
+        fn dmc () {
+           run!("roms/apu/apu_mixer/dmc.nes");
+        }
+
+        #[test]
+        fn noise () {
+           run!("roms/apu/apu_mixer/noise.nes");
+        }
+
+        #[test]
+        fn triangle () {
+           run!("roms/apu/apu_mixer/triangle.nes");
+        }
+    }
+
+    mod apu_test {
+        #[test]
+        fn len_table () {
+           run!("roms/apu/apu_test/2-len_table.nes");
+        }
+
+        #[test]
+        fn irq_flag () {
+           run!("roms/apu/apu_test/3-irq_flag.nes");
+        }
+
+        #[test]
+        fn jitter () {
+           run!("roms/apu/apu_test/4-jitter.nes");
+        }
+
+        #[test]
+        fn len_timing () {
+           run!("roms/apu/apu_test/5-len_timing.nes");
+        }
+
+        #[test]
+        fn irq_flag_timing () {
+           run!("roms/apu/apu_test/6-irq_flag_timing.nes");
+        }
+
+        #[test]
+        fn dmc_basics () {
+           run!("roms/apu/apu_test/7-dmc_basics.nes");
+        }
+
+        #[test]
+        fn dmc_rates () {
+           run!("roms/apu/apu_test/8-dmc_rates.nes");
+        }
+    }
+}
+
+mod mapper {
+    mod mmc3_test {
+        #[test]
+        fn clocking () {
+           run!("roms/mapper/mmc3_test/1-clocking.nes");
+        }
+
+        #[test]
+        fn details () {
+           run!("roms/mapper/mmc3_test/2-details.nes");
+        }
+
+        #[test]
+        fn a12_clocking () {
+           run!("roms/mapper/mmc3_test/3-A12_clocking.nes");
+        }
+
+        #[test]
+        fn scanline_timing () {
+           run!("roms/mapper/mmc3_test/4-scanline_timing.nes");
+        }
+
+        #[test]
+        fn mmc3 () {
+           run!("roms/mapper/mmc3_test/5-MMC3.nes");
+        }
+
+        #[test]
+        fn mmc6 () {
+           // Old IRQ behavior, which the iNES header can't tell: make it a NES 2.0 header with the MMC6 submapper
+           let mut rom = include_bytes!("roms/mapper/mmc3_test/6-MMC6.nes").to_vec();
+           rom[7] = (rom[7] & 0b1111_0011) | 0b0000_1000;
+           rom[8] = 1 << 4;
+
+           run!(bytes &rom);
+        }
+    }
+
+    mod holy_mapperel {
+        use crate::{run_mapperel, Mapperel};
+
+        #[test]
+        fn uxrom () {
+            assert_eq!(
+                run_mapperel(include_bytes!("roms/mapper/holy-mapperel-bin-0.02/testroms/M2_P128K_V.nes")),
+                Mapperel { mapper: 2, prg_kib: 128, chr_rom: false, chr_kib: 8, chr_ok: true, detail: 0 },
+            );
+            assert_eq!(
+                run_mapperel(include_bytes!("roms/mapper/holy-mapperel-bin-0.02/testroms/M2_P128K_CR8K_V.nes")),
+                Mapperel { mapper: 2, prg_kib: 128, chr_rom: false, chr_kib: 8, chr_ok: true, detail: 0 },
+            );
+        }
+
+        #[test]
+        fn axrom () {
+            assert_eq!(
+                run_mapperel(include_bytes!("roms/mapper/holy-mapperel-bin-0.02/testroms/M7_P128K.nes")),
+                Mapperel { mapper: 7, prg_kib: 128, chr_rom: false, chr_kib: 8, chr_ok: true, detail: 0 },
+            );
+            assert_eq!(
+                run_mapperel(include_bytes!("roms/mapper/holy-mapperel-bin-0.02/testroms/M7_P128K_CR8K.nes")),
+                Mapperel { mapper: 7, prg_kib: 128, chr_rom: false, chr_kib: 8, chr_ok: true, detail: 0 },
+            );
+        }
+
+        #[test]
+        fn gxrom () {
+            assert_eq!(
+                run_mapperel(include_bytes!("roms/mapper/holy-mapperel-bin-0.02/testroms/M66_P64K_C16K_V.nes")),
+                Mapperel { mapper: 66, prg_kib: 64, chr_rom: true, chr_kib: 16, chr_ok: true, detail: 0 },
+            );
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use std::{cell::RefCell, rc::Rc};
+use wasm_nes::{cpu::Interrupt, AddressSpace, BreakpointKind, BusAccess, Emulator, Expression, StopReason, SymbolError, TraceFormat};
+
+/**
+ * NROM program calling a subroutine in a loop, with NMI enabled
+ */
+fn program () -> Emulator {
+    let mut prg = vec![0xEA; 0x4000];
+    let code: &[(usize, &[u8])] = &[
+        (0x0000, &[0xA2, 0xFF, 0x9A, 0xA9, 0x80, 0x8D, 0x00, 0x20]), // LDX #$FF, TXS, LDA #$80, STA $2000
+        (0x0008, &[0x20, 0x20, 0xC0, 0xEE, 0x00, 0x03, 0x4C, 0x08, 0xC0]), // JSR $C020, INC $0300, JMP $C008
+        (0x0020, &[0xA9, 0x40, 0x8D, 0x01, 0x0B, 0xAD, 0x02, 0x20, 0x60]), // LDA #$40, STA $0B01 (mirror of $0301), LDA $2002, RTS
+        (0x0030, &[0x40]), // RTI
+        (0x0040, &[0xD0, 0xFC, 0x6C, 0xFC, 0xFF]), // Never runs: BNE $C03E, JMP ($FFFC)
+        (0x3FFA, &[0x30, 0xC0, 0x00, 0xC0, 0x30, 0xC0]), // Vectors
+    ];
+    for (offset, bytes) in code {
+        prg[*offset .. offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    let mut rom = vec![0x4E, 0x45, 0x53, 0x1A, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    rom.extend(prg);
+    rom.extend(vec![0; 0x2000]);
+
+    Emulator::new(rom, 48_000.0).unwrap()
+}
+
+#[test]
+fn breakpoints () {
+    let mut nes = program();
+    let id = nes.add_breakpoint(BreakpointKind::Execute { address: 0xC020, bank: None });
+
+    assert_eq!(nes.run_until_break(), StopReason::Breakpoint { id, address: 0xC020 });
+    assert_eq!(nes.cpu().pc, 0xC020);
+    assert_eq!(nes.run_until_break(), StopReason::Breakpoint { id, address: 0xC020 });
+    assert_eq!(nes.breakpoints()[0].hits, 2);
+
+    // Another bank is never mapped there
+    let other = nes.add_breakpoint(BreakpointKind::Execute { address: 0xC00B, bank: Some(1) });
+    assert!(nes.remove_breakpoint(id));
+    assert!(!nes.remove_breakpoint(id));
+    assert_eq!(nes.run_until_break(), StopReason::Frame);
+    assert_eq!(nes.breakpoints()[0].id, other);
+}
+
+#[test]
+fn steps () {
+    let mut nes = program();
+    nes.add_breakpoint(BreakpointKind::Execute { address: 0xC008, bank: None });
+    nes.run_until_break();
+    nes.clear_breakpoints();
+
+    assert_eq!(nes.step_over(), StopReason::Step);
+    assert_eq!(nes.cpu().pc, 0xC00B);
+    assert_eq!(nes.peek(0x0301), Some(0x40));
+
+    assert_eq!((nes.step_instruction(), nes.step_instruction(), nes.step_instruction()), (StopReason::Step, StopReason::Step, StopReason::Step));
+    assert_eq!(nes.cpu().pc, 0xC020);
+
+    assert_eq!(nes.step_instruction(), StopReason::Step);
+    assert_eq!(nes.step_out(), StopReason::Step);
+    assert_eq!(nes.cpu().pc, 0xC00B);
+}
+
+#[test]
+fn watchpoints () {
+    let mut nes = program();
+    // Mirrors are matched through their canonical address
+    let id = nes.add_breakpoint(BreakpointKind::Access { start: 0x0300, end: 0x0301, read: false, write: true });
+
+    assert_eq!(nes.run_until_break(), StopReason::Watchpoint { id, pc: 0xC022, access: BusAccess { address: 0x0B01, data: 0x40, write: true } });
+    assert_eq!(nes.cpu().pc, 0xC025);
+    assert_eq!(nes.run_until_break(), StopReason::Watchpoint { id, pc: 0xC00B, access: BusAccess { address: 0x0300, data: 0x00, write: true } });
+    nes.remove_breakpoint(id);
+
+    let id = nes.add_breakpoint(BreakpointKind::PpuRegister { register: 2, read: true, write: false });
+    assert!(matches!(nes.run_until_break(), StopReason::Watchpoint { id: hit, pc: 0xC025, access: BusAccess { address: 0x2002, write: false, .. } } if hit == id));
+}
+
+#[test]
+fn interrupts () {
+    let mut nes = program();
+    let id = nes.add_breakpoint(BreakpointKind::Interrupt(Interrupt::NMI));
+
+    assert_eq!(nes.run_until_break(), StopReason::Interrupt { id, interrupt: Interrupt::NMI });
+    assert_eq!(nes.cpu().pc, 0xC030);
+    assert_eq!(nes.step_out(), StopReason::Step);
+    assert!((0xC008 ..= 0xC028).contains(&nes.cpu().pc));
+}
+
+#[test]
+fn conditions () {
+    let mut nes = program();
+    let id = nes.add_breakpoint(BreakpointKind::Execute { address: 0xC020, bank: None });
+
+    // $0300 counts completed calls
+    assert!(nes.set_breakpoint_condition(id, Some(Expression::parse("hits == 3 && [$0300] == 2 && scanline < 240").unwrap())));
+    assert_eq!(nes.run_until_break(), StopReason::Breakpoint { id, address: 0xC020 });
+    assert_eq!((nes.breakpoints()[0].hits, nes.peek(0x0300)), (3, Some(2)));
+
+    let id = nes.add_breakpoint(BreakpointKind::Access { start: 0x0301, end: 0x0301, read: false, write: true });
+    nes.set_breakpoint_condition(id, Some(Expression::parse("A != $40").unwrap()));
+    assert_eq!(nes.run_until_break(), StopReason::Frame);
+    assert!(nes.breakpoints()[1].hits > 0);
+
+    assert!(!nes.set_breakpoint_condition(100, None));
+}
+
+#[test]
+fn trace () {
+    let mut nes = program();
+    nes.add_breakpoint(BreakpointKind::Execute { address: 0xC020, bank: None });
+    nes.trace_to_buffer(TraceFormat::Nestest, 3);
+    nes.run_until_break();
+
+    // Logged before running, up to the breakpoint. Enabling NMI during VBlank (set at power-on) raises one right away
+    let trace = nes.take_trace();
+    assert_eq!(trace.iter().map(|line| &line[..40]).collect::<Vec<_>>(), vec![
+        "C008  20 20 C0  JSR $C020               ",
+        "C030  40        RTI                     ",
+        "C020  A9 40     LDA #$40                ",
+    ]);
+    assert!(trace[2].ends_with("A:80 X:FF Y:00 P:A4 SP:FD PPU:  0,108 CYC:36"));
+    assert!(nes.take_trace().is_empty());
+
+    let lines = Rc::new(RefCell::new(vec![]));
+    let sink = lines.clone();
+    nes.trace_to_callback(TraceFormat::Mesen, move |line| sink.borrow_mut().push(line.to_string()));
+    nes.step_instruction();
+    nes.stop_trace();
+    nes.step_instruction();
+
+    assert_eq!(lines.borrow().len(), 1);
+    assert!(lines.borrow()[0].starts_with("C022  8D 01 0B  STA $0B01 = 00"));
+    assert!(lines.borrow()[0].ends_with("SP:FD CYC:114 SL:0   CPU Cycle:38"));
+}
+
+#[test]
+fn disassembly () {
+    let mut nes = program();
+    let text = |nes: &Emulator, space, address| nes.disassemble(space, address).map(|instruction| instruction.to_string());
+
+    assert_eq!(text(&nes, AddressSpace::Cpu, 0xC005), Some("STA PPUCTRL".to_string()));
+    assert_eq!(text(&nes, AddressSpace::Cpu, 0xC040), Some("BNE $C03E".to_string()));
+    assert_eq!(text(&nes, AddressSpace::Cpu, 0xC042), Some("JMP (RESET_VECTOR)".to_string()));
+    assert_eq!(nes.disassemble(AddressSpace::Cpu, 0xC000).unwrap().label, Some("RESET".to_string()));
+    assert_eq!(nes.disassemble(AddressSpace::Cpu, 0xC030).unwrap().label, Some("NMI".to_string()));
+
+    // Reading PPUSTATUS would clear VBlank, and the operand is write-only anyway
+    let status = nes.peek(0x2002);
+    assert_eq!(nes.disassemble(AddressSpace::Cpu, 0x2002), None);
+    assert_eq!(nes.peek(0x2002), status);
+
+    // 16 KiB of PRG-ROM, mirrored at $8000 and $C000
+    let bank = AddressSpace::Prg { bank: 0, size: 0x4000, base: 0xC000 };
+    let total = nes.disassembly_len(bank);
+    assert_eq!(nes.disassembly_len(AddressSpace::Cpu), total * 2);
+    assert_eq!(nes.disassembly_index(AddressSpace::Cpu, 0xC000), total);
+    let index = nes.disassembly_index(bank, 0xC008);
+    assert_eq!(nes.disassembly_index(bank, 0xC009), index + 1);
+    assert_eq!(nes.disassembly_address(bank, index), Some(0xC008));
+    assert_eq!(nes.disassembly_address(bank, total), None);
+    assert_eq!(nes.disassemble(bank, 0xC008).unwrap().bytes, vec![0x20, 0x20, 0xC0]);
+    assert_eq!(nes.disassemble(bank, 0x8000), None);
+}
+
+#[test]
+fn symbols () {
+    let mut nes = program();
+    let text = |nes: &Emulator, address| nes.disassemble(AddressSpace::Cpu, address).unwrap().to_string();
+
+    assert_eq!(nes.load_symbols("program.nes.0.nl", "$C008#ReadJoypad#Calls the subroutine\n$C020#Subroutine#\n"), Ok(2));
+    assert_eq!(nes.load_symbols("program.nes.ram.nl", "$0300#Calls#\n$0301/2#Value#\n"), Ok(2));
+    assert_eq!(nes.load_symbols("program.mlb", "P:0030:Handler:NMI and IRQ\nR:0300:Counter\nG:2002:Status\n"), Ok(3));
+
+    assert_eq!(text(&nes, 0xC008), "JSR Subroutine");
+    assert_eq!(text(&nes, 0xC00B), "INC Calls");
+    assert_eq!(text(&nes, 0xC025), "LDA Status");
+    assert_eq!(nes.disassemble(AddressSpace::Cpu, 0xC030).unwrap().label, Some("Handler".to_string()));
+    // Same bank, mirrored
+    assert_eq!(nes.disassemble(AddressSpace::Cpu, 0x8008).unwrap().label, Some("ReadJoypad".to_string()));
+
+    let id = nes.add_breakpoint_at("ReadJoypad").unwrap();
+    assert_eq!(nes.breakpoints()[0].kind, BreakpointKind::Execute { address: 0xC008, bank: Some(0) });
+    assert_eq!(nes.run_until_break(), StopReason::Breakpoint { id, address: 0xC008 });
+    assert_eq!(nes.resolve_symbol("Handler"), Ok((0x8030, Some(0))));
+    assert_eq!(nes.resolve_symbol("Counter"), Ok((0x0300, None)));
+
+    assert_eq!(nes.add_breakpoint_at("Missing"), Err(SymbolError::UnknownSymbol("Missing".to_string())));
+    assert_eq!(nes.load_symbols("program.sym", ""), Err(SymbolError::UnknownFormat("program.sym".to_string())));
+    assert_eq!(nes.load_symbols("program.mlb", "P:0030:Fine\nP:zz:Broken"), Err(SymbolError::Syntax { line: 2, reason: "invalid address".to_string() }));
+    assert_eq!(nes.load_symbols("program.mlb", "S:1FFF:Last\nS:A000:Wrapped"), Err(SymbolError::Syntax { line: 2, reason: "address out of range".to_string() }));
+    assert_eq!(nes.load_symbols("program.mlb", "G:10000:Truncated"), Err(SymbolError::Syntax { line: 1, reason: "address out of range".to_string() }));
+    assert_eq!(nes.load_symbols("program.mlb", "R:07FF:Top\nR:0800:Mirror"), Err(SymbolError::Syntax { line: 2, reason: "address out of range".to_string() }));
+    assert!(nes.symbols().find("Fine").is_none());
+}
@@ -0,0 +1,127 @@
+use wasm_nes::{input::Button, Emulator, Movie, MovieError};
+
+const NESTEST: &[u8] = include_bytes!("roms/cpu/nestest/nestest.nes");
+
+/**
+ * Run nestest's menu: wait, select the second test page and start it
+ */
+fn play (nes: &mut Emulator) {
+    for frame in 0..120 {
+        match frame {
+            30 => nes.update_controller(0, Button::Down, true),
+            35 => nes.update_controller(0, Button::Down, false),
+            40 => nes.update_controller(0, Button::Start, true),
+            42 => nes.update_controller(0, Button::Start, false),
+            _ => {},
+        }
+        nes.cycle_until_frame();
+    }
+}
+
+fn record (nes: &mut Emulator, from_power_on: bool) -> (Movie, Vec<u8>) {
+    nes.movie_record(from_power_on);
+    play(nes);
+    let end = nes.save_state();
+
+    (nes.movie_stop().unwrap(), end)
+}
+
+fn replay (nes: &mut Emulator, movie: Movie) -> Vec<u8> {
+    let length = movie.length;
+
+    nes.movie_play(movie).unwrap();
+    for _ in 0..length {
+        nes.cycle_until_frame();
+    }
+
+    assert_eq!(nes.movie_desync(), None);
+    nes.save_state()
+}
+
+#[test]
+fn from_power_on () {
+    let mut nes = Emulator::new(NESTEST.to_vec(), 48_000.0).unwrap();
+    for _ in 0..50 {
+        nes.cycle_until_frame();
+    }
+
+    let (movie, end) = record(&mut nes, true);
+    assert_eq!((movie.start.is_none(), movie.length, movie.hashes.len()), (true, 120, 120));
+
+    // Input while playing is ignored, and the movie survives encoding
+    let mut other = Emulator::new(NESTEST.to_vec(), 48_000.0).unwrap();
+    other.update_controller(0, Button::Start, true);
+    let movie = Movie::decode(&movie.encode()).unwrap();
+    assert!(replay(&mut other, movie) == end, "Playback diverged from the recording");
+}
+
+#[test]
+fn from_state () {
+    let mut nes = Emulator::new(NESTEST.to_vec(), 48_000.0).unwrap();
+    for _ in 0..50 {
+        nes.cycle_until_frame();
+    }
+
+    let (movie, end) = record(&mut nes, false);
+    assert!(movie.start.is_some());
+    assert_eq!(movie.to_fm2(), Err(MovieError::NotFromPowerOn));
+
+    let mut other = Emulator::new(NESTEST.to_vec(), 48_000.0).unwrap();
+    assert!(replay(&mut other, movie) == end, "Playback diverged from the recording");
+}
+
+#[test]
+fn sram () {
+    // Test ROMs report their status in PRG-RAM, flag it as battery-backed
+    let mut rom = include_bytes!("roms/mapper/mmc3_test/1-clocking.nes").to_vec();
+    rom[6] |= 0b0000_0010;
+
+    // Game saves restored by the host survive power cycling, and the movie starts from them
+    let mut nes = Emulator::new(rom.clone(), 48_000.0).unwrap();
+    nes.set_sram(&[0x42; 0x2000]).unwrap();
+    let (movie, end) = record(&mut nes, true);
+    assert!(movie.sram == Some(vec![0x42; 0x2000]), "Recording didn't start from the game saves");
+
+    let mut other = Emulator::new(rom, 48_000.0).unwrap();
+    let movie = Movie::decode(&movie.encode()).unwrap();
+    assert!(replay(&mut other, movie.clone()) == end, "Playback diverged from the recording");
+
+    let mut nestest = Emulator::new(NESTEST.to_vec(), 48_000.0).unwrap();
+    let mut mismatch = movie;
+    mismatch.checksum = None;
+    assert!(matches!(nestest.movie_play(mismatch), Err(MovieError::InvalidFormat(_))));
+}
+
+#[test]
+fn fm2 () {
+    let mut nes = Emulator::new(NESTEST.to_vec(), 48_000.0).unwrap();
+    let (movie, end) = record(&mut nes, true);
+
+    let imported = Movie::from_fm2(&movie.to_fm2().unwrap()).unwrap();
+    assert_eq!((imported.checksum, imported.hashes.len()), (None, 0));
+    assert!(replay(&mut nes, imported) == end, "Playback diverged from the recording");
+}
+
+#[test]
+fn desync () {
+    let mut nes = Emulator::new(NESTEST.to_vec(), 48_000.0).unwrap();
+    let (mut movie, _) = record(&mut nes, true);
+    movie.hashes[60] ^= 1;
+
+    nes.movie_play(movie).unwrap();
+    for _ in 0..120 {
+        nes.cycle_until_frame();
+    }
+    assert_eq!((nes.movie_frame(), nes.movie_desync()), (Some(120), Some(60)));
+}
+
+#[test]
+fn errors () {
+    let mut nes = Emulator::new(NESTEST.to_vec(), 48_000.0).unwrap();
+    let mut other = Emulator::new(include_bytes!("roms/mapper/mmc3_test/3-A12_clocking.nes").to_vec(), 48_000.0).unwrap();
+    let (movie, _) = record(&mut nes, true);
+
+    assert_eq!(other.movie_play(movie.clone()), Err(MovieError::RomMismatch));
+    assert_eq!(Movie::decode(b"NESM\xFF\x00"), Err(MovieError::UnsupportedVersion(0x00FF)));
+    assert!(matches!(Movie::decode(b"NES\x1A"), Err(MovieError::InvalidFormat(_))));
+}
@@ -0,0 +1,51 @@
+use wasm_nes::{
+    Emulator,
+    bus::Bus,
+    clock::Region,
+};
+
+const ROM: &[u8] = include_bytes!("roms/cpu/nestest/nestest.nes");
+
+/**
+ * PPU clocks per frame, and the scanline vblank starts on, with rendering disabled
+ */
+fn frame_timing (region: Region) -> (usize, u16) {
+    let mut bus = Bus::new(ROM, 48_000.0, Some(region)).unwrap();
+    let mut vblank = None;
+
+    // Align on the start of a frame
+    while bus.ppu.frame == 0 {
+        bus.ppu.cycle(&mut bus.cartridge);
+    }
+
+    let start = bus.ppu.cycles;
+
+    while bus.ppu.frame == 1 {
+        if vblank.is_none() && bus.ppu.status & 0b1000_0000 > 0 {
+            vblank = Some(bus.ppu.scanline);
+        }
+        bus.ppu.cycle(&mut bus.cartridge);
+    }
+
+    (bus.ppu.cycles - start, vblank.unwrap())
+}
+
+#[test]
+fn frames () {
+    assert_eq!(frame_timing(Region::NTSC), (341 * 262, 241));
+    assert_eq!(frame_timing(Region::PAL), (341 * 312, 241));
+    assert_eq!(frame_timing(Region::Dendy), (341 * 312, 291));
+}
+
+#[test]
+fn header () {
+    let mut rom = ROM.to_vec();
+
+    assert_eq!(Emulator::new(rom.clone(), 48_000.0).unwrap().region(), Region::NTSC);
+    assert_eq!(Emulator::with_region(rom.clone(), 48_000.0, Region::Dendy).unwrap().region(), Region::Dendy);
+
+    // NES 2.0 PAL
+    rom[7] = 0b0000_1000;
+    rom[12] = 0x01;
+    assert_eq!(Emulator::new(rom, 48_000.0).unwrap().region(), Region::PAL);
+}
@@ -0,0 +1,77 @@
+use wasm_nes::{Emulator, StateError};
+
+fn run (nes: &mut Emulator, frames: usize) {
+    for _ in 0..frames {
+        nes.cycle_until_frame();
+    }
+}
+
+#[test]
+fn round_trip () {
+    let mut nes = Emulator::new(include_bytes!("roms/mapper/mmc3_test/3-A12_clocking.nes").to_vec(), 48_000.0).unwrap();
+
+    run(&mut nes, 10);
+    let state = nes.save_state();
+    run(&mut nes, 10);
+    let expected = nes.save_state();
+
+    nes.load_state(&state).unwrap();
+    run(&mut nes, 10);
+
+    assert!(nes.save_state() == expected, "Machine diverged after restoring state");
+}
+
+#[test]
+fn errors () {
+    let mut nes = Emulator::new(include_bytes!("roms/mapper/mmc3_test/3-A12_clocking.nes").to_vec(), 48_000.0).unwrap();
+    let other = Emulator::new(include_bytes!("roms/cpu/nestest/nestest.nes").to_vec(), 48_000.0).unwrap();
+    let mut state = nes.save_state();
+
+    assert_eq!(nes.load_state(&state[..4]), Err(StateError::InvalidMagic));
+    assert_eq!(nes.load_state(&other.save_state()), Err(StateError::RomMismatch));
+    assert!(matches!(nes.load_state(&state[..state.len() - 1]), Err(StateError::Corrupted(_))));
+
+    state[4] = 0xFF;
+    assert_eq!(nes.load_state(&state), Err(StateError::UnsupportedVersion(0x00FF)));
+}
+
+#[test]
+fn sram () {
+    // Test ROMs report their status in PRG-RAM, flag it as battery-backed
+    let mut rom = include_bytes!("roms/mapper/mmc3_test/1-clocking.nes").to_vec();
+    rom[6] |= 0b0000_0010;
+
+    let mut nes = Emulator::new(rom, 48_000.0).unwrap();
+    let mut other = Emulator::new(include_bytes!("roms/cpu/nestest/nestest.nes").to_vec(), 48_000.0).unwrap();
+
+    assert_eq!(nes.sram_writes(), 0);
+    run(&mut nes, 10);
+    assert!(nes.sram_writes() > 0, "Game didn't write to battery-backed RAM");
+
+    let sram = nes.get_sram().unwrap();
+    assert_eq!(sram.len(), 0x2000);
+    assert_eq!(nes.set_sram(&sram[1..]), Err(StateError::SramSize { expected: 0x2000, actual: 0x1FFF }));
+    nes.set_sram(&vec![0x42; 0x2000]).unwrap();
+    assert_eq!(nes.get_sram().unwrap(), vec![0x42; 0x2000]);
+
+    assert_eq!(other.get_sram(), None);
+    assert_eq!(other.set_sram(&sram), Err(StateError::NoBattery));
+}
+
+#[test]
+fn rewind () {
+    let mut nes = Emulator::new(include_bytes!("roms/mapper/mmc3_test/3-A12_clocking.nes").to_vec(), 48_000.0).unwrap();
+
+    nes.set_rewind(5, 4);
+    run(&mut nes, 15);
+    let expected = nes.save_state();
+    run(&mut nes, 15);
+    assert_eq!(nes.rewind_len(), 4);
+
+    // Snapshots taken at frames 30, 25, 20 and 15, older ones were dropped
+    for _ in 0..4 {
+        assert!(nes.rewind_step());
+    }
+    assert!(!nes.rewind_step());
+    assert!(nes.save_state() == expected, "Rewinding didn't restore the machine state");
+}
@@ -45,22 +45,29 @@ class DebugCartridge extends Memoizable {
     }
 }
 
+// The CPU bus when no PRG-ROM bank is given
+type AddressSpace = { Prg: { bank: number, size: number, base: number } } | undefined;
+
 class DebugDisassembly extends Memoizable {
     constructor (private vm: Emulator) {
         super();
-        this.memoize('total', () => vm.debug_disassembly_total());
+        this.memoize('total', () => vm.debug_disassembly_total(undefined));
+    }
+
+    at (address, space?: AddressSpace) {
+        return this.vm.debug_disassembly_at(address, space);
     }
 
-    at (address) {
-        return this.vm.debug_disassembly_at(address);
+    address_to_index (address, space?: AddressSpace) {
+        return this.vm.debug_disassembly_address_to_index(address, space);
     }
 
-    address_to_index (address) {
-        return this.vm.debug_disassembly_address_to_index(address);
+    index_to_address (index, space?: AddressSpace) {
+        return this.vm.debug_disassembly_index_to_address(index, space);
     }
 
-    index_to_address (index) {
-        return this.vm.debug_disassembly_index_to_address(index);
+    total_in (space: AddressSpace) {
+        return this.vm.debug_disassembly_total(space);
     }
 }
 
@@ -77,6 +77,51 @@ export class Nes {
         this.#vm.reset();
     }
 
+    saveState (): Uint8Array {
+        return this.#vm.save_state();
+    }
+
+    loadState (state: Uint8Array) {
+        this.#vm.load_state(state);
+        this.render();
+    }
+
+    rewind () {
+        if (this.#vm.rewind_step()) {
+            this.render();
+        }
+    }
+
+    recordMovie (fromPowerOn: boolean) {
+        this.#vm.movie_record(fromPowerOn);
+        this.render();
+    }
+
+    playMovie (movie: Uint8Array) {
+        this.#vm.movie_play(movie);
+        this.render();
+    }
+
+    stopMovie (): Uint8Array | undefined {
+        return this.#vm.movie_stop();
+    }
+
+    get movieDesync (): number | undefined {
+        return this.#vm.movie_desync();
+    }
+
+    get sram (): Uint8Array | undefined {
+        return this.#vm.get_sram();
+    }
+
+    set sram (sram: Uint8Array) {
+        this.#vm.set_sram(sram);
+    }
+
+    get sramWrites (): number {
+        return this.#vm.sram_writes();
+    }
+
     private cycle (fn) {
         try {
             // this.vm.update_controllers(this.inputs);
@@ -133,5 +178,6 @@ export {
     PpuCtrlFlag,
     PpuMaskFlag,
     PpuStatusFlag,
+    Region,
     SpriteAttribute,
 } from '../backend/pkg';